@@ -1,5 +1,5 @@
 use bethlehem::*;
-use sdl3::event::Event;
+use sdl3::event::{Event, WindowEvent};
 use std::borrow::Cow;
 
 fn main() {
@@ -50,6 +50,7 @@ fn main() {
             },
             kind: ShaderKind::Mesh,
             entry_point: "ms_main".into(),
+            reflect: true,
         })
         .unwrap();
 
@@ -62,11 +63,64 @@ fn main() {
             },
             kind: ShaderKind::Fragment,
             entry_point: "fs_main".into(),
+            reflect: true,
         })
         .unwrap();
 
     println!("Compiled shaders");
 
+    let pipeline = device
+        .create_mesh_pipeline(&MeshPipelineDesc {
+            name: "Triangle pipeline".into(),
+            binding_layout: None,
+            task_shader: None,
+            mesh_shader: &mesh_shader,
+            fragment_shader: Some(&fragment_shader),
+            rasterizer: RasterizerState::default(),
+            depth_stencil: DepthStencilState::default(),
+            blend: BlendState::default(),
+            render_target_formats: RenderTargetFormats {
+                color_formats: vec![Format::B8G8R8A8Srgb],
+                ..Default::default()
+            },
+        })
+        .unwrap();
+
+    println!("Created pipeline");
+
+    let (width, height) = window.size_in_pixels();
+
+    let swapchain = device
+        .create_swapchain(&SwapchainDesc {
+            window_handle: WindowHandle::Sdl(&window),
+            width,
+            height,
+            format: Format::B8G8R8A8Srgb,
+            color_space: ColorSpace::SrgbNonLinear,
+            present_mode: PresentMode::Fifo,
+            image_count: 3,
+        })
+        .unwrap();
+
+    println!(
+        "Created swapchain: {}x{} {:?} {:?} {:?} ({} images)",
+        swapchain.width(),
+        swapchain.height(),
+        swapchain.format(),
+        swapchain.color_space(),
+        swapchain.present_mode(),
+        swapchain.image_count()
+    );
+
+    let mut frames_in_flight = FramesInFlight::new(
+        &device,
+        &FramesInFlightDesc {
+            frame_count: 2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
     //Game loop
 
     let mut event_pump = sdl.event_pump().unwrap();
@@ -79,9 +133,58 @@ fn main() {
                 Event::Quit { .. } => {
                     running = false;
                 }
+                Event::Window {
+                    win_event: WindowEvent::PixelSizeChanged(width, height),
+                    ..
+                } => {
+                    swapchain.resize(width as u32, height as u32);
+                }
                 _ => {}
             }
         }
+
+        let Some(swapchain_image) = swapchain.acquire_next_image().unwrap() else {
+            continue;
+        };
+
+        if swapchain_image.resized {
+            println!(
+                "Swapchain resized: {}x{}",
+                swapchain_image.width, swapchain_image.height
+            );
+        }
+
+        let swapchain_texture = &swapchain_image.texture;
+
+        let frame = frames_in_flight.begin_frame().unwrap();
+        let command_list = &mut frame.command_list;
+
+        command_list.begin_render_pass(&RenderPassDesc {
+            color_attachments: &[ColorAttachment {
+                texture: swapchain_texture,
+                resolve_target: None,
+                load_op: LoadOp::Clear,
+                store_op: StoreOp::Store,
+                clear_value: [0.1, 0.1, 0.1, 1.0],
+            }],
+            depth_stencil_attachment: None,
+            render_area: Rect {
+                x: 0,
+                y: 0,
+                width: swapchain_image.width,
+                height: swapchain_image.height,
+            },
+        });
+
+        command_list.bind_pipeline(&pipeline).unwrap();
+        command_list.draw_mesh_tasks(1, 1, 1).unwrap();
+
+        command_list.end_render_pass();
+
+        command_list.transition_texture(swapchain_texture, None, ResourceState::Present);
+
+        frames_in_flight.end_frame().unwrap();
+        direct_queue.present(&swapchain).unwrap();
     }
 }
 
@@ -0,0 +1,116 @@
+use bethlehem::*;
+use std::path::Path;
+
+fn main() {
+    let harness = GoldenImageHarness::new(
+        BackendType::Vulkan,
+        std::env::var("BETHLEHEM_PHYSICAL_DEVICE").ok().as_deref(),
+        std::env::var_os("BETHLEHEM_DEBUG").is_some(),
+    )
+    .unwrap();
+
+    let device = harness.device();
+
+    let mesh_shader = device
+        .create_shader_module(&ShaderDesc {
+            name: "Mesh shader".into(),
+            source: ShaderSource::Hlsl {
+                source: MESH_SHADER.into(),
+                defines: vec![],
+            },
+            kind: ShaderKind::Mesh,
+            entry_point: "ms_main".into(),
+            reflect: true,
+        })
+        .unwrap();
+
+    let fragment_shader = device
+        .create_shader_module(&ShaderDesc {
+            name: "Fragment shader".into(),
+            source: ShaderSource::Hlsl {
+                source: FRAGMENT_SHADER.into(),
+                defines: vec![],
+            },
+            kind: ShaderKind::Fragment,
+            entry_point: "fs_main".into(),
+            reflect: true,
+        })
+        .unwrap();
+
+    let pipeline = device
+        .create_mesh_pipeline(&MeshPipelineDesc {
+            name: "Triangle pipeline".into(),
+            binding_layout: None,
+            task_shader: None,
+            mesh_shader: &mesh_shader,
+            fragment_shader: Some(&fragment_shader),
+            rasterizer: RasterizerState::default(),
+            depth_stencil: DepthStencilState::default(),
+            blend: BlendState::default(),
+            render_target_formats: RenderTargetFormats {
+                color_formats: vec![Format::R8G8B8A8Unorm],
+                ..Default::default()
+            },
+        })
+        .unwrap();
+
+    let comparison = harness
+        .check(
+            &GoldenImageDesc {
+                name: "mesh_triangle".into(),
+                width: 256,
+                height: 256,
+                format: Format::R8G8B8A8Unorm,
+                clear_value: [0.1, 0.1, 0.1, 1.0],
+                reference_dir: Path::new("examples/golden"),
+                output_dir: Path::new("target/golden"),
+                tolerance: 2,
+                max_differing_pixels: 16,
+                update_reference: std::env::var_os("BETHLEHEM_UPDATE_GOLDEN").is_some(),
+            },
+            |command_list, _| {
+                command_list.bind_pipeline(&pipeline).unwrap();
+                command_list.draw_mesh_tasks(1, 1, 1).unwrap();
+            },
+        )
+        .unwrap();
+
+    println!(
+        "Golden image matched: {} pixels differ, max difference {}",
+        comparison.differing_pixels, comparison.max_difference
+    );
+}
+
+static MESH_SHADER: &'static str = r#"
+struct MSOutput {
+    float4 Position: SV_Position;
+    float3 Color: COLOR0;
+};
+
+[NumThreads(1, 1, 1)]
+[OutputTopology("triangle")]
+void ms_main(uint gtid: SV_GroupThreadID, uint gid: SV_GroupID, out indices uint3 triangles[124], out vertices MSOutput vertices[64]) {
+    SetMeshOutputCounts(3, 1);
+    triangles[0] = uint3(0, 1, 2);
+
+    vertices[0].Position = float4(-0.5, 0.5, 0.0, 1.0);
+    vertices[0].Color = float3(1.0, 0.0, 0.0);
+
+    vertices[1].Position = float4(0.5, 0.5, 0.0, 1.0);
+    vertices[1].Color = float3(0.0, 1.0, 0.0);
+
+    vertices[2].Position = float4(0.0, -0.5, 0.0, 1.0);
+    vertices[2].Color = float3(0.0, 0.0, 1.0);
+}
+"#;
+
+static FRAGMENT_SHADER: &'static str = r#"
+struct MSOutput {
+    float4 Position: SV_Position;
+    float3 Color: COLOR0;
+};
+
+float4 fs_main(MSOutput input) : SV_Target0 {
+    return float4(input.Color, 1.0);
+}
+"#;
@@ -0,0 +1,118 @@
+use bethlehem::*;
+use std::borrow::Cow;
+
+const FRAME_COUNT: u32 = 120;
+
+fn main() {
+    let instance = Instance::new(&InstanceDesc {
+        backend_type: BackendType::Vulkan,
+        debug: true,
+        engine_name: Cow::Borrowed("Test Engine"),
+        application_name: Cow::Borrowed("Headless Test"),
+        window_handle: WindowHandle::Headless,
+    })
+    .unwrap();
+
+    println!("Instance created: {:?}", instance.backend());
+
+    let physical_devices = instance.get_physical_devices().unwrap();
+
+    for physical_device in &physical_devices {
+        println!("Found physical device: {}", physical_device.name());
+    }
+
+    let device = instance
+        .create_device(&DeviceDesc {
+            physical_device: physical_devices.first().unwrap(),
+        })
+        .unwrap();
+
+    let direct_queue = device.get_direct_queue();
+
+    let swapchain = device
+        .create_swapchain(&SwapchainDesc {
+            window_handle: WindowHandle::Headless,
+            width: 640,
+            height: 480,
+            format: Format::B8G8R8A8Srgb,
+            color_space: ColorSpace::SrgbNonLinear,
+            present_mode: PresentMode::Fifo,
+            image_count: 3,
+        })
+        .unwrap();
+
+    println!(
+        "Created headless swapchain: {}x{} {:?} {:?} ({} images)",
+        swapchain.width(),
+        swapchain.height(),
+        swapchain.format(),
+        swapchain.present_mode(),
+        swapchain.image_count()
+    );
+
+    let mut frames_in_flight =
+        FramesInFlight::new(&device, &FramesInFlightDesc::default()).unwrap();
+
+    for frame_number in 0..FRAME_COUNT {
+        if frame_number == FRAME_COUNT / 2 {
+            swapchain.resize(800, 600);
+        }
+
+        let swapchain_image = swapchain
+            .acquire_next_image()
+            .unwrap()
+            .expect("Headless swapchains are never minimized");
+
+        if swapchain_image.resized {
+            println!(
+                "Swapchain resized: {}x{}",
+                swapchain_image.width, swapchain_image.height
+            );
+        }
+
+        let swapchain_texture = &swapchain_image.texture;
+
+        let frame = frames_in_flight.begin_frame().unwrap();
+        let command_list = &mut frame.command_list;
+
+        let t = frame_number as f32 / FRAME_COUNT as f32;
+
+        command_list.begin_render_pass(&RenderPassDesc {
+            color_attachments: &[ColorAttachment {
+                texture: swapchain_texture,
+                resolve_target: None,
+                load_op: LoadOp::Clear,
+                store_op: StoreOp::Store,
+                clear_value: [t, 0.1, 1.0 - t, 1.0],
+            }],
+            depth_stencil_attachment: None,
+            render_area: Rect {
+                x: 0,
+                y: 0,
+                width: swapchain_image.width,
+                height: swapchain_image.height,
+            },
+        });
+
+        command_list.end_render_pass();
+
+        command_list.transition_texture(swapchain_texture, None, ResourceState::Present);
+
+        frames_in_flight.end_frame().unwrap();
+
+        if frame_number == FRAME_COUNT - 1 {
+            capture_swapchain_image(&device, &direct_queue, &swapchain_image)
+                .unwrap()
+                .save_png("headless.png")
+                .unwrap();
+
+            println!("Saved headless.png");
+        }
+
+        direct_queue.present(&swapchain).unwrap();
+    }
+
+    frames_in_flight.wait_idle().unwrap();
+
+    println!("Presented {FRAME_COUNT} frames");
+}
@@ -0,0 +1,25 @@
+use crate::{BindingLayoutDesc, Error};
+
+pub(crate) const MAX_PUSH_CONSTANTS_SIZE: u32 = 4096;
+
+pub struct MetalBindingLayout {
+    desc: BindingLayoutDesc,
+}
+
+impl MetalBindingLayout {
+    pub fn new(desc: &BindingLayoutDesc) -> Result<Self, Error> {
+        if desc.push_constants.is_some_and(|push_constants| {
+            push_constants.size > MAX_PUSH_CONSTANTS_SIZE || push_constants.size % 4 != 0
+        }) {
+            return Err(Error::Unsupported(
+                "Push constant size must be a multiple of 4 and within the device limit",
+            ));
+        }
+
+        Ok(Self { desc: desc.clone() })
+    }
+
+    pub fn desc(&self) -> &BindingLayoutDesc {
+        &self.desc
+    }
+}
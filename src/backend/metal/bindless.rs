@@ -0,0 +1,131 @@
+use crate::bindless::DescriptorIndexAllocator;
+use crate::{BindlessResource, DescriptorIndex, Error};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{MTLBuffer, MTLDevice, MTLResourceOptions, MTLSamplerState, MTLTexture};
+use std::sync::Mutex;
+
+const RESOURCE_CAPACITY: u32 = 1 << 16;
+const SAMPLER_CAPACITY: u32 = 2048;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct IRDescriptorTableEntry {
+    gpu_va: u64,
+    texture_view_id: u64,
+    metadata: u64,
+}
+
+pub(crate) struct MetalBindlessHeap {
+    pub(crate) resource_heap: Retained<ProtocolObject<dyn MTLBuffer>>,
+    pub(crate) sampler_heap: Retained<ProtocolObject<dyn MTLBuffer>>,
+
+    resources: Mutex<DescriptorIndexAllocator>,
+    samplers: Mutex<DescriptorIndexAllocator>,
+}
+
+impl MetalBindlessHeap {
+    pub(crate) fn new(mtl_device: &ProtocolObject<dyn MTLDevice>) -> Result<Self, Error> {
+        let new_heap = |capacity: u32| {
+            mtl_device
+                .newBufferWithLength_options(
+                    capacity as usize * size_of::<IRDescriptorTableEntry>(),
+                    MTLResourceOptions::StorageModeShared,
+                )
+                .ok_or(Error::MetalError(
+                    "Failed to create descriptor heap".to_owned(),
+                ))
+        };
+
+        Ok(Self {
+            resource_heap: new_heap(RESOURCE_CAPACITY)?,
+            sampler_heap: new_heap(SAMPLER_CAPACITY)?,
+            resources: Mutex::new(DescriptorIndexAllocator::new(RESOURCE_CAPACITY)),
+            samplers: Mutex::new(DescriptorIndexAllocator::new(SAMPLER_CAPACITY)),
+        })
+    }
+
+    pub(crate) fn allocate_resource(
+        &self,
+        resource: BindlessResource,
+        completed_values: [u64; 3],
+    ) -> Result<DescriptorIndex, Error> {
+        let index = self
+            .resources
+            .lock()
+            .unwrap()
+            .allocate(completed_values)
+            .ok_or(Error::Unsupported("Bindless resource heap is full"))?;
+
+        let entry = match resource {
+            BindlessResource::UniformBuffer {
+                buffer,
+                offset,
+                size,
+            }
+            | BindlessResource::StorageBuffer {
+                buffer,
+                offset,
+                size,
+            } => IRDescriptorTableEntry {
+                gpu_va: buffer.as_metal_buffer().buffer.gpuAddress() + offset,
+                texture_view_id: 0,
+                metadata: size,
+            },
+            BindlessResource::SampledTexture(texture)
+            | BindlessResource::StorageTexture(texture) => IRDescriptorTableEntry {
+                gpu_va: 0,
+                texture_view_id: texture.as_metal_texture().texture.gpuResourceID().to_raw(),
+                metadata: 0,
+            },
+        };
+
+        unsafe { write_entry(&self.resource_heap, index, entry) };
+
+        Ok(index)
+    }
+
+    pub(crate) fn allocate_sampler(
+        &self,
+        sampler_state: &ProtocolObject<dyn MTLSamplerState>,
+        lod_bias: f32,
+        completed_values: [u64; 3],
+    ) -> Result<DescriptorIndex, Error> {
+        let index = self
+            .samplers
+            .lock()
+            .unwrap()
+            .allocate(completed_values)
+            .ok_or(Error::Unsupported("Bindless sampler heap is full"))?;
+
+        let entry = IRDescriptorTableEntry {
+            gpu_va: sampler_state.gpuResourceID().to_raw(),
+            texture_view_id: 0,
+            metadata: lod_bias.to_bits() as u64,
+        };
+
+        unsafe { write_entry(&self.sampler_heap, index, entry) };
+
+        Ok(index)
+    }
+
+    pub(crate) fn free_resource(&self, index: DescriptorIndex, submitted_values: [u64; 3]) {
+        self.resources.lock().unwrap().free(index, submitted_values);
+    }
+
+    pub(crate) fn free_sampler(&self, index: DescriptorIndex, submitted_values: [u64; 3]) {
+        self.samplers.lock().unwrap().free(index, submitted_values);
+    }
+}
+
+unsafe fn write_entry(
+    heap: &ProtocolObject<dyn MTLBuffer>,
+    index: DescriptorIndex,
+    entry: IRDescriptorTableEntry,
+) {
+    unsafe {
+        (heap.contents().as_ptr() as *mut IRDescriptorTableEntry)
+            .add(index.index() as usize)
+            .write(entry)
+    };
+}
@@ -0,0 +1,80 @@
+use crate::backend::metal::{MetalDevice, MetalGarbage};
+use crate::{BufferUsage, Error};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{MTLBuffer, MTLResidencySet};
+use std::sync::Arc;
+
+pub struct MetalBuffer {
+    device: Arc<MetalDevice>,
+
+    pub(crate) buffer: Retained<ProtocolObject<dyn MTLBuffer>>,
+
+    size: u64,
+    usage: BufferUsage,
+    host_visible: bool,
+}
+
+impl MetalBuffer {
+    pub fn new(
+        device: Arc<MetalDevice>,
+        buffer: Retained<ProtocolObject<dyn MTLBuffer>>,
+        size: u64,
+        usage: BufferUsage,
+        host_visible: bool,
+    ) -> Result<Self, Error> {
+        device
+            .residency_set
+            .addAllocation(ProtocolObject::from_ref(&*buffer));
+        device.residency_set.commit();
+
+        Ok(Self {
+            device,
+            buffer,
+            size,
+            usage,
+            host_visible,
+        })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn device_address(&self) -> Result<u64, Error> {
+        if !self.usage.contains(BufferUsage::DEVICE_ADDRESS) {
+            return Err(Error::Unsupported(
+                "Buffer was not created with BufferUsage::DEVICE_ADDRESS",
+            ));
+        }
+
+        Ok(self.buffer.gpuAddress())
+    }
+
+    pub fn mapped_slice(&self) -> Option<&[u8]> {
+        self.host_visible.then(|| unsafe {
+            std::slice::from_raw_parts(
+                self.buffer.contents().as_ptr() as *const u8,
+                self.size as usize,
+            )
+        })
+    }
+
+    pub fn mapped_slice_mut(&mut self) -> Option<&mut [u8]> {
+        self.host_visible.then(|| unsafe {
+            std::slice::from_raw_parts_mut(
+                self.buffer.contents().as_ptr() as *mut u8,
+                self.size as usize,
+            )
+        })
+    }
+}
+
+impl Drop for MetalBuffer {
+    fn drop(&mut self) {
+        self.device
+            .destroy_deferred(MetalGarbage::Allocation(ProtocolObject::from_retained(
+                self.buffer.clone(),
+            )));
+    }
+}
@@ -0,0 +1,599 @@
+use crate::backend::metal::*;
+use crate::{
+    BufferBarrier, Error, IndexType, QueueType, Rect, RenderPassDesc, ResourceState,
+    TextureBarrier, TextureSubresourceRange, Viewport,
+};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{
+    MTL4ArgumentTable, MTL4ArgumentTableDescriptor, MTL4CommandAllocator, MTL4CommandBuffer,
+    MTL4CommandEncoder, MTL4ComputeCommandEncoder, MTL4RenderCommandEncoder,
+    MTL4RenderPassDescriptor, MTL4VisibilityOptions, MTLBuffer, MTLClearColor, MTLDevice,
+    MTLGPUAddress, MTLOrigin, MTLPrimitiveType, MTLRenderStages, MTLResidencySet,
+    MTLResourceOptions, MTLScissorRect, MTLSize, MTLStages, MTLViewport,
+};
+use std::sync::Arc;
+
+const MAX_VERTEX_BUFFER_BIND_COUNT: usize = 31;
+const PUSH_CONSTANT_BUFFER_SIZE: usize = 64 * 1024;
+const PUSH_CONSTANT_ALIGNMENT: usize = 256;
+
+pub struct MetalCommandList {
+    pub(crate) device: Arc<MetalDevice>,
+
+    queue_type: QueueType,
+
+    command_allocator: Retained<ProtocolObject<dyn MTL4CommandAllocator>>,
+    pub(crate) command_buffer: Retained<ProtocolObject<dyn MTL4CommandBuffer>>,
+
+    pub(crate) render_encoder: Option<Retained<ProtocolObject<dyn MTL4RenderCommandEncoder>>>,
+    pub(crate) vertex_argument_table: Retained<ProtocolObject<dyn MTL4ArgumentTable>>,
+
+    binding_layout: Option<Arc<MetalBindingLayout>>,
+    push_constant_buffer: Retained<ProtocolObject<dyn MTLBuffer>>,
+    push_constant_offset: usize,
+
+    primitive_type: MTLPrimitiveType,
+    index_buffer: Option<(MTLGPUAddress, usize, IndexType)>,
+
+    threads_per_object_threadgroup: MTLSize,
+    threads_per_mesh_threadgroup: MTLSize,
+
+    automatic_barriers: bool,
+    pending_barrier: bool,
+}
+
+impl MetalCommandList {
+    pub fn new(device: Arc<MetalDevice>, queue_type: QueueType) -> Result<Self, Error> {
+        let mtl_device = &device.physical_device.mtl_device;
+
+        let command_allocator = mtl_device.newCommandAllocator().ok_or(Error::MetalError(
+            "Failed to create command allocator".to_owned(),
+        ))?;
+
+        let command_buffer = mtl_device.newCommandBuffer().ok_or(Error::MetalError(
+            "Failed to create command buffer".to_owned(),
+        ))?;
+
+        let argument_table_descriptor = MTL4ArgumentTableDescriptor::new();
+        argument_table_descriptor.setMaxBufferBindCount(MAX_VERTEX_BUFFER_BIND_COUNT);
+
+        let vertex_argument_table =
+            mtl_device.newArgumentTableWithDescriptor_error(&argument_table_descriptor)?;
+
+        let push_constant_buffer = mtl_device
+            .newBufferWithLength_options(
+                PUSH_CONSTANT_BUFFER_SIZE,
+                MTLResourceOptions::StorageModeShared,
+            )
+            .ok_or(Error::MetalError(
+                "Failed to create push constant buffer".to_owned(),
+            ))?;
+
+        device
+            .residency_set
+            .addAllocation(ProtocolObject::from_ref(&*push_constant_buffer));
+        device.residency_set.commit();
+
+        unsafe {
+            vertex_argument_table.setAddress_atIndex(
+                device.bindless.resource_heap.gpuAddress(),
+                IR_DESCRIPTOR_HEAP_BIND_POINT,
+            );
+            vertex_argument_table.setAddress_atIndex(
+                device.bindless.sampler_heap.gpuAddress(),
+                IR_SAMPLER_HEAP_BIND_POINT,
+            );
+        }
+
+        Ok(Self {
+            device,
+            queue_type,
+            command_allocator,
+            command_buffer,
+            render_encoder: None,
+            vertex_argument_table,
+            binding_layout: None,
+            push_constant_buffer,
+            push_constant_offset: 0,
+            primitive_type: MTLPrimitiveType::Triangle,
+            index_buffer: None,
+            threads_per_object_threadgroup: MTLSize {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+            threads_per_mesh_threadgroup: MTLSize {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+            automatic_barriers: true,
+            pending_barrier: false,
+        })
+    }
+
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    pub fn begin(&mut self) -> Result<(), Error> {
+        self.binding_layout = None;
+        self.push_constant_offset = 0;
+
+        self.command_allocator.reset();
+        self.command_buffer
+            .beginCommandBufferWithAllocator(&self.command_allocator);
+        self.command_buffer
+            .useResidencySet(&self.device.residency_set);
+
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> Result<(), Error> {
+        if let Some(render_encoder) = self.render_encoder.take() {
+            render_encoder.endEncoding();
+        }
+
+        self.command_buffer.endCommandBuffer();
+
+        Ok(())
+    }
+
+    pub fn set_automatic_barriers(&mut self, enabled: bool) {
+        self.automatic_barriers = enabled;
+    }
+
+    pub fn transition_buffer(&mut self, _buffer: &MetalBuffer, _new_state: ResourceState) {
+        self.insert_barrier();
+    }
+
+    pub fn transition_texture(
+        &mut self,
+        _texture: &MetalTexture,
+        _range: Option<TextureSubresourceRange>,
+        _new_state: ResourceState,
+    ) {
+        self.insert_barrier();
+    }
+
+    pub fn barrier(
+        &mut self,
+        buffer_barriers: &[BufferBarrier],
+        texture_barriers: &[TextureBarrier],
+    ) {
+        if !buffer_barriers.is_empty() || !texture_barriers.is_empty() {
+            self.insert_barrier();
+        }
+    }
+
+    pub fn begin_render_pass(&mut self, desc: &RenderPassDesc) {
+        let descriptor = MTL4RenderPassDescriptor::new();
+
+        for (i, attachment) in desc.color_attachments.iter().enumerate() {
+            let color_attachment =
+                unsafe { descriptor.colorAttachments().objectAtIndexedSubscript(i) };
+
+            color_attachment.setTexture(Some(&attachment.texture.as_metal_texture().texture));
+            color_attachment.setLoadAction(mtl_load_action(attachment.load_op));
+            color_attachment.setStoreAction(mtl_store_action(
+                attachment.store_op,
+                attachment.resolve_target.is_some(),
+            ));
+            color_attachment.setClearColor(MTLClearColor {
+                red: attachment.clear_value[0] as f64,
+                green: attachment.clear_value[1] as f64,
+                blue: attachment.clear_value[2] as f64,
+                alpha: attachment.clear_value[3] as f64,
+            });
+
+            if let Some(resolve_target) = attachment.resolve_target {
+                color_attachment
+                    .setResolveTexture(Some(&resolve_target.as_metal_texture().texture));
+            }
+        }
+
+        if let Some(attachment) = &desc.depth_stencil_attachment {
+            let texture = &attachment.texture.as_metal_texture().texture;
+
+            let depth_attachment = descriptor.depthAttachment();
+            depth_attachment.setTexture(Some(texture));
+            depth_attachment.setLoadAction(mtl_load_action(attachment.depth_load_op));
+            depth_attachment.setStoreAction(mtl_store_action(attachment.depth_store_op, false));
+            depth_attachment.setClearDepth(attachment.clear_depth as f64);
+
+            if attachment.texture.desc().format.is_stencil() {
+                let stencil_attachment = descriptor.stencilAttachment();
+                stencil_attachment.setTexture(Some(texture));
+                stencil_attachment.setLoadAction(mtl_load_action(attachment.stencil_load_op));
+                stencil_attachment
+                    .setStoreAction(mtl_store_action(attachment.stencil_store_op, false));
+                stencil_attachment.setClearStencil(attachment.clear_stencil);
+            }
+        }
+
+        let render_area = desc.render_area;
+        descriptor.setRenderTargetWidth((render_area.x.max(0) as u32 + render_area.width) as usize);
+        descriptor
+            .setRenderTargetHeight((render_area.y.max(0) as u32 + render_area.height) as usize);
+
+        self.render_encoder = self
+            .command_buffer
+            .renderCommandEncoderWithDescriptor(&descriptor);
+
+        if let Some(render_encoder) = &self.render_encoder {
+            if self.automatic_barriers || self.pending_barrier {
+                render_encoder.barrierAfterQueueStages_beforeStages_visibilityOptions(
+                    MTLStages::All,
+                    render_stages(),
+                    MTL4VisibilityOptions::Device,
+                );
+                self.pending_barrier = false;
+            }
+
+            render_encoder
+                .setArgumentTable_atStages(&self.vertex_argument_table, argument_stages());
+        }
+
+        self.set_viewport(&Viewport {
+            x: render_area.x as f32,
+            y: render_area.y as f32,
+            width: render_area.width as f32,
+            height: render_area.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        });
+        self.set_scissor(&render_area);
+    }
+
+    pub fn end_render_pass(&mut self) {
+        if let Some(render_encoder) = self.render_encoder.take() {
+            render_encoder.endEncoding();
+        }
+    }
+
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        if let Some(render_encoder) = &self.render_encoder {
+            render_encoder.setViewport(MTLViewport {
+                originX: viewport.x as f64,
+                originY: viewport.y as f64,
+                width: viewport.width as f64,
+                height: viewport.height as f64,
+                znear: viewport.min_depth as f64,
+                zfar: viewport.max_depth as f64,
+            });
+        }
+    }
+
+    pub fn set_scissor(&mut self, scissor: &Rect) {
+        if let Some(render_encoder) = &self.render_encoder {
+            render_encoder.setScissorRect(MTLScissorRect {
+                x: scissor.x.max(0) as usize,
+                y: scissor.y.max(0) as usize,
+                width: scissor.width as usize,
+                height: scissor.height as usize,
+            });
+        }
+    }
+
+    pub fn bind_pipeline(&mut self, pipeline: &MetalPipeline) {
+        self.threads_per_object_threadgroup = pipeline.threads_per_object_threadgroup;
+        self.threads_per_mesh_threadgroup = pipeline.threads_per_mesh_threadgroup;
+        self.primitive_type = pipeline.primitive_type;
+
+        if let Some(render_encoder) = &self.render_encoder {
+            let rasterizer = &pipeline.rasterizer;
+
+            render_encoder.setRenderPipelineState(&pipeline.render_pipeline_state);
+            if let Some(depth_stencil_state) = &pipeline.depth_stencil_state {
+                render_encoder.setDepthStencilState(Some(depth_stencil_state));
+            }
+            render_encoder.setCullMode(mtl_cull_mode(rasterizer.cull_mode));
+            render_encoder.setFrontFacingWinding(mtl_winding(rasterizer.front_face));
+            render_encoder.setTriangleFillMode(mtl_triangle_fill_mode(rasterizer.polygon_mode));
+            render_encoder.setDepthBias_slopeScale_clamp(
+                rasterizer.depth_bias,
+                rasterizer.depth_bias_slope_scale,
+                rasterizer.depth_bias_clamp,
+            );
+        }
+
+        self.binding_layout = pipeline.binding_layout.clone();
+    }
+
+    pub fn set_push_constants(&mut self, data: &[u8]) -> Result<(), Error> {
+        let push_constants = self
+            .binding_layout
+            .as_ref()
+            .ok_or(Error::Unsupported(
+                "A pipeline with a binding layout must be bound before setting push constants",
+            ))?
+            .desc()
+            .push_constants
+            .ok_or(Error::Unsupported(
+                "The bound pipeline does not declare push constants",
+            ))?;
+
+        let max_size = push_constants.size.min(MAX_PUSH_CONSTANTS_SIZE);
+
+        if data.len() % 4 != 0 || data.len() > max_size as usize {
+            return Err(Error::InvalidPushConstantSize(data.len(), max_size));
+        }
+
+        let offset = self
+            .push_constant_offset
+            .next_multiple_of(PUSH_CONSTANT_ALIGNMENT);
+
+        if offset + data.len() > PUSH_CONSTANT_BUFFER_SIZE {
+            return Err(Error::Unsupported(
+                "Too many push constant updates in one command list",
+            ));
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                (self.push_constant_buffer.contents().as_ptr() as *mut u8).add(offset),
+                data.len(),
+            );
+
+            self.vertex_argument_table.setAddress_atIndex(
+                self.push_constant_buffer.gpuAddress() + offset as u64,
+                IR_ARGUMENT_BUFFER_BIND_POINT,
+            );
+        }
+
+        self.push_constant_offset = offset + data.len();
+
+        if let Some(render_encoder) = &self.render_encoder {
+            render_encoder
+                .setArgumentTable_atStages(&self.vertex_argument_table, argument_stages());
+        }
+
+        Ok(())
+    }
+
+    pub fn bind_vertex_buffers(&mut self, first_binding: u32, buffers: &[(&MetalBuffer, u64)]) {
+        for (i, (buffer, offset)) in buffers.iter().enumerate() {
+            unsafe {
+                self.vertex_argument_table.setAddress_atIndex(
+                    buffer.buffer.gpuAddress() + offset,
+                    IR_VERTEX_BUFFER_BIND_POINT + first_binding as usize + i,
+                )
+            };
+        }
+
+        if let Some(render_encoder) = &self.render_encoder {
+            render_encoder
+                .setArgumentTable_atStages(&self.vertex_argument_table, argument_stages());
+        }
+    }
+
+    pub fn bind_index_buffer(&mut self, buffer: &MetalBuffer, offset: u64, index_type: IndexType) {
+        self.index_buffer = Some((
+            buffer.buffer.gpuAddress() + offset,
+            (buffer.size() - offset) as usize,
+            index_type,
+        ));
+    }
+
+    pub fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        if let Some(render_encoder) = &self.render_encoder {
+            unsafe {
+                render_encoder.drawPrimitives_vertexStart_vertexCount_instanceCount_baseInstance(
+                    self.primitive_type,
+                    first_vertex as usize,
+                    vertex_count as usize,
+                    instance_count as usize,
+                    first_instance as usize,
+                )
+            };
+        }
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        if let (Some(render_encoder), Some((address, length, index_type))) =
+            (&self.render_encoder, self.index_buffer)
+        {
+            let index_offset = first_index as u64 * index_type.size();
+
+            unsafe {
+                render_encoder
+                    .drawIndexedPrimitives_indexCount_indexType_indexBuffer_indexBufferLength_instanceCount_baseVertex_baseInstance(
+                        self.primitive_type,
+                        index_count as usize,
+                        mtl_index_type(index_type),
+                        address + index_offset,
+                        length.saturating_sub(index_offset as usize),
+                        instance_count as usize,
+                        vertex_offset as isize,
+                        first_instance as usize,
+                    )
+            };
+        }
+    }
+
+    pub fn draw_indirect(
+        &mut self,
+        buffer: &MetalBuffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        if let Some(render_encoder) = &self.render_encoder {
+            let gpu_address = buffer.buffer.gpuAddress() + offset;
+
+            for i in 0..draw_count as u64 {
+                render_encoder.drawPrimitives_indirectBuffer(
+                    self.primitive_type,
+                    gpu_address + i * stride as u64,
+                );
+            }
+        }
+    }
+
+    pub fn draw_indexed_indirect(
+        &mut self,
+        buffer: &MetalBuffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        if let (Some(render_encoder), Some((address, length, index_type))) =
+            (&self.render_encoder, self.index_buffer)
+        {
+            let gpu_address = buffer.buffer.gpuAddress() + offset;
+
+            for i in 0..draw_count as u64 {
+                unsafe {
+                    render_encoder
+                        .drawIndexedPrimitives_indexType_indexBuffer_indexBufferLength_indirectBuffer(
+                            self.primitive_type,
+                            mtl_index_type(index_type),
+                            address,
+                            length,
+                            gpu_address + i * stride as u64,
+                        )
+                };
+            }
+        }
+    }
+
+    pub fn draw_mesh_tasks(&mut self, group_count_x: u32, group_count_y: u32, group_count_z: u32) {
+        if let Some(render_encoder) = &self.render_encoder {
+            render_encoder
+                .drawMeshThreadgroups_threadsPerObjectThreadgroup_threadsPerMeshThreadgroup(
+                    MTLSize {
+                        width: group_count_x as usize,
+                        height: group_count_y as usize,
+                        depth: group_count_z as usize,
+                    },
+                    self.threads_per_object_threadgroup,
+                    self.threads_per_mesh_threadgroup,
+                );
+        }
+    }
+
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &MetalBuffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        if let Some(render_encoder) = &self.render_encoder {
+            let gpu_address = buffer.buffer.gpuAddress() + offset;
+
+            for i in 0..draw_count as u64 {
+                render_encoder
+                    .drawMeshThreadgroupsWithIndirectBuffer_threadsPerObjectThreadgroup_threadsPerMeshThreadgroup(
+                        gpu_address + i * stride as u64,
+                        self.threads_per_object_threadgroup,
+                        self.threads_per_mesh_threadgroup,
+                    );
+            }
+        }
+    }
+
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        texture: &MetalTexture,
+        mip_level: u32,
+        array_layer: u32,
+        buffer: &MetalBuffer,
+        buffer_offset: u64,
+        bytes_per_row: u32,
+    ) {
+        let Some(compute_encoder) = self.command_buffer.computeCommandEncoder() else {
+            return;
+        };
+
+        if self.automatic_barriers || self.pending_barrier {
+            compute_encoder.barrierAfterQueueStages_beforeStages_visibilityOptions(
+                MTLStages::All,
+                MTLStages::Blit,
+                MTL4VisibilityOptions::Device,
+            );
+            self.pending_barrier = false;
+        }
+
+        let desc = texture.desc();
+
+        let height = (desc.height >> mip_level).max(1);
+
+        unsafe {
+            compute_encoder
+                .copyFromTexture_sourceSlice_sourceLevel_sourceOrigin_sourceSize_toBuffer_destinationOffset_destinationBytesPerRow_destinationBytesPerImage(
+                    &texture.texture,
+                    array_layer as usize,
+                    mip_level as usize,
+                    MTLOrigin { x: 0, y: 0, z: 0 },
+                    MTLSize {
+                        width: (desc.width >> mip_level).max(1) as usize,
+                        height: height as usize,
+                        depth: (desc.depth >> mip_level).max(1) as usize,
+                    },
+                    &buffer.buffer,
+                    buffer_offset as usize,
+                    bytes_per_row as usize,
+                    bytes_per_row as usize * height as usize,
+                )
+        };
+
+        compute_encoder.endEncoding();
+
+        self.pending_barrier = true;
+    }
+}
+
+impl MetalCommandList {
+    fn insert_barrier(&mut self) {
+        match &self.render_encoder {
+            Some(render_encoder) => render_encoder
+                .barrierAfterEncoderStages_beforeEncoderStages_visibilityOptions(
+                    render_stages(),
+                    render_stages(),
+                    MTL4VisibilityOptions::Device,
+                ),
+            None => self.pending_barrier = true,
+        }
+    }
+}
+
+impl Drop for MetalCommandList {
+    fn drop(&mut self) {
+        self.device
+            .destroy_deferred(MetalGarbage::Allocation(ProtocolObject::from_retained(
+                self.push_constant_buffer.clone(),
+            )));
+
+        self.device.destroy_deferred(MetalGarbage::Object(Box::new((
+            self.command_allocator.clone(),
+            self.command_buffer.clone(),
+        ))));
+    }
+}
+
+fn render_stages() -> MTLStages {
+    MTLStages::Vertex | MTLStages::Fragment | MTLStages::Object | MTLStages::Mesh
+}
+
+fn argument_stages() -> MTLRenderStages {
+    MTLRenderStages::Vertex
+        | MTLRenderStages::Fragment
+        | MTLRenderStages::Object
+        | MTLRenderStages::Mesh
+}
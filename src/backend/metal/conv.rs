@@ -0,0 +1,277 @@
+use crate::{
+    AddressMode, BlendFactor, BlendOp, BorderColor, ColorWriteMask, CompareOp, CullMode, Filter,
+    Format, FrontFace, IndexType, LoadOp, MemoryLocation, PolygonMode, PrimitiveTopology,
+    StencilOp, StoreOp, TextureDimension, TextureUsage, VertexStepMode,
+};
+use objc2_metal::{
+    MTLBlendFactor, MTLBlendOperation, MTLColorWriteMask, MTLCompareFunction, MTLCullMode,
+    MTLIndexType, MTLLoadAction, MTLPixelFormat, MTLPrimitiveTopologyClass, MTLPrimitiveType,
+    MTLResourceOptions, MTLSamplerAddressMode, MTLSamplerBorderColor, MTLSamplerMinMagFilter,
+    MTLSamplerMipFilter, MTLStencilOperation, MTLStoreAction, MTLTextureType, MTLTextureUsage,
+    MTLTriangleFillMode, MTLVertexFormat, MTLVertexStepFunction, MTLWinding,
+};
+
+pub(crate) fn mtl_pixel_format(format: Format) -> MTLPixelFormat {
+    match format {
+        Format::R8Unorm => MTLPixelFormat::R8Unorm,
+        Format::R8G8Unorm => MTLPixelFormat::RG8Unorm,
+        Format::R8G8B8A8Unorm => MTLPixelFormat::RGBA8Unorm,
+        Format::R8G8B8A8Srgb => MTLPixelFormat::RGBA8Unorm_sRGB,
+        Format::B8G8R8A8Unorm => MTLPixelFormat::BGRA8Unorm,
+        Format::B8G8R8A8Srgb => MTLPixelFormat::BGRA8Unorm_sRGB,
+        Format::A2B10G10R10Unorm => MTLPixelFormat::RGB10A2Unorm,
+        Format::R16Float => MTLPixelFormat::R16Float,
+        Format::R16G16Float => MTLPixelFormat::RG16Float,
+        Format::R16G16B16A16Float => MTLPixelFormat::RGBA16Float,
+        Format::R32Uint => MTLPixelFormat::R32Uint,
+        Format::R32Float => MTLPixelFormat::R32Float,
+        Format::R32G32Float => MTLPixelFormat::RG32Float,
+        Format::R32G32B32Float => MTLPixelFormat::Invalid,
+        Format::R32G32B32A32Float => MTLPixelFormat::RGBA32Float,
+        Format::D16Unorm => MTLPixelFormat::Depth16Unorm,
+        Format::D32Float => MTLPixelFormat::Depth32Float,
+        Format::D24UnormS8Uint => MTLPixelFormat::Depth24Unorm_Stencil8,
+        Format::D32FloatS8Uint => MTLPixelFormat::Depth32Float_Stencil8,
+    }
+}
+
+pub(crate) fn mtl_texture_type(
+    dimension: TextureDimension,
+    array_layers: u32,
+    sample_count: u32,
+) -> MTLTextureType {
+    match (dimension, array_layers > 1, sample_count > 1) {
+        (TextureDimension::D1, false, _) => MTLTextureType::Type1D,
+        (TextureDimension::D1, true, _) => MTLTextureType::Type1DArray,
+        (TextureDimension::D2, false, false) => MTLTextureType::Type2D,
+        (TextureDimension::D2, true, false) => MTLTextureType::Type2DArray,
+        (TextureDimension::D2, false, true) => MTLTextureType::Type2DMultisample,
+        (TextureDimension::D2, true, true) => MTLTextureType::Type2DMultisampleArray,
+        (TextureDimension::D3, _, _) => MTLTextureType::Type3D,
+    }
+}
+
+pub(crate) fn mtl_texture_usage(usage: TextureUsage) -> MTLTextureUsage {
+    let mut flags = MTLTextureUsage::Unknown;
+
+    if usage.contains(TextureUsage::SAMPLED) {
+        flags |= MTLTextureUsage::ShaderRead;
+    }
+    if usage.contains(TextureUsage::STORAGE) {
+        flags |= MTLTextureUsage::ShaderRead | MTLTextureUsage::ShaderWrite;
+    }
+    if usage.contains(TextureUsage::RENDER_TARGET) || usage.contains(TextureUsage::DEPTH_STENCIL) {
+        flags |= MTLTextureUsage::RenderTarget;
+    }
+
+    flags
+}
+
+pub(crate) fn mtl_load_action(load_op: LoadOp) -> MTLLoadAction {
+    match load_op {
+        LoadOp::Load => MTLLoadAction::Load,
+        LoadOp::Clear => MTLLoadAction::Clear,
+        LoadOp::DontCare => MTLLoadAction::DontCare,
+    }
+}
+
+pub(crate) fn mtl_store_action(store_op: StoreOp, resolve: bool) -> MTLStoreAction {
+    match (store_op, resolve) {
+        (StoreOp::Store, false) => MTLStoreAction::Store,
+        (StoreOp::Store, true) => MTLStoreAction::StoreAndMultisampleResolve,
+        (StoreOp::DontCare, false) => MTLStoreAction::DontCare,
+        (StoreOp::DontCare, true) => MTLStoreAction::MultisampleResolve,
+    }
+}
+
+pub(crate) fn mtl_vertex_format(format: Format) -> MTLVertexFormat {
+    match format {
+        Format::R8Unorm => MTLVertexFormat::UCharNormalized,
+        Format::R8G8Unorm => MTLVertexFormat::UChar2Normalized,
+        Format::R8G8B8A8Unorm | Format::R8G8B8A8Srgb => MTLVertexFormat::UChar4Normalized,
+        Format::B8G8R8A8Unorm | Format::B8G8R8A8Srgb => MTLVertexFormat::UChar4Normalized_BGRA,
+        Format::A2B10G10R10Unorm => MTLVertexFormat::UInt1010102Normalized,
+        Format::R16Float => MTLVertexFormat::Half,
+        Format::R16G16Float => MTLVertexFormat::Half2,
+        Format::R16G16B16A16Float => MTLVertexFormat::Half4,
+        Format::R32Uint => MTLVertexFormat::UInt,
+        Format::R32Float => MTLVertexFormat::Float,
+        Format::R32G32Float => MTLVertexFormat::Float2,
+        Format::R32G32B32Float => MTLVertexFormat::Float3,
+        Format::R32G32B32A32Float => MTLVertexFormat::Float4,
+        Format::D16Unorm | Format::D32Float | Format::D24UnormS8Uint | Format::D32FloatS8Uint => {
+            MTLVertexFormat::Invalid
+        }
+    }
+}
+
+pub(crate) fn mtl_vertex_step_function(step_mode: VertexStepMode) -> MTLVertexStepFunction {
+    match step_mode {
+        VertexStepMode::Vertex => MTLVertexStepFunction::PerVertex,
+        VertexStepMode::Instance => MTLVertexStepFunction::PerInstance,
+    }
+}
+
+pub(crate) fn mtl_primitive_type(primitive_topology: PrimitiveTopology) -> MTLPrimitiveType {
+    match primitive_topology {
+        PrimitiveTopology::PointList => MTLPrimitiveType::Point,
+        PrimitiveTopology::LineList => MTLPrimitiveType::Line,
+        PrimitiveTopology::LineStrip => MTLPrimitiveType::LineStrip,
+        PrimitiveTopology::TriangleList => MTLPrimitiveType::Triangle,
+        PrimitiveTopology::TriangleStrip => MTLPrimitiveType::TriangleStrip,
+    }
+}
+
+pub(crate) fn mtl_primitive_topology_class(
+    primitive_topology: PrimitiveTopology,
+) -> MTLPrimitiveTopologyClass {
+    match primitive_topology {
+        PrimitiveTopology::PointList => MTLPrimitiveTopologyClass::Point,
+        PrimitiveTopology::LineList | PrimitiveTopology::LineStrip => {
+            MTLPrimitiveTopologyClass::Line
+        }
+        PrimitiveTopology::TriangleList | PrimitiveTopology::TriangleStrip => {
+            MTLPrimitiveTopologyClass::Triangle
+        }
+    }
+}
+
+pub(crate) fn mtl_index_type(index_type: IndexType) -> MTLIndexType {
+    match index_type {
+        IndexType::U16 => MTLIndexType::UInt16,
+        IndexType::U32 => MTLIndexType::UInt32,
+    }
+}
+
+pub(crate) fn mtl_resource_options(memory_location: MemoryLocation) -> MTLResourceOptions {
+    match memory_location {
+        MemoryLocation::GpuOnly => MTLResourceOptions::StorageModePrivate,
+        MemoryLocation::CpuToGpu => {
+            MTLResourceOptions::StorageModeShared | MTLResourceOptions::CPUCacheModeWriteCombined
+        }
+        MemoryLocation::GpuToCpu => MTLResourceOptions::StorageModeShared,
+    }
+}
+
+pub(crate) fn mtl_triangle_fill_mode(polygon_mode: PolygonMode) -> MTLTriangleFillMode {
+    match polygon_mode {
+        PolygonMode::Fill => MTLTriangleFillMode::Fill,
+        PolygonMode::Line => MTLTriangleFillMode::Lines,
+    }
+}
+
+pub(crate) fn mtl_cull_mode(cull_mode: CullMode) -> MTLCullMode {
+    match cull_mode {
+        CullMode::None => MTLCullMode::None,
+        CullMode::Front => MTLCullMode::Front,
+        CullMode::Back => MTLCullMode::Back,
+    }
+}
+
+pub(crate) fn mtl_winding(front_face: FrontFace) -> MTLWinding {
+    match front_face {
+        FrontFace::Clockwise => MTLWinding::Clockwise,
+        FrontFace::CounterClockwise => MTLWinding::CounterClockwise,
+    }
+}
+
+pub(crate) fn mtl_compare_function(compare_op: CompareOp) -> MTLCompareFunction {
+    match compare_op {
+        CompareOp::Never => MTLCompareFunction::Never,
+        CompareOp::Less => MTLCompareFunction::Less,
+        CompareOp::Equal => MTLCompareFunction::Equal,
+        CompareOp::LessOrEqual => MTLCompareFunction::LessEqual,
+        CompareOp::Greater => MTLCompareFunction::Greater,
+        CompareOp::NotEqual => MTLCompareFunction::NotEqual,
+        CompareOp::GreaterOrEqual => MTLCompareFunction::GreaterEqual,
+        CompareOp::Always => MTLCompareFunction::Always,
+    }
+}
+
+pub(crate) fn mtl_stencil_operation(stencil_op: StencilOp) -> MTLStencilOperation {
+    match stencil_op {
+        StencilOp::Keep => MTLStencilOperation::Keep,
+        StencilOp::Zero => MTLStencilOperation::Zero,
+        StencilOp::Replace => MTLStencilOperation::Replace,
+        StencilOp::IncrementAndClamp => MTLStencilOperation::IncrementClamp,
+        StencilOp::DecrementAndClamp => MTLStencilOperation::DecrementClamp,
+        StencilOp::Invert => MTLStencilOperation::Invert,
+        StencilOp::IncrementAndWrap => MTLStencilOperation::IncrementWrap,
+        StencilOp::DecrementAndWrap => MTLStencilOperation::DecrementWrap,
+    }
+}
+
+pub(crate) fn mtl_blend_factor(blend_factor: BlendFactor) -> MTLBlendFactor {
+    match blend_factor {
+        BlendFactor::Zero => MTLBlendFactor::Zero,
+        BlendFactor::One => MTLBlendFactor::One,
+        BlendFactor::SrcColor => MTLBlendFactor::SourceColor,
+        BlendFactor::OneMinusSrcColor => MTLBlendFactor::OneMinusSourceColor,
+        BlendFactor::DstColor => MTLBlendFactor::DestinationColor,
+        BlendFactor::OneMinusDstColor => MTLBlendFactor::OneMinusDestinationColor,
+        BlendFactor::SrcAlpha => MTLBlendFactor::SourceAlpha,
+        BlendFactor::OneMinusSrcAlpha => MTLBlendFactor::OneMinusSourceAlpha,
+        BlendFactor::DstAlpha => MTLBlendFactor::DestinationAlpha,
+        BlendFactor::OneMinusDstAlpha => MTLBlendFactor::OneMinusDestinationAlpha,
+    }
+}
+
+pub(crate) fn mtl_blend_operation(blend_op: BlendOp) -> MTLBlendOperation {
+    match blend_op {
+        BlendOp::Add => MTLBlendOperation::Add,
+        BlendOp::Subtract => MTLBlendOperation::Subtract,
+        BlendOp::ReverseSubtract => MTLBlendOperation::ReverseSubtract,
+        BlendOp::Min => MTLBlendOperation::Min,
+        BlendOp::Max => MTLBlendOperation::Max,
+    }
+}
+
+pub(crate) fn mtl_color_write_mask(write_mask: ColorWriteMask) -> MTLColorWriteMask {
+    let mut mask = MTLColorWriteMask::None;
+
+    if write_mask.contains(ColorWriteMask::R) {
+        mask |= MTLColorWriteMask::Red;
+    }
+    if write_mask.contains(ColorWriteMask::G) {
+        mask |= MTLColorWriteMask::Green;
+    }
+    if write_mask.contains(ColorWriteMask::B) {
+        mask |= MTLColorWriteMask::Blue;
+    }
+    if write_mask.contains(ColorWriteMask::A) {
+        mask |= MTLColorWriteMask::Alpha;
+    }
+
+    mask
+}
+
+pub(crate) fn mtl_sampler_min_mag_filter(filter: Filter) -> MTLSamplerMinMagFilter {
+    match filter {
+        Filter::Nearest => MTLSamplerMinMagFilter::Nearest,
+        Filter::Linear => MTLSamplerMinMagFilter::Linear,
+    }
+}
+
+pub(crate) fn mtl_sampler_mip_filter(filter: Filter) -> MTLSamplerMipFilter {
+    match filter {
+        Filter::Nearest => MTLSamplerMipFilter::Nearest,
+        Filter::Linear => MTLSamplerMipFilter::Linear,
+    }
+}
+
+pub(crate) fn mtl_sampler_address_mode(address_mode: AddressMode) -> MTLSamplerAddressMode {
+    match address_mode {
+        AddressMode::Repeat => MTLSamplerAddressMode::Repeat,
+        AddressMode::MirroredRepeat => MTLSamplerAddressMode::MirrorRepeat,
+        AddressMode::ClampToEdge => MTLSamplerAddressMode::ClampToEdge,
+        AddressMode::ClampToBorder => MTLSamplerAddressMode::ClampToBorderColor,
+    }
+}
+
+pub(crate) fn mtl_sampler_border_color(border_color: BorderColor) -> MTLSamplerBorderColor {
+    match border_color {
+        BorderColor::TransparentBlack => MTLSamplerBorderColor::TransparentBlack,
+        BorderColor::OpaqueBlack => MTLSamplerBorderColor::OpaqueBlack,
+        BorderColor::OpaqueWhite => MTLSamplerBorderColor::OpaqueWhite,
+    }
+}
@@ -0,0 +1,31 @@
+use crate::backend::metal::MetalBindingLayout;
+use crate::{DescriptorSet, DescriptorWrite, Error};
+
+// Descriptor pools are not supported by the Metal backend, so these can never be created.
+pub enum MetalDescriptorPool {}
+
+impl MetalDescriptorPool {
+    pub fn allocate(
+        &mut self,
+        _binding_layout: &MetalBindingLayout,
+        _table: u32,
+    ) -> Result<DescriptorSet, Error> {
+        match *self {}
+    }
+
+    pub fn reset(&mut self) -> Result<(), Error> {
+        match *self {}
+    }
+}
+
+pub enum MetalDescriptorSet {}
+
+impl MetalDescriptorSet {
+    pub fn table(&self) -> u32 {
+        match *self {}
+    }
+
+    pub fn write(&self, _writes: &[DescriptorWrite]) -> Result<(), Error> {
+        match *self {}
+    }
+}
@@ -1,27 +1,54 @@
 use crate::backend::metal::*;
-use crate::backend::metal::*;
-use crate::{Error, Queue, ShaderDesc, ShaderKind, ShaderModule, util};
+use crate::shader_reflection::reflect_spirv;
+use crate::{
+    BindingLayout, BindingLayoutDesc, BindlessResource, BlendState, Buffer, BufferDesc,
+    CommandList, DepthStencilState, DescriptorIndex, Error, GraphicsPipelineDesc, Heap, HeapDesc,
+    MemoryLocation, MemoryRequirements, MeshPipelineDesc, Pipeline, Queue, QueueType,
+    RenderTargetFormats, Sampler, SamplerDesc, ShaderDesc, ShaderKind, ShaderModule,
+    ShaderReflection, ShaderSource, StencilFaceState, Swapchain, SwapchainDesc, Texture,
+    TextureDesc, util,
+};
 use dispatch2::{DispatchData, dispatch_block_t};
 use metal_irconverter::sys;
 use metal_irconverter::sys::{
-    IRErrorGetCode, IRObjectGetReflection, IRShaderReflectionCreate,
+    IRErrorGetCode, IRObjectGetReflection, IRShaderReflection, IRShaderReflectionCreate,
     IRShaderReflectionGetResourceCount, IRShaderReflectionGetResourceLocations, IRShaderStage,
     IRShaderStage_IRShaderStageAmplification, IRShaderStage_IRShaderStageCompute,
     IRShaderStage_IRShaderStageFragment, IRShaderStage_IRShaderStageMesh,
     IRShaderStage_IRShaderStageVertex,
 };
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
 use objc2_foundation::NSString;
-use objc2_metal::{MTL4CommandQueueDescriptor, MTLDevice, MTLLibrary};
+use objc2_metal::{
+    MTL4CommandQueueDescriptor, MTLDepthStencilDescriptor, MTLDepthStencilState, MTLDevice,
+    MTLLibrary, MTLMeshRenderPipelineDescriptor, MTLPipelineOption, MTLPrimitiveType,
+    MTLRenderPipelineColorAttachmentDescriptorArray, MTLRenderPipelineDescriptor, MTLResidencySet,
+    MTLResidencySetDescriptor, MTLResource, MTLSamplerDescriptor, MTLSize, MTLStencilDescriptor,
+    MTLStorageMode, MTLTextureDescriptor, MTLVertexDescriptor,
+};
 use std::ffi::CString;
 use std::ptr::NonNull;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+pub(crate) const IR_DESCRIPTOR_HEAP_BIND_POINT: usize = 0;
+pub(crate) const IR_SAMPLER_HEAP_BIND_POINT: usize = 1;
+pub(crate) const IR_ARGUMENT_BUFFER_BIND_POINT: usize = 2;
+pub(crate) const IR_VERTEX_BUFFER_BIND_POINT: usize = 6;
+pub(crate) const IR_STAGE_IN_ATTRIBUTE_START_INDEX: usize = 11;
 
 pub struct MetalDevice {
-    physical_device: Arc<MetalPhysicalDevice>,
+    pub(crate) physical_device: Arc<MetalPhysicalDevice>,
+
+    pub(crate) residency_set: Retained<ProtocolObject<dyn MTLResidencySet>>,
 
-    direct_queue: Arc<MetalQueue>,
+    pub(crate) direct_queue: Arc<MetalQueue>,
     compute_queue: Arc<MetalQueue>,
     transfer_queue: Arc<MetalQueue>,
+
+    pub(crate) bindless: MetalBindlessHeap,
+
+    garbage: Mutex<Vec<(MetalGarbage, [u64; 3])>>,
 }
 
 impl MetalDevice {
@@ -47,11 +74,52 @@ impl MetalDevice {
             .mtl_device
             .newMTL4CommandQueueWithDescriptor_error(&transfer_queue_descriptor)?;
 
+        let residency_set_descriptor = MTLResidencySetDescriptor::new();
+        residency_set_descriptor.setLabel(Some(&NSString::from_str("Device residency set")));
+
+        let residency_set = physical_device
+            .mtl_device
+            .newResidencySetWithDescriptor_error(&residency_set_descriptor)?;
+
+        direct_queue.addResidencySet(&residency_set);
+        compute_queue.addResidencySet(&residency_set);
+        transfer_queue.addResidencySet(&residency_set);
+
+        let bindless = MetalBindlessHeap::new(&physical_device.mtl_device)?;
+
+        residency_set.addAllocation(ProtocolObject::from_ref(&*bindless.resource_heap));
+        residency_set.addAllocation(ProtocolObject::from_ref(&*bindless.sampler_heap));
+        residency_set.commit();
+
+        let new_shared_event = || {
+            physical_device
+                .mtl_device
+                .newSharedEvent()
+                .ok_or(Error::MetalError(
+                    "Failed to create shared event".to_owned(),
+                ))
+        };
+
         Ok(Self {
+            direct_queue: Arc::new(MetalQueue::new(
+                direct_queue,
+                QueueType::Direct,
+                new_shared_event()?,
+            )?),
+            compute_queue: Arc::new(MetalQueue::new(
+                compute_queue,
+                QueueType::Compute,
+                new_shared_event()?,
+            )?),
+            transfer_queue: Arc::new(MetalQueue::new(
+                transfer_queue,
+                QueueType::Transfer,
+                new_shared_event()?,
+            )?),
             physical_device,
-            direct_queue: Arc::new(MetalQueue::new(direct_queue)?),
-            compute_queue: Arc::new(MetalQueue::new(compute_queue)?),
-            transfer_queue: Arc::new(MetalQueue::new(transfer_queue)?),
+            residency_set,
+            bindless,
+            garbage: Mutex::new(Vec::new()),
         })
     }
 
@@ -59,7 +127,31 @@ impl MetalDevice {
         self: &Arc<Self>,
         desc: &ShaderDesc,
     ) -> Result<ShaderModule, Error> {
-        let dxil_code = util::compile_hlsl(desc, false)?;
+        let dxil_code = util::dxil_code(desc)?;
+
+        // Reflection needs SPIR-V, which DXIL-only sources lack and HLSL sources only get from a
+        // second compile.
+        let reflect = match &desc.source {
+            ShaderSource::Embedded { spirv, .. } => !spirv.is_empty(),
+            ShaderSource::Hlsl { .. } => desc.reflect,
+            _ => false,
+        };
+
+        let shader_reflection = if reflect {
+            reflect_spirv(&util::spirv_code(desc)?, desc.kind, &desc.entry_point)?
+        } else {
+            ShaderReflection {
+                kind: desc.kind,
+                entry_point: desc.entry_point.to_string(),
+                bindings: Vec::new(),
+                unsupported_bindings: Vec::new(),
+                push_constants: None,
+                thread_group_size: None,
+                mesh_output: None,
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+            }
+        };
 
         unsafe {
             let entry_point_cstr = CString::new(desc.entry_point.as_str())?;
@@ -113,6 +205,8 @@ impl MetalDevice {
                 ));
             }
 
+            let threads_per_threadgroup = ir_threads_per_threadgroup(reflection, desc.kind);
+
             let count = IRShaderReflectionGetResourceCount(reflection);
             let mut locations = Vec::with_capacity(count);
             IRShaderReflectionGetResourceLocations(reflection, locations.as_mut_ptr());
@@ -141,11 +235,484 @@ impl MetalDevice {
                 .ok_or(Error::MetalError("Failed to create function".to_owned()))?;
 
             Ok(ShaderModule::Metal(MetalShaderModule::new(
-                library, function, locations,
+                library,
+                function,
+                locations,
+                desc.kind,
+                threads_per_threadgroup,
+                shader_reflection,
+                reflect,
             )?))
         }
     }
 
+    pub fn create_buffer(self: &Arc<Self>, desc: &BufferDesc) -> Result<Buffer, Error> {
+        let buffer = self
+            .physical_device
+            .mtl_device
+            .newBufferWithLength_options(
+                desc.size as usize,
+                mtl_resource_options(desc.memory_location),
+            )
+            .ok_or(Error::MetalError("Failed to create buffer".to_owned()))?;
+        buffer.setLabel(Some(&NSString::from_str(&desc.name)));
+
+        Ok(Buffer::Metal(MetalBuffer::new(
+            self.clone(),
+            buffer,
+            desc.size,
+            desc.usage,
+            desc.memory_location != MemoryLocation::GpuOnly,
+        )?))
+    }
+
+    pub fn create_texture(self: &Arc<Self>, desc: &TextureDesc) -> Result<Texture, Error> {
+        let texture = self
+            .physical_device
+            .mtl_device
+            .newTextureWithDescriptor(&texture_descriptor(desc))
+            .ok_or(Error::MetalError("Failed to create texture".to_owned()))?;
+        texture.setLabel(Some(&NSString::from_str(&desc.name)));
+
+        Ok(Texture::Metal(MetalTexture::new(
+            self.clone(),
+            texture,
+            desc.clone(),
+        )?))
+    }
+
+    pub fn create_heap(self: &Arc<Self>, desc: &HeapDesc) -> Result<Heap, Error> {
+        let descriptor = MTLHeapDescriptor::new();
+        descriptor.setType(MTLHeapType::Placement);
+        descriptor.setStorageMode(MTLStorageMode::Private);
+        descriptor.setSize(desc.size as usize);
+
+        let heap = self
+            .physical_device
+            .mtl_device
+            .newHeapWithDescriptor(&descriptor)
+            .ok_or(Error::MetalError("Failed to create heap".to_owned()))?;
+        heap.setLabel(Some(&NSString::from_str(&desc.name)));
+
+        Ok(Heap::Metal(Arc::new(MetalHeap::new(
+            self.clone(),
+            heap,
+            desc.size,
+        )?)))
+    }
+
+    pub fn buffer_memory_requirements(&self, desc: &BufferDesc) -> MemoryRequirements {
+        let size_and_align = self
+            .physical_device
+            .mtl_device
+            .heapBufferSizeAndAlignWithLength_options(
+                desc.size as usize,
+                mtl_resource_options(desc.memory_location),
+            );
+
+        MemoryRequirements {
+            size: size_and_align.size as u64,
+            alignment: size_and_align.align as u64,
+            memory_type_bits: u32::MAX,
+        }
+    }
+
+    pub fn texture_memory_requirements(&self, desc: &TextureDesc) -> MemoryRequirements {
+        let size_and_align = self
+            .physical_device
+            .mtl_device
+            .heapTextureSizeAndAlignWithDescriptor(&texture_descriptor(desc));
+
+        MemoryRequirements {
+            size: size_and_align.size as u64,
+            alignment: size_and_align.align as u64,
+            memory_type_bits: u32::MAX,
+        }
+    }
+
+    pub fn create_placed_buffer(
+        self: &Arc<Self>,
+        heap: &Arc<MetalHeap>,
+        offset: u64,
+        desc: &BufferDesc,
+    ) -> Result<Buffer, Error> {
+        if desc.memory_location != MemoryLocation::GpuOnly {
+            return Err(Error::Unsupported(
+                "Placed buffers must use MemoryLocation::GpuOnly",
+            ));
+        }
+
+        let buffer = unsafe {
+            heap.heap.newBufferWithLength_options_offset(
+                desc.size as usize,
+                mtl_resource_options(desc.memory_location),
+                offset as usize,
+            )
+        }
+        .ok_or(Error::MetalError(
+            "Failed to create placed buffer".to_owned(),
+        ))?;
+        buffer.setLabel(Some(&NSString::from_str(&desc.name)));
+
+        Ok(Buffer::Metal(MetalBuffer::new(
+            self.clone(),
+            buffer,
+            desc.size,
+            desc.usage,
+            false,
+        )?))
+    }
+
+    pub fn create_placed_texture(
+        self: &Arc<Self>,
+        heap: &Arc<MetalHeap>,
+        offset: u64,
+        desc: &TextureDesc,
+    ) -> Result<Texture, Error> {
+        let texture = unsafe {
+            heap.heap
+                .newTextureWithDescriptor_offset(&texture_descriptor(desc), offset as usize)
+        }
+        .ok_or(Error::MetalError(
+            "Failed to create placed texture".to_owned(),
+        ))?;
+        texture.setLabel(Some(&NSString::from_str(&desc.name)));
+
+        Ok(Texture::Metal(MetalTexture::new(
+            self.clone(),
+            texture,
+            desc.clone(),
+        )?))
+    }
+
+    pub fn create_mesh_pipeline(
+        self: &Arc<Self>,
+        desc: &MeshPipelineDesc,
+    ) -> Result<Pipeline, Error> {
+        let descriptor = MTLMeshRenderPipelineDescriptor::new();
+        descriptor.setLabel(Some(&NSString::from_str(&desc.name)));
+
+        let task_shader = desc
+            .task_shader
+            .map(|task_shader| shader_function(task_shader, ShaderKind::Task))
+            .transpose()?;
+        let mesh_shader = shader_function(desc.mesh_shader, ShaderKind::Mesh)?;
+        let fragment_shader = desc
+            .fragment_shader
+            .map(|fragment_shader| shader_function(fragment_shader, ShaderKind::Fragment))
+            .transpose()?;
+
+        unsafe {
+            descriptor.setObjectFunction(task_shader.map(|shader| &*shader.function));
+            descriptor.setMeshFunction(Some(&mesh_shader.function));
+            descriptor.setFragmentFunction(fragment_shader.map(|shader| &*shader.function));
+            descriptor.setRasterSampleCount(desc.render_target_formats.sample_count as usize);
+        }
+        descriptor.setAlphaToCoverageEnabled(desc.blend.alpha_to_coverage);
+
+        configure_render_targets(
+            &descriptor.colorAttachments(),
+            &desc.blend,
+            &desc.render_target_formats,
+        );
+        if let Some(format) = desc.render_target_formats.depth_stencil_format {
+            descriptor.setDepthAttachmentPixelFormat(mtl_pixel_format(format));
+
+            if format.is_stencil() {
+                descriptor.setStencilAttachmentPixelFormat(mtl_pixel_format(format));
+            }
+        }
+
+        let render_pipeline_state = self
+            .physical_device
+            .mtl_device
+            .newRenderPipelineStateWithMeshDescriptor_options_reflection_error(
+                &descriptor,
+                MTLPipelineOption::None,
+                None,
+            )?;
+
+        let depth_stencil_state = self.create_depth_stencil_state(&desc.depth_stencil)?;
+
+        let threads_per_object_threadgroup = task_shader
+            .map(|shader| mtl_size(shader.threads_per_threadgroup))
+            .unwrap_or(mtl_size([1, 1, 1]));
+        let threads_per_mesh_threadgroup = mtl_size(mesh_shader.threads_per_threadgroup);
+
+        Ok(Pipeline::Metal(MetalPipeline::new(
+            render_pipeline_state,
+            depth_stencil_state,
+            Some(pipeline_binding_layout(
+                desc.binding_layout,
+                &desc.name,
+                &[
+                    desc.task_shader,
+                    Some(desc.mesh_shader),
+                    desc.fragment_shader,
+                ],
+            )?),
+            desc.rasterizer,
+            MTLPrimitiveType::Triangle,
+            threads_per_object_threadgroup,
+            threads_per_mesh_threadgroup,
+        )?))
+    }
+
+    pub fn create_graphics_pipeline(
+        self: &Arc<Self>,
+        desc: &GraphicsPipelineDesc,
+    ) -> Result<Pipeline, Error> {
+        let descriptor = MTLRenderPipelineDescriptor::new();
+        descriptor.setLabel(Some(&NSString::from_str(&desc.name)));
+
+        let vertex_shader = shader_function(desc.vertex_shader, ShaderKind::Vertex)?;
+        let fragment_shader = desc
+            .fragment_shader
+            .map(|fragment_shader| shader_function(fragment_shader, ShaderKind::Fragment))
+            .transpose()?;
+
+        let vertex_descriptor = MTLVertexDescriptor::new();
+        for (i, buffer) in desc.vertex_input.buffers.iter().enumerate() {
+            let layout = unsafe {
+                vertex_descriptor
+                    .layouts()
+                    .objectAtIndexedSubscript(IR_VERTEX_BUFFER_BIND_POINT + i)
+            };
+
+            unsafe { layout.setStride(buffer.stride as usize) };
+            layout.setStepFunction(mtl_vertex_step_function(buffer.step_mode));
+        }
+        for attribute in &desc.vertex_input.attributes {
+            let attribute_descriptor = unsafe {
+                vertex_descriptor.attributes().objectAtIndexedSubscript(
+                    IR_STAGE_IN_ATTRIBUTE_START_INDEX + attribute.location as usize,
+                )
+            };
+
+            attribute_descriptor.setFormat(mtl_vertex_format(attribute.format));
+            unsafe {
+                attribute_descriptor.setOffset(attribute.offset as usize);
+                attribute_descriptor
+                    .setBufferIndex(IR_VERTEX_BUFFER_BIND_POINT + attribute.buffer_index as usize);
+            }
+        }
+
+        unsafe {
+            descriptor.setVertexFunction(Some(&vertex_shader.function));
+            descriptor.setFragmentFunction(fragment_shader.map(|shader| &*shader.function));
+            descriptor.setVertexDescriptor(Some(&vertex_descriptor));
+            descriptor
+                .setInputPrimitiveTopology(mtl_primitive_topology_class(desc.primitive_topology));
+            descriptor.setRasterSampleCount(desc.render_target_formats.sample_count as usize);
+        }
+        descriptor.setAlphaToCoverageEnabled(desc.blend.alpha_to_coverage);
+
+        configure_render_targets(
+            &descriptor.colorAttachments(),
+            &desc.blend,
+            &desc.render_target_formats,
+        );
+        if let Some(format) = desc.render_target_formats.depth_stencil_format {
+            descriptor.setDepthAttachmentPixelFormat(mtl_pixel_format(format));
+
+            if format.is_stencil() {
+                descriptor.setStencilAttachmentPixelFormat(mtl_pixel_format(format));
+            }
+        }
+
+        let render_pipeline_state = self
+            .physical_device
+            .mtl_device
+            .newRenderPipelineStateWithDescriptor_error(&descriptor)?;
+
+        let depth_stencil_state = self.create_depth_stencil_state(&desc.depth_stencil)?;
+
+        Ok(Pipeline::Metal(MetalPipeline::new(
+            render_pipeline_state,
+            depth_stencil_state,
+            Some(pipeline_binding_layout(
+                desc.binding_layout,
+                &desc.name,
+                &[Some(desc.vertex_shader), desc.fragment_shader],
+            )?),
+            desc.rasterizer,
+            mtl_primitive_type(desc.primitive_topology),
+            mtl_size([1, 1, 1]),
+            mtl_size([1, 1, 1]),
+        )?))
+    }
+
+    fn create_depth_stencil_state(
+        &self,
+        depth_stencil: &DepthStencilState,
+    ) -> Result<Option<Retained<ProtocolObject<dyn MTLDepthStencilState>>>, Error> {
+        if !depth_stencil.depth_test && !depth_stencil.stencil_test {
+            return Ok(None);
+        }
+
+        let depth_stencil_descriptor = MTLDepthStencilDescriptor::new();
+
+        if depth_stencil.depth_test {
+            depth_stencil_descriptor
+                .setDepthCompareFunction(mtl_compare_function(depth_stencil.depth_compare_op));
+            depth_stencil_descriptor.setDepthWriteEnabled(depth_stencil.depth_write);
+        }
+
+        if depth_stencil.stencil_test {
+            let stencil_descriptor = |face: &StencilFaceState| {
+                let stencil_descriptor = MTLStencilDescriptor::new();
+                stencil_descriptor.setStencilFailureOperation(mtl_stencil_operation(face.fail_op));
+                stencil_descriptor
+                    .setDepthFailureOperation(mtl_stencil_operation(face.depth_fail_op));
+                stencil_descriptor
+                    .setDepthStencilPassOperation(mtl_stencil_operation(face.pass_op));
+                stencil_descriptor.setStencilCompareFunction(mtl_compare_function(face.compare_op));
+                stencil_descriptor.setReadMask(depth_stencil.stencil_read_mask as u32);
+                stencil_descriptor.setWriteMask(depth_stencil.stencil_write_mask as u32);
+                stencil_descriptor
+            };
+
+            depth_stencil_descriptor
+                .setFrontFaceStencil(Some(&stencil_descriptor(&depth_stencil.stencil_front)));
+            depth_stencil_descriptor
+                .setBackFaceStencil(Some(&stencil_descriptor(&depth_stencil.stencil_back)));
+        }
+
+        Ok(Some(
+            self.physical_device
+                .mtl_device
+                .newDepthStencilStateWithDescriptor(&depth_stencil_descriptor)
+                .ok_or(Error::MetalError(
+                    "Failed to create depth stencil state".to_owned(),
+                ))?,
+        ))
+    }
+
+    pub fn create_binding_layout(
+        self: &Arc<Self>,
+        desc: &BindingLayoutDesc,
+    ) -> Result<BindingLayout, Error> {
+        Ok(BindingLayout::Metal(Arc::new(MetalBindingLayout::new(
+            desc,
+        )?)))
+    }
+
+    pub fn create_sampler(self: &Arc<Self>, desc: &SamplerDesc) -> Result<Sampler, Error> {
+        let descriptor = MTLSamplerDescriptor::new();
+        descriptor.setMinFilter(mtl_sampler_min_mag_filter(desc.min_filter));
+        descriptor.setMagFilter(mtl_sampler_min_mag_filter(desc.mag_filter));
+        descriptor.setMipFilter(mtl_sampler_mip_filter(desc.mipmap_filter));
+        descriptor.setSAddressMode(mtl_sampler_address_mode(desc.address_mode_u));
+        descriptor.setTAddressMode(mtl_sampler_address_mode(desc.address_mode_v));
+        descriptor.setRAddressMode(mtl_sampler_address_mode(desc.address_mode_w));
+        descriptor.setBorderColor(mtl_sampler_border_color(desc.border_color));
+        descriptor.setLodMinClamp(desc.min_lod);
+        descriptor.setLodMaxClamp(desc.max_lod);
+        descriptor.setLodBias(desc.mip_lod_bias);
+        descriptor.setSupportArgumentBuffers(true);
+
+        if let Some(max_anisotropy) = desc.max_anisotropy {
+            descriptor.setMaxAnisotropy(max_anisotropy as usize);
+        }
+
+        if let Some(compare_op) = desc.compare_op {
+            descriptor.setCompareFunction(mtl_compare_function(compare_op));
+        }
+
+        let sampler_state = self
+            .physical_device
+            .mtl_device
+            .newSamplerStateWithDescriptor(&descriptor)
+            .ok_or(Error::MetalError("Failed to create sampler".to_owned()))?;
+
+        Ok(Sampler::Metal(MetalSampler::new(sampler_state, *desc)?))
+    }
+
+    pub fn allocate_descriptor(
+        &self,
+        resource: BindlessResource,
+    ) -> Result<DescriptorIndex, Error> {
+        self.bindless
+            .allocate_resource(resource, self.completed_values()?)
+    }
+
+    pub fn allocate_sampler_descriptor(
+        &self,
+        sampler: &MetalSampler,
+    ) -> Result<DescriptorIndex, Error> {
+        self.bindless.allocate_sampler(
+            &sampler.sampler_state,
+            sampler.desc().mip_lod_bias,
+            self.completed_values()?,
+        )
+    }
+
+    pub fn free_descriptor(&self, index: DescriptorIndex) {
+        self.bindless.free_resource(index, self.submitted_values());
+    }
+
+    pub fn free_sampler_descriptor(&self, index: DescriptorIndex) {
+        self.bindless.free_sampler(index, self.submitted_values());
+    }
+
+    pub fn create_swapchain(self: &Arc<Self>, desc: &SwapchainDesc) -> Result<Swapchain, Error> {
+        Ok(Swapchain::Metal(MetalSwapchain::new(self.clone(), desc)?))
+    }
+
+    pub fn create_command_list(
+        self: &Arc<Self>,
+        queue_type: QueueType,
+    ) -> Result<CommandList, Error> {
+        Ok(CommandList::Metal(MetalCommandList::new(
+            self.clone(),
+            queue_type,
+        )?))
+    }
+
+    pub fn collect_garbage(&self) -> Result<(), Error> {
+        let completed_values = self.completed_values()?;
+
+        let mut garbage = self.garbage.lock().unwrap();
+        let mut residency_set_changed = false;
+
+        for (garbage, _) in garbage.extract_if(.., |(_, values)| {
+            values
+                .iter()
+                .zip(&completed_values)
+                .all(|(value, completed_value)| value <= completed_value)
+        }) {
+            residency_set_changed |= garbage.destroy(&self.residency_set);
+        }
+
+        if residency_set_changed {
+            self.residency_set.commit();
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn destroy_deferred(&self, garbage: MetalGarbage) {
+        let values = self.submitted_values();
+
+        self.garbage.lock().unwrap().push((garbage, values));
+    }
+
+    fn completed_values(&self) -> Result<[u64; 3], Error> {
+        Ok([
+            self.direct_queue.completed_value()?,
+            self.compute_queue.completed_value()?,
+            self.transfer_queue.completed_value()?,
+        ])
+    }
+
+    fn submitted_values(&self) -> [u64; 3] {
+        [
+            self.direct_queue.submitted_value(),
+            self.compute_queue.submitted_value(),
+            self.transfer_queue.submitted_value(),
+        ]
+    }
+
     pub fn get_direct_queue(&self) -> Queue {
         Queue::Metal(self.direct_queue.clone())
     }
@@ -159,6 +726,20 @@ impl MetalDevice {
     }
 }
 
+impl Drop for MetalDevice {
+    fn drop(&mut self) {
+        let _ = self.direct_queue.wait_idle();
+        let _ = self.compute_queue.wait_idle();
+        let _ = self.transfer_queue.wait_idle();
+
+        for (garbage, _) in self.garbage.get_mut().unwrap().drain(..) {
+            garbage.destroy(&self.residency_set);
+        }
+
+        self.residency_set.commit();
+    }
+}
+
 fn ir_shader_stage(kind: ShaderKind) -> IRShaderStage {
     match kind {
         ShaderKind::Vertex => IRShaderStage_IRShaderStageVertex,
@@ -168,3 +749,155 @@ fn ir_shader_stage(kind: ShaderKind) -> IRShaderStage {
         ShaderKind::Compute => IRShaderStage_IRShaderStageCompute,
     }
 }
+
+unsafe fn ir_threads_per_threadgroup(
+    reflection: *mut IRShaderReflection,
+    kind: ShaderKind,
+) -> [u32; 3] {
+    unsafe {
+        match kind {
+            ShaderKind::Task => {
+                let mut info = std::mem::zeroed::<sys::IRVersionedASInfo>();
+                if !sys::IRShaderReflectionCopyAmplificationInfo(
+                    reflection,
+                    sys::IRReflectionVersion_IRReflectionVersion_1_0,
+                    &mut info,
+                ) {
+                    return [1, 1, 1];
+                }
+
+                let num_threads = info.__bindgen_anon_1.info_1_0.num_threads;
+                sys::IRShaderReflectionReleaseAmplificationInfo(&mut info);
+                num_threads
+            }
+            ShaderKind::Mesh => {
+                let mut info = std::mem::zeroed::<sys::IRVersionedMSInfo>();
+                if !sys::IRShaderReflectionCopyMeshInfo(
+                    reflection,
+                    sys::IRReflectionVersion_IRReflectionVersion_1_0,
+                    &mut info,
+                ) {
+                    return [1, 1, 1];
+                }
+
+                let num_threads = info.__bindgen_anon_1.info_1_0.num_threads;
+                sys::IRShaderReflectionReleaseMeshInfo(&mut info);
+                num_threads
+            }
+            ShaderKind::Compute => {
+                let mut info = std::mem::zeroed::<sys::IRVersionedCSInfo>();
+                if !sys::IRShaderReflectionCopyComputeInfo(
+                    reflection,
+                    sys::IRReflectionVersion_IRReflectionVersion_1_0,
+                    &mut info,
+                ) {
+                    return [1, 1, 1];
+                }
+
+                let tg_size = info.__bindgen_anon_1.info_1_0.tg_size;
+                sys::IRShaderReflectionReleaseComputeInfo(&mut info);
+                tg_size
+            }
+            ShaderKind::Vertex | ShaderKind::Fragment => [1, 1, 1],
+        }
+    }
+}
+
+fn shader_function(
+    shader_module: &ShaderModule,
+    expected_kind: ShaderKind,
+) -> Result<&MetalShaderModule, Error> {
+    let shader_module = shader_module.as_metal_shader_module();
+
+    if shader_module.kind() != expected_kind {
+        return Err(Error::InvalidShaderKind(
+            shader_module.kind(),
+            expected_kind,
+        ));
+    }
+
+    Ok(shader_module)
+}
+
+fn configure_render_targets(
+    color_attachments: &MTLRenderPipelineColorAttachmentDescriptorArray,
+    blend: &BlendState,
+    render_target_formats: &RenderTargetFormats,
+) {
+    for (i, format) in render_target_formats.color_formats.iter().enumerate() {
+        let attachment = blend.attachments.get(i).copied().unwrap_or_default();
+        let color_attachment = unsafe { color_attachments.objectAtIndexedSubscript(i) };
+
+        color_attachment.setPixelFormat(mtl_pixel_format(*format));
+        color_attachment.setBlendingEnabled(attachment.blend_enable);
+        color_attachment
+            .setSourceRGBBlendFactor(mtl_blend_factor(attachment.src_color_blend_factor));
+        color_attachment
+            .setDestinationRGBBlendFactor(mtl_blend_factor(attachment.dst_color_blend_factor));
+        color_attachment.setRgbBlendOperation(mtl_blend_operation(attachment.color_blend_op));
+        color_attachment
+            .setSourceAlphaBlendFactor(mtl_blend_factor(attachment.src_alpha_blend_factor));
+        color_attachment
+            .setDestinationAlphaBlendFactor(mtl_blend_factor(attachment.dst_alpha_blend_factor));
+        color_attachment.setAlphaBlendOperation(mtl_blend_operation(attachment.alpha_blend_op));
+        color_attachment.setWriteMask(mtl_color_write_mask(attachment.write_mask));
+    }
+}
+
+fn texture_descriptor(desc: &TextureDesc) -> Retained<MTLTextureDescriptor> {
+    let descriptor = MTLTextureDescriptor::new();
+    descriptor.setTextureType(mtl_texture_type(
+        desc.dimension,
+        desc.array_layers,
+        desc.sample_count,
+    ));
+    descriptor.setPixelFormat(mtl_pixel_format(desc.format));
+    unsafe {
+        descriptor.setWidth(desc.width as usize);
+        descriptor.setHeight(desc.height as usize);
+        descriptor.setDepth(desc.depth as usize);
+        descriptor.setMipmapLevelCount(desc.mip_levels as usize);
+        descriptor.setSampleCount(desc.sample_count as usize);
+        descriptor.setArrayLength(desc.array_layers as usize);
+    }
+    descriptor.setStorageMode(MTLStorageMode::Private);
+    descriptor.setUsage(mtl_texture_usage(desc.usage));
+    descriptor
+}
+
+fn mtl_size(size: [u32; 3]) -> MTLSize {
+    MTLSize {
+        width: size[0] as usize,
+        height: size[1] as usize,
+        depth: size[2] as usize,
+    }
+}
+
+fn pipeline_binding_layout(
+    binding_layout: Option<&BindingLayout>,
+    name: &str,
+    shaders: &[Option<&ShaderModule>],
+) -> Result<Arc<MetalBindingLayout>, Error> {
+    if let Some(binding_layout) = binding_layout {
+        return Ok(binding_layout.as_metal_binding_layout().clone());
+    }
+
+    let reflections = shaders
+        .iter()
+        .flatten()
+        .map(|shader| {
+            let metal_shader_module = shader.as_metal_shader_module();
+            if !metal_shader_module.is_reflected() {
+                return Err(Error::Unsupported(
+                    "Binding layouts cannot be derived for shaders without reflection",
+                ));
+            }
+
+            Ok(metal_shader_module.reflection())
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(Arc::new(MetalBindingLayout::new(
+        &BindingLayoutDesc::from_reflection(name.to_owned().into(), &reflections)?,
+    )?))
+}
@@ -0,0 +1,21 @@
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{MTLAllocation, MTLResidencySet};
+use std::any::Any;
+
+pub(crate) enum MetalGarbage {
+    Allocation(Retained<ProtocolObject<dyn MTLAllocation>>),
+    Object(Box<dyn Any>),
+}
+
+impl MetalGarbage {
+    pub(crate) fn destroy(self, residency_set: &ProtocolObject<dyn MTLResidencySet>) -> bool {
+        match self {
+            MetalGarbage::Allocation(allocation) => {
+                residency_set.removeAllocation(&allocation);
+                true
+            }
+            MetalGarbage::Object(_) => false,
+        }
+    }
+}
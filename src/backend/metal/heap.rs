@@ -0,0 +1,41 @@
+use crate::Error;
+use crate::backend::metal::{MetalDevice, MetalGarbage};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{MTLHeap, MTLResidencySet};
+use std::sync::Arc;
+
+pub struct MetalHeap {
+    device: Arc<MetalDevice>,
+
+    pub(crate) heap: Retained<ProtocolObject<dyn MTLHeap>>,
+    size: u64,
+}
+
+impl MetalHeap {
+    pub fn new(
+        device: Arc<MetalDevice>,
+        heap: Retained<ProtocolObject<dyn MTLHeap>>,
+        size: u64,
+    ) -> Result<Self, Error> {
+        device
+            .residency_set
+            .addAllocation(ProtocolObject::from_ref(&*heap));
+        device.residency_set.commit();
+
+        Ok(Self { device, heap, size })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Drop for MetalHeap {
+    fn drop(&mut self) {
+        self.device
+            .destroy_deferred(MetalGarbage::Allocation(ProtocolObject::from_retained(
+                self.heap.clone(),
+            )));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::backend::metal::MetalBindingLayout;
+use crate::{Error, RasterizerState};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{MTLDepthStencilState, MTLPrimitiveType, MTLRenderPipelineState, MTLSize};
+use std::sync::Arc;
+
+pub struct MetalPipeline {
+    pub(crate) render_pipeline_state: Retained<ProtocolObject<dyn MTLRenderPipelineState>>,
+    pub(crate) depth_stencil_state: Option<Retained<ProtocolObject<dyn MTLDepthStencilState>>>,
+    pub(crate) binding_layout: Option<Arc<MetalBindingLayout>>,
+
+    pub(crate) rasterizer: RasterizerState,
+    pub(crate) primitive_type: MTLPrimitiveType,
+
+    pub(crate) threads_per_object_threadgroup: MTLSize,
+    pub(crate) threads_per_mesh_threadgroup: MTLSize,
+}
+
+impl MetalPipeline {
+    pub fn new(
+        render_pipeline_state: Retained<ProtocolObject<dyn MTLRenderPipelineState>>,
+        depth_stencil_state: Option<Retained<ProtocolObject<dyn MTLDepthStencilState>>>,
+        binding_layout: Option<Arc<MetalBindingLayout>>,
+        rasterizer: RasterizerState,
+        primitive_type: MTLPrimitiveType,
+        threads_per_object_threadgroup: MTLSize,
+        threads_per_mesh_threadgroup: MTLSize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            render_pipeline_state,
+            depth_stencil_state,
+            binding_layout,
+            rasterizer,
+            primitive_type,
+            threads_per_object_threadgroup,
+            threads_per_mesh_threadgroup,
+        })
+    }
+
+    pub fn binding_layout(&self) -> Option<&Arc<MetalBindingLayout>> {
+        self.binding_layout.as_ref()
+    }
+}
@@ -1,14 +1,107 @@
-use crate::Error;
+use crate::backend::metal::{MetalCommandList, MetalSwapchain};
+use crate::{Error, QueueType};
 use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
-use objc2_metal::MTL4CommandQueue;
+use objc2_metal::{MTL4CommandBuffer, MTL4CommandQueue, MTLSharedEvent};
+use std::ptr::NonNull;
+use std::sync::Mutex;
 
 pub struct MetalQueue {
-    queue: Retained<ProtocolObject<dyn MTL4CommandQueue>>,
+    pub(crate) queue: Retained<ProtocolObject<dyn MTL4CommandQueue>>,
+    queue_type: QueueType,
+
+    event: Retained<ProtocolObject<dyn MTLSharedEvent>>,
+    event_value: Mutex<u64>,
 }
 
 impl MetalQueue {
-    pub fn new(queue: Retained<ProtocolObject<dyn MTL4CommandQueue>>) -> Result<Self, Error> {
-        Ok(Self { queue })
+    pub fn new(
+        queue: Retained<ProtocolObject<dyn MTL4CommandQueue>>,
+        queue_type: QueueType,
+        event: Retained<ProtocolObject<dyn MTLSharedEvent>>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            queue,
+            queue_type,
+            event,
+            event_value: Mutex::new(0),
+        })
+    }
+
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    pub fn submit(
+        &self,
+        command_lists: &[&MetalCommandList],
+        waits: &[(&MetalQueue, u64)],
+    ) -> Result<u64, Error> {
+        let mut command_buffers = command_lists
+            .iter()
+            .map(|command_list| {
+                NonNull::from(&*command_list.command_buffer)
+                    as NonNull<ProtocolObject<dyn MTL4CommandBuffer>>
+            })
+            .collect::<Vec<_>>();
+
+        let mut event_value = self.event_value.lock().unwrap();
+
+        for (queue, value) in waits {
+            self.queue
+                .waitForEvent_value(ProtocolObject::from_ref(&*queue.event), *value);
+        }
+
+        if let Some(command_buffers) = NonNull::new(command_buffers.as_mut_ptr()) {
+            unsafe {
+                self.queue
+                    .commit_count(command_buffers, command_lists.len())
+            };
+        }
+
+        *event_value += 1;
+        self.queue
+            .signalEvent_value(ProtocolObject::from_ref(&*self.event), *event_value);
+
+        let signal_value = *event_value;
+        drop(event_value);
+
+        if let Some(command_list) = command_lists.first() {
+            command_list.device.collect_garbage()?;
+        }
+
+        Ok(signal_value)
+    }
+
+    pub fn present(&self, swapchain: &MetalSwapchain) -> Result<(), Error> {
+        if self.queue_type != QueueType::Direct {
+            return Err(Error::Unsupported(
+                "Presentation is only supported on the direct queue",
+            ));
+        }
+
+        swapchain.present(self)
+    }
+
+    pub fn wait_for_value(&self, value: u64) -> Result<(), Error> {
+        if !self.event.waitUntilSignaledValue_timeoutMS(value, u64::MAX) {
+            return Err(Error::MetalError("Timed out waiting for queue".to_owned()));
+        }
+
+        Ok(())
+    }
+
+    pub fn submitted_value(&self) -> u64 {
+        *self.event_value.lock().unwrap()
+    }
+
+    pub fn completed_value(&self) -> Result<u64, Error> {
+        Ok(self.event.signaledValue())
+    }
+
+    pub fn wait_idle(&self) -> Result<(), Error> {
+        let event_value = *self.event_value.lock().unwrap();
+
+        self.wait_for_value(event_value)
     }
 }
@@ -0,0 +1,26 @@
+use crate::{Error, SamplerDesc};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::MTLSamplerState;
+
+pub struct MetalSampler {
+    pub(crate) sampler_state: Retained<ProtocolObject<dyn MTLSamplerState>>,
+
+    desc: SamplerDesc,
+}
+
+impl MetalSampler {
+    pub fn new(
+        sampler_state: Retained<ProtocolObject<dyn MTLSamplerState>>,
+        desc: SamplerDesc,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            sampler_state,
+            desc,
+        })
+    }
+
+    pub fn desc(&self) -> &SamplerDesc {
+        &self.desc
+    }
+}
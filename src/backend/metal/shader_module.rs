@@ -1,4 +1,4 @@
-use crate::Error;
+use crate::{Error, ShaderKind, ShaderReflection};
 use metal_irconverter::sys::IRResourceLocation;
 use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
@@ -6,19 +6,44 @@ use objc2_metal::{MTLFunction, MTLLibrary};
 
 pub struct MetalShaderModule {
     library: Retained<ProtocolObject<dyn MTLLibrary>>,
-    function: Retained<ProtocolObject<dyn MTLFunction>>,
+    pub(crate) function: Retained<ProtocolObject<dyn MTLFunction>>,
     locations: Vec<IRResourceLocation>,
+
+    kind: ShaderKind,
+    pub(crate) threads_per_threadgroup: [u32; 3],
+    reflection: ShaderReflection,
+    reflected: bool,
 }
 impl MetalShaderModule {
     pub fn new(
         library: Retained<ProtocolObject<dyn MTLLibrary>>,
         function: Retained<ProtocolObject<dyn MTLFunction>>,
         locations: Vec<IRResourceLocation>,
+        kind: ShaderKind,
+        threads_per_threadgroup: [u32; 3],
+        reflection: ShaderReflection,
+        reflected: bool,
     ) -> Result<Self, Error> {
         Ok(Self {
             library,
             function,
             locations,
+            kind,
+            threads_per_threadgroup,
+            reflection,
+            reflected,
         })
     }
+
+    pub fn kind(&self) -> ShaderKind {
+        self.kind
+    }
+
+    pub fn reflection(&self) -> &ShaderReflection {
+        &self.reflection
+    }
+
+    pub fn is_reflected(&self) -> bool {
+        self.reflected
+    }
 }
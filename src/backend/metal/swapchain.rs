@@ -0,0 +1,245 @@
+use crate::backend::metal::*;
+use crate::{
+    ColorSpace, Error, Format, PresentMode, SwapchainDesc, SwapchainImage, Texture, TextureDesc,
+    TextureDimension, TextureUsage, WindowHandle,
+};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_core_foundation::CGSize;
+use objc2_core_graphics::{
+    CGColorSpace, kCGColorSpaceExtendedLinearSRGB, kCGColorSpaceITUR_2100_PQ, kCGColorSpaceSRGB,
+};
+use objc2_metal::{MTL4CommandQueue, MTLDrawable};
+use objc2_quartz_core::{CAMetalDrawable, CAMetalLayer};
+use sdl3::sys::metal::{
+    SDL_Metal_CreateView, SDL_Metal_DestroyView, SDL_Metal_GetLayer, SDL_MetalView,
+};
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+pub struct MetalSwapchain {
+    device: Arc<MetalDevice>,
+
+    view: Option<SDL_MetalView>,
+    layer: Retained<CAMetalLayer>,
+
+    format: Format,
+    color_space: ColorSpace,
+    present_mode: PresentMode,
+
+    state: Mutex<MetalSwapchainState>,
+}
+
+struct MetalSwapchainState {
+    width: u32,
+    height: u32,
+    resized: bool,
+
+    current_drawable: Option<Retained<ProtocolObject<dyn CAMetalDrawable>>>,
+}
+
+impl MetalSwapchain {
+    pub fn new(device: Arc<MetalDevice>, desc: &SwapchainDesc) -> Result<Self, Error> {
+        let (view, layer) = match desc.window_handle {
+            WindowHandle::Sdl(sdl_window) => {
+                let view = unsafe { SDL_Metal_CreateView(sdl_window.raw()) };
+
+                if view.is_null() {
+                    return Err(Error::MetalError("Failed to create metal view".to_owned()));
+                }
+
+                let Some(layer) =
+                    (unsafe { Retained::retain(SDL_Metal_GetLayer(view) as *mut CAMetalLayer) })
+                else {
+                    unsafe { SDL_Metal_DestroyView(view) };
+
+                    return Err(Error::MetalError("Failed to get metal layer".to_owned()));
+                };
+
+                (Some(view), layer)
+            }
+            WindowHandle::Headless => (None, CAMetalLayer::new()),
+        };
+
+        let (format, color_space) = match desc.color_space {
+            ColorSpace::SrgbNonLinear => (
+                match desc.format {
+                    Format::B8G8R8A8Unorm | Format::B8G8R8A8Srgb => desc.format,
+                    _ => Format::B8G8R8A8Srgb,
+                },
+                ColorSpace::SrgbNonLinear,
+            ),
+            ColorSpace::Hdr10St2084 => (Format::A2B10G10R10Unorm, ColorSpace::Hdr10St2084),
+            ColorSpace::ExtendedSrgbLinear => {
+                (Format::R16G16B16A16Float, ColorSpace::ExtendedSrgbLinear)
+            }
+        };
+
+        let color_space_name = unsafe {
+            match color_space {
+                ColorSpace::SrgbNonLinear => kCGColorSpaceSRGB,
+                ColorSpace::Hdr10St2084 => kCGColorSpaceITUR_2100_PQ,
+                ColorSpace::ExtendedSrgbLinear => kCGColorSpaceExtendedLinearSRGB,
+            }
+        };
+
+        layer.setDevice(Some(&device.physical_device.mtl_device));
+        layer.setPixelFormat(mtl_pixel_format(format));
+        layer.setColorspace(CGColorSpace::with_name(Some(color_space_name)).as_deref());
+        layer.setWantsExtendedDynamicRangeContent(color_space != ColorSpace::SrgbNonLinear);
+        layer.setFramebufferOnly(false);
+        layer.setMaximumDrawableCount(desc.image_count.clamp(2, 3) as _);
+        layer.setDrawableSize(CGSize::new(desc.width as _, desc.height as _));
+
+        #[cfg(target_os = "macos")]
+        let present_mode = match desc.present_mode {
+            PresentMode::Immediate => PresentMode::Immediate,
+            _ => PresentMode::Fifo,
+        };
+
+        #[cfg(not(target_os = "macos"))]
+        let present_mode = PresentMode::Fifo;
+
+        #[cfg(target_os = "macos")]
+        layer.setDisplaySyncEnabled(present_mode != PresentMode::Immediate);
+
+        device
+            .direct_queue
+            .queue
+            .addResidencySet(&layer.residencySet());
+
+        Ok(Self {
+            device,
+            view,
+            layer,
+            format,
+            color_space,
+            present_mode,
+            state: Mutex::new(MetalSwapchainState {
+                width: desc.width,
+                height: desc.height,
+                resized: false,
+                current_drawable: None,
+            }),
+        })
+    }
+
+    pub fn acquire_next_image(&self) -> Result<Option<SwapchainImage>, Error> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.width == 0 || state.height == 0 {
+            return Ok(None);
+        }
+
+        let drawable = self
+            .layer
+            .nextDrawable()
+            .ok_or_else(|| Error::MetalError("Failed to acquire drawable".to_owned()))?;
+
+        self.device
+            .direct_queue
+            .queue
+            .waitForDrawable(ProtocolObject::from_ref(&*drawable));
+
+        let texture = Texture::Metal(MetalTexture::from_drawable(
+            self.device.clone(),
+            drawable.texture(),
+            TextureDesc {
+                name: Cow::Borrowed("Swapchain drawable"),
+                dimension: TextureDimension::D2,
+                width: state.width,
+                height: state.height,
+                depth: 1,
+                array_layers: 1,
+                mip_levels: 1,
+                sample_count: 1,
+                format: self.format,
+                usage: TextureUsage::RENDER_TARGET
+                    | TextureUsage::TRANSFER_SRC
+                    | TextureUsage::TRANSFER_DST,
+            },
+        ));
+
+        state.current_drawable = Some(drawable);
+
+        Ok(Some(SwapchainImage {
+            texture: Arc::new(texture),
+            width: state.width,
+            height: state.height,
+            resized: std::mem::take(&mut state.resized),
+        }))
+    }
+
+    pub(crate) fn present(&self, queue: &MetalQueue) -> Result<(), Error> {
+        let drawable = self
+            .state
+            .lock()
+            .unwrap()
+            .current_drawable
+            .take()
+            .ok_or(Error::Unsupported("No swapchain image was acquired"))?;
+
+        queue
+            .queue
+            .signalDrawable(ProtocolObject::from_ref(&*drawable));
+        drawable.present();
+
+        Ok(())
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.width == width && state.height == height {
+            return;
+        }
+
+        state.width = width;
+        state.height = height;
+        state.resized = true;
+
+        if width != 0 && height != 0 {
+            self.layer
+                .setDrawableSize(CGSize::new(width as _, height as _));
+        }
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn width(&self) -> u32 {
+        self.state.lock().unwrap().width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.state.lock().unwrap().height
+    }
+
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    pub fn image_count(&self) -> u32 {
+        self.layer.maximumDrawableCount() as u32
+    }
+}
+
+impl Drop for MetalSwapchain {
+    fn drop(&mut self) {
+        let _ = self.device.direct_queue.wait_idle();
+
+        self.device
+            .direct_queue
+            .queue
+            .removeResidencySet(&self.layer.residencySet());
+
+        if let Some(view) = self.view {
+            unsafe { SDL_Metal_DestroyView(view) };
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::backend::metal::{MetalDevice, MetalGarbage};
+use crate::{Error, TextureDesc};
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_metal::{MTLResidencySet, MTLTexture};
+use std::sync::Arc;
+
+pub struct MetalTexture {
+    device: Arc<MetalDevice>,
+
+    pub(crate) texture: Retained<ProtocolObject<dyn MTLTexture>>,
+
+    desc: TextureDesc,
+
+    resident: bool,
+}
+
+impl MetalTexture {
+    pub fn new(
+        device: Arc<MetalDevice>,
+        texture: Retained<ProtocolObject<dyn MTLTexture>>,
+        desc: TextureDesc,
+    ) -> Result<Self, Error> {
+        device
+            .residency_set
+            .addAllocation(ProtocolObject::from_ref(&*texture));
+        device.residency_set.commit();
+
+        Ok(Self {
+            device,
+            texture,
+            desc,
+            resident: true,
+        })
+    }
+
+    pub(crate) fn from_drawable(
+        device: Arc<MetalDevice>,
+        texture: Retained<ProtocolObject<dyn MTLTexture>>,
+        desc: TextureDesc,
+    ) -> Self {
+        Self {
+            device,
+            texture,
+            desc,
+            resident: false,
+        }
+    }
+
+    pub fn desc(&self) -> &TextureDesc {
+        &self.desc
+    }
+}
+
+impl Drop for MetalTexture {
+    fn drop(&mut self) {
+        let texture = self.texture.clone();
+
+        self.device.destroy_deferred(if self.resident {
+            MetalGarbage::Allocation(ProtocolObject::from_retained(texture))
+        } else {
+            MetalGarbage::Object(Box::new(texture))
+        });
+    }
+}
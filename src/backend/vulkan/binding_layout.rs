@@ -0,0 +1,205 @@
+use crate::backend::vulkan::*;
+use crate::{BindingLayoutDesc, Error};
+use ash::vk;
+use std::sync::Arc;
+
+pub struct VulkanBindingLayout {
+    device: Arc<VulkanDevice>,
+
+    desc: BindingLayoutDesc,
+
+    pub(crate) set_layouts: Vec<vk::DescriptorSetLayout>,
+    pub(crate) pipeline_layout: vk::PipelineLayout,
+    pub(crate) push_constant_stages: vk::ShaderStageFlags,
+
+    pub(crate) static_samplers: Vec<vk::Sampler>,
+    pub(crate) embedded_sampler_sets: Vec<u32>,
+}
+
+impl VulkanBindingLayout {
+    pub fn new(device: Arc<VulkanDevice>, desc: &BindingLayoutDesc) -> Result<Self, Error> {
+        if let Some(push_constants) = &desc.push_constants {
+            let max_push_constants_size = device
+                .physical_device
+                .properties
+                .limits
+                .max_push_constants_size;
+
+            if push_constants.size > max_push_constants_size || push_constants.size % 4 != 0 {
+                return Err(Error::Unsupported(
+                    "Push constant size must be a multiple of 4 and within the device limit",
+                ));
+            }
+        }
+
+        let bindless_set_layout = if desc.bindless {
+            let bindless = device.bindless.as_ref().ok_or(Error::Unsupported(
+                "Bindless descriptors are not supported by this device",
+            ))?;
+
+            if desc.table_count() > BINDLESS_DESCRIPTOR_SET {
+                return Err(Error::Unsupported(
+                    "Bindless binding layouts reserve descriptor space 3",
+                ));
+            }
+
+            Some(bindless.set_layout)
+        } else {
+            None
+        };
+
+        let mut binding_layout = Self {
+            device,
+            desc: desc.clone(),
+            set_layouts: Vec::new(),
+            pipeline_layout: vk::PipelineLayout::null(),
+            push_constant_stages: desc
+                .push_constants
+                .map_or(vk::ShaderStageFlags::empty(), |push_constants| {
+                    vk_shader_stages(push_constants.stages)
+                }),
+            static_samplers: Vec::new(),
+            embedded_sampler_sets: Vec::new(),
+        };
+
+        let descriptor_buffer = binding_layout.device.descriptor_buffer_device.is_some();
+        let set_layout_flags = if descriptor_buffer {
+            vk::DescriptorSetLayoutCreateFlags::DESCRIPTOR_BUFFER_EXT
+        } else {
+            vk::DescriptorSetLayoutCreateFlags::empty()
+        };
+
+        for static_sampler in &desc.static_samplers {
+            let sampler = binding_layout
+                .device
+                .create_sampler_handle(&static_sampler.sampler)?;
+            binding_layout.static_samplers.push(sampler);
+        }
+
+        for space in 0..desc.table_count() {
+            let mut bindings = desc
+                .tables
+                .get(space as usize)
+                .map(|table| {
+                    table
+                        .bindings
+                        .iter()
+                        .map(|binding| {
+                            vk::DescriptorSetLayoutBinding::default()
+                                .binding(binding.binding)
+                                .descriptor_type(vk_descriptor_type(binding.descriptor_type))
+                                .descriptor_count(binding.count)
+                                .stage_flags(vk_shader_stages(binding.stages))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            for (static_sampler, sampler) in desc
+                .static_samplers
+                .iter()
+                .zip(&binding_layout.static_samplers)
+                .filter(|(static_sampler, _)| static_sampler.space == space)
+            {
+                bindings.push(
+                    vk::DescriptorSetLayoutBinding::default()
+                        .binding(static_sampler.binding)
+                        .descriptor_type(vk::DescriptorType::SAMPLER)
+                        .stage_flags(vk_shader_stages(static_sampler.stages))
+                        .immutable_samplers(std::slice::from_ref(sampler)),
+                );
+            }
+
+            let mut flags = set_layout_flags;
+
+            let samplers_only = !bindings.is_empty()
+                && bindings
+                    .iter()
+                    .all(|binding| !binding.p_immutable_samplers.is_null());
+
+            if descriptor_buffer && samplers_only {
+                flags |= vk::DescriptorSetLayoutCreateFlags::EMBEDDED_IMMUTABLE_SAMPLERS_EXT;
+                binding_layout.embedded_sampler_sets.push(space);
+            }
+
+            let set_layout = unsafe {
+                binding_layout.device.device.create_descriptor_set_layout(
+                    &vk::DescriptorSetLayoutCreateInfo::default()
+                        .flags(flags)
+                        .bindings(&bindings),
+                    None,
+                )
+            }?;
+            binding_layout.set_layouts.push(set_layout);
+        }
+
+        if let Some(bindless_set_layout) = bindless_set_layout {
+            while binding_layout.set_layouts.len() < BINDLESS_DESCRIPTOR_SET as usize {
+                let set_layout = unsafe {
+                    binding_layout.device.device.create_descriptor_set_layout(
+                        &vk::DescriptorSetLayoutCreateInfo::default().flags(set_layout_flags),
+                        None,
+                    )
+                }?;
+                binding_layout.set_layouts.push(set_layout);
+            }
+
+            binding_layout.set_layouts.push(bindless_set_layout);
+        }
+
+        let push_constant_ranges = desc
+            .push_constants
+            .iter()
+            .map(|push_constants| {
+                vk::PushConstantRange::default()
+                    .stage_flags(binding_layout.push_constant_stages)
+                    .offset(0)
+                    .size(push_constants.size)
+            })
+            .collect::<Vec<_>>();
+
+        binding_layout.pipeline_layout = unsafe {
+            binding_layout.device.device.create_pipeline_layout(
+                &vk::PipelineLayoutCreateInfo::default()
+                    .set_layouts(&binding_layout.set_layouts)
+                    .push_constant_ranges(&push_constant_ranges),
+                None,
+            )
+        }?;
+
+        Ok(binding_layout)
+    }
+
+    pub fn desc(&self) -> &BindingLayoutDesc {
+        &self.desc
+    }
+}
+
+impl Drop for VulkanBindingLayout {
+    fn drop(&mut self) {
+        if self.pipeline_layout != vk::PipelineLayout::null() {
+            self.device
+                .destroy_deferred(VulkanGarbage::PipelineLayout(self.pipeline_layout));
+        }
+
+        let bindless_set_layout = self
+            .device
+            .bindless
+            .as_ref()
+            .map(|bindless| bindless.set_layout);
+
+        for set_layout in self
+            .set_layouts
+            .drain(..)
+            .filter(|set_layout| Some(*set_layout) != bindless_set_layout)
+        {
+            self.device
+                .destroy_deferred(VulkanGarbage::DescriptorSetLayout(set_layout));
+        }
+
+        for sampler in self.static_samplers.drain(..) {
+            self.device
+                .destroy_deferred(VulkanGarbage::Sampler(sampler));
+        }
+    }
+}
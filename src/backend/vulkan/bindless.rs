@@ -0,0 +1,404 @@
+use crate::backend::vulkan::{VulkanDescriptor, VulkanDescriptorBuffer, VulkanDevice};
+use crate::bindless::DescriptorIndexAllocator;
+use crate::{BindlessResource, DescriptorIndex, Error};
+use ash::vk;
+use std::sync::Mutex;
+
+pub(crate) const BINDLESS_DESCRIPTOR_SET: u32 = 3;
+
+const RESOURCE_BINDING: u32 = 0;
+const SAMPLER_BINDING: u32 = 1;
+
+const RESOURCE_CAPACITY: u32 = 1 << 16;
+const SAMPLER_CAPACITY: u32 = 2048;
+
+pub(crate) struct VulkanBindlessHeap {
+    pub(crate) set_layout: vk::DescriptorSetLayout,
+    pub(crate) storage: VulkanBindlessStorage,
+
+    uniform_buffers_supported: bool,
+
+    resources: Mutex<DescriptorIndexAllocator>,
+    samplers: Mutex<DescriptorIndexAllocator>,
+}
+
+pub(crate) enum VulkanBindlessStorage {
+    DescriptorSet {
+        pool: vk::DescriptorPool,
+        set: vk::DescriptorSet,
+    },
+    DescriptorBuffer {
+        buffer: VulkanDescriptorBuffer,
+        resource_offset: u64,
+        resource_stride: u64,
+        sampler_offset: u64,
+    },
+}
+
+impl VulkanBindlessHeap {
+    pub(crate) fn new(device: &VulkanDevice) -> Result<Self, Error> {
+        let uniform_buffers_supported = device.physical_device.bindless_uniform_buffers_supported;
+
+        let mut resource_types = vec![
+            vk::DescriptorType::SAMPLED_IMAGE,
+            vk::DescriptorType::STORAGE_IMAGE,
+            vk::DescriptorType::STORAGE_BUFFER,
+        ];
+
+        if uniform_buffers_supported {
+            resource_types.push(vk::DescriptorType::UNIFORM_BUFFER);
+        }
+
+        let mutable_type_lists = [
+            vk::MutableDescriptorTypeListEXT::default().descriptor_types(&resource_types),
+            vk::MutableDescriptorTypeListEXT::default(),
+        ];
+
+        let bindings = [
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(RESOURCE_BINDING)
+                .descriptor_type(vk::DescriptorType::MUTABLE_EXT)
+                .descriptor_count(RESOURCE_CAPACITY)
+                .stage_flags(vk::ShaderStageFlags::ALL),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(SAMPLER_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLER)
+                .descriptor_count(SAMPLER_CAPACITY)
+                .stage_flags(vk::ShaderStageFlags::ALL),
+        ];
+
+        let mut mutable_type_create_info = vk::MutableDescriptorTypeCreateInfoEXT::default()
+            .mutable_descriptor_type_lists(&mutable_type_lists);
+
+        if let Some(descriptor_buffer_device) = &device.descriptor_buffer_device {
+            let set_layout = unsafe {
+                device.device.create_descriptor_set_layout(
+                    &vk::DescriptorSetLayoutCreateInfo::default()
+                        .flags(vk::DescriptorSetLayoutCreateFlags::DESCRIPTOR_BUFFER_EXT)
+                        .bindings(&bindings)
+                        .push_next(&mut mutable_type_create_info),
+                    None,
+                )
+            }?;
+
+            let (size, resource_offset, sampler_offset) = unsafe {
+                (
+                    descriptor_buffer_device.get_descriptor_set_layout_size(set_layout),
+                    descriptor_buffer_device
+                        .get_descriptor_set_layout_binding_offset(set_layout, RESOURCE_BINDING),
+                    descriptor_buffer_device
+                        .get_descriptor_set_layout_binding_offset(set_layout, SAMPLER_BINDING),
+                )
+            };
+
+            let resource_stride = resource_types
+                .iter()
+                .map(|descriptor_type| device.descriptor_size(*descriptor_type))
+                .max()
+                .unwrap_or(0);
+
+            let buffer = match VulkanDescriptorBuffer::new(device, size) {
+                Ok(buffer) => buffer,
+                Err(err) => {
+                    unsafe {
+                        device
+                            .device
+                            .destroy_descriptor_set_layout(set_layout, None)
+                    };
+                    return Err(err);
+                }
+            };
+
+            return Ok(Self {
+                set_layout,
+                storage: VulkanBindlessStorage::DescriptorBuffer {
+                    buffer,
+                    resource_offset,
+                    resource_stride,
+                    sampler_offset,
+                },
+                uniform_buffers_supported,
+                resources: Mutex::new(DescriptorIndexAllocator::new(RESOURCE_CAPACITY)),
+                samplers: Mutex::new(DescriptorIndexAllocator::new(SAMPLER_CAPACITY)),
+            });
+        }
+
+        let binding_flags = [vk::DescriptorBindingFlags::PARTIALLY_BOUND
+            | vk::DescriptorBindingFlags::UPDATE_AFTER_BIND; 2];
+
+        let mut binding_flags_create_info =
+            vk::DescriptorSetLayoutBindingFlagsCreateInfo::default().binding_flags(&binding_flags);
+
+        let set_layout = unsafe {
+            device.device.create_descriptor_set_layout(
+                &vk::DescriptorSetLayoutCreateInfo::default()
+                    .flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL)
+                    .bindings(&bindings)
+                    .push_next(&mut binding_flags_create_info)
+                    .push_next(&mut mutable_type_create_info),
+                None,
+            )
+        }?;
+
+        let pool_sizes = [
+            vk::DescriptorPoolSize::default()
+                .ty(vk::DescriptorType::MUTABLE_EXT)
+                .descriptor_count(RESOURCE_CAPACITY),
+            vk::DescriptorPoolSize::default()
+                .ty(vk::DescriptorType::SAMPLER)
+                .descriptor_count(SAMPLER_CAPACITY),
+        ];
+
+        let mut mutable_type_create_info = vk::MutableDescriptorTypeCreateInfoEXT::default()
+            .mutable_descriptor_type_lists(&mutable_type_lists);
+
+        let pool = match unsafe {
+            device.device.create_descriptor_pool(
+                &vk::DescriptorPoolCreateInfo::default()
+                    .flags(vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND)
+                    .max_sets(1)
+                    .pool_sizes(&pool_sizes)
+                    .push_next(&mut mutable_type_create_info),
+                None,
+            )
+        } {
+            Ok(pool) => pool,
+            Err(err) => {
+                unsafe {
+                    device
+                        .device
+                        .destroy_descriptor_set_layout(set_layout, None)
+                };
+                return Err(err.into());
+            }
+        };
+
+        let set = match unsafe {
+            device.device.allocate_descriptor_sets(
+                &vk::DescriptorSetAllocateInfo::default()
+                    .descriptor_pool(pool)
+                    .set_layouts(std::slice::from_ref(&set_layout)),
+            )
+        } {
+            Ok(sets) => sets[0],
+            Err(err) => {
+                unsafe {
+                    device.device.destroy_descriptor_pool(pool, None);
+                    device
+                        .device
+                        .destroy_descriptor_set_layout(set_layout, None);
+                }
+                return Err(err.into());
+            }
+        };
+
+        Ok(Self {
+            set_layout,
+            storage: VulkanBindlessStorage::DescriptorSet { pool, set },
+            uniform_buffers_supported,
+            resources: Mutex::new(DescriptorIndexAllocator::new(RESOURCE_CAPACITY)),
+            samplers: Mutex::new(DescriptorIndexAllocator::new(SAMPLER_CAPACITY)),
+        })
+    }
+
+    pub(crate) fn allocate_resource(
+        &self,
+        device: &VulkanDevice,
+        resource: BindlessResource,
+        completed_values: [u64; 3],
+    ) -> Result<DescriptorIndex, Error> {
+        if matches!(resource, BindlessResource::UniformBuffer { .. })
+            && !self.uniform_buffers_supported
+        {
+            return Err(Error::Unsupported(
+                "Bindless uniform buffers are not supported by this device",
+            ));
+        }
+
+        let index = self
+            .resources
+            .lock()
+            .unwrap()
+            .allocate(completed_values)
+            .ok_or(Error::Unsupported("Bindless resource heap is full"))?;
+
+        match &self.storage {
+            VulkanBindlessStorage::DescriptorSet { set, .. } => {
+                let write = vk::WriteDescriptorSet::default()
+                    .dst_set(*set)
+                    .dst_binding(RESOURCE_BINDING)
+                    .dst_array_element(index.index());
+
+                let (descriptor_type, buffer_info, image_info) = match resource {
+                    BindlessResource::UniformBuffer {
+                        buffer,
+                        offset,
+                        size,
+                    }
+                    | BindlessResource::StorageBuffer {
+                        buffer,
+                        offset,
+                        size,
+                    } => (
+                        if matches!(resource, BindlessResource::UniformBuffer { .. }) {
+                            vk::DescriptorType::UNIFORM_BUFFER
+                        } else {
+                            vk::DescriptorType::STORAGE_BUFFER
+                        },
+                        Some(
+                            vk::DescriptorBufferInfo::default()
+                                .buffer(buffer.as_vulkan_buffer().handle)
+                                .offset(offset)
+                                .range(size),
+                        ),
+                        None,
+                    ),
+                    BindlessResource::SampledTexture(texture) => (
+                        vk::DescriptorType::SAMPLED_IMAGE,
+                        None,
+                        Some(
+                            vk::DescriptorImageInfo::default()
+                                .image_view(texture.as_vulkan_texture().view)
+                                .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL),
+                        ),
+                    ),
+                    BindlessResource::StorageTexture(texture) => (
+                        vk::DescriptorType::STORAGE_IMAGE,
+                        None,
+                        Some(
+                            vk::DescriptorImageInfo::default()
+                                .image_view(texture.as_vulkan_texture().view)
+                                .image_layout(vk::ImageLayout::GENERAL),
+                        ),
+                    ),
+                };
+
+                let write = write.descriptor_type(descriptor_type);
+                let write = match (&buffer_info, &image_info) {
+                    (Some(buffer_info), _) => write.buffer_info(std::slice::from_ref(buffer_info)),
+                    (_, Some(image_info)) => write.image_info(std::slice::from_ref(image_info)),
+                    _ => write,
+                };
+
+                unsafe { device.device.update_descriptor_sets(&[write], &[]) };
+            }
+            VulkanBindlessStorage::DescriptorBuffer {
+                buffer,
+                resource_offset,
+                resource_stride,
+                ..
+            } => {
+                let descriptor = match resource {
+                    BindlessResource::UniformBuffer {
+                        buffer,
+                        offset,
+                        size,
+                    } => VulkanDescriptor::UniformBuffer(
+                        vk::DescriptorAddressInfoEXT::default()
+                            .address(buffer.as_vulkan_buffer().address + offset)
+                            .range(size),
+                    ),
+                    BindlessResource::StorageBuffer {
+                        buffer,
+                        offset,
+                        size,
+                    } => VulkanDescriptor::StorageBuffer(
+                        vk::DescriptorAddressInfoEXT::default()
+                            .address(buffer.as_vulkan_buffer().address + offset)
+                            .range(size),
+                    ),
+                    BindlessResource::SampledTexture(texture) => VulkanDescriptor::SampledImage(
+                        vk::DescriptorImageInfo::default()
+                            .image_view(texture.as_vulkan_texture().view)
+                            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL),
+                    ),
+                    BindlessResource::StorageTexture(texture) => VulkanDescriptor::StorageImage(
+                        vk::DescriptorImageInfo::default()
+                            .image_view(texture.as_vulkan_texture().view)
+                            .image_layout(vk::ImageLayout::GENERAL),
+                    ),
+                };
+
+                buffer.write_descriptor(
+                    device,
+                    resource_offset + index.index() as u64 * resource_stride,
+                    &descriptor,
+                );
+            }
+        }
+
+        Ok(index)
+    }
+
+    pub(crate) fn allocate_sampler(
+        &self,
+        device: &VulkanDevice,
+        sampler: vk::Sampler,
+        completed_values: [u64; 3],
+    ) -> Result<DescriptorIndex, Error> {
+        let index = self
+            .samplers
+            .lock()
+            .unwrap()
+            .allocate(completed_values)
+            .ok_or(Error::Unsupported("Bindless sampler heap is full"))?;
+
+        match &self.storage {
+            VulkanBindlessStorage::DescriptorSet { set, .. } => {
+                let image_info = vk::DescriptorImageInfo::default().sampler(sampler);
+
+                unsafe {
+                    device.device.update_descriptor_sets(
+                        &[vk::WriteDescriptorSet::default()
+                            .dst_set(*set)
+                            .dst_binding(SAMPLER_BINDING)
+                            .dst_array_element(index.index())
+                            .descriptor_type(vk::DescriptorType::SAMPLER)
+                            .image_info(std::slice::from_ref(&image_info))],
+                        &[],
+                    )
+                };
+            }
+            VulkanBindlessStorage::DescriptorBuffer {
+                buffer,
+                sampler_offset,
+                ..
+            } => {
+                buffer.write_descriptor(
+                    device,
+                    sampler_offset
+                        + index.index() as u64
+                            * device.descriptor_size(vk::DescriptorType::SAMPLER),
+                    &VulkanDescriptor::Sampler(sampler),
+                );
+            }
+        }
+
+        Ok(index)
+    }
+
+    pub(crate) fn free_resource(&self, index: DescriptorIndex, submitted_values: [u64; 3]) {
+        self.resources.lock().unwrap().free(index, submitted_values);
+    }
+
+    pub(crate) fn free_sampler(&self, index: DescriptorIndex, submitted_values: [u64; 3]) {
+        self.samplers.lock().unwrap().free(index, submitted_values);
+    }
+
+    pub(crate) unsafe fn destroy(&self, device: &VulkanDevice) {
+        unsafe {
+            match &self.storage {
+                VulkanBindlessStorage::DescriptorSet { pool, .. } => {
+                    device.device.destroy_descriptor_pool(*pool, None)
+                }
+                VulkanBindlessStorage::DescriptorBuffer { buffer, .. } => {
+                    buffer.destroy(&device.device)
+                }
+            }
+
+            device
+                .device
+                .destroy_descriptor_set_layout(self.set_layout, None);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::backend::vulkan::{
+    VulkanAllocation, VulkanDevice, VulkanGarbage, VulkanResourceLifetime, vk_resource_state,
+};
+use crate::{BufferUsage, Error, ResourceState};
+use ash::vk;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+
+pub struct VulkanBuffer {
+    pub(crate) handle: vk::Buffer,
+    pub(crate) lifetime: Arc<VulkanResourceLifetime>,
+
+    size: u64,
+    usage: BufferUsage,
+    mapped_ptr: Option<NonNull<u8>>,
+    pub(crate) address: vk::DeviceAddress,
+
+    pub(crate) state: Mutex<VulkanBufferState>,
+}
+
+// Buffers can be read by several stages between two writes, so a write has to wait on all of
+// them rather than only on the most recent read.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VulkanBufferState {
+    write: Option<(vk::PipelineStageFlags2, vk::AccessFlags2)>,
+    read_stages: vk::PipelineStageFlags2,
+    read_access: vk::AccessFlags2,
+}
+
+impl VulkanBufferState {
+    fn new() -> Self {
+        Self {
+            write: None,
+            read_stages: vk::PipelineStageFlags2::NONE,
+            read_access: vk::AccessFlags2::NONE,
+        }
+    }
+
+    // Returns the source scope a barrier into `new_state` has to wait on, if one is needed.
+    pub(crate) fn transition(
+        &mut self,
+        new_state: ResourceState,
+    ) -> Option<(vk::PipelineStageFlags2, vk::AccessFlags2)> {
+        let (stage_mask, access_mask, _) = vk_resource_state(new_state);
+
+        if new_state.is_write() {
+            let (write_stages, write_access) = self
+                .write
+                .unwrap_or((vk::PipelineStageFlags2::NONE, vk::AccessFlags2::NONE));
+            let src_stage_mask = write_stages | self.read_stages;
+            let src_access_mask = write_access | self.read_access;
+
+            *self = Self {
+                write: Some((stage_mask, access_mask)),
+                ..Self::new()
+            };
+
+            return (!src_stage_mask.is_empty()).then_some((src_stage_mask, src_access_mask));
+        }
+
+        let visible =
+            self.read_stages.contains(stage_mask) && self.read_access.contains(access_mask);
+
+        self.read_stages |= stage_mask;
+        self.read_access |= access_mask;
+
+        self.write.filter(|_| !visible)
+    }
+
+    pub(crate) fn reset(&mut self, new_state: ResourceState) {
+        let (stage_mask, access_mask, _) = vk_resource_state(new_state);
+
+        *self = if new_state.is_write() {
+            Self {
+                write: Some((stage_mask, access_mask)),
+                ..Self::new()
+            }
+        } else {
+            Self {
+                write: None,
+                read_stages: stage_mask,
+                read_access: access_mask,
+            }
+        };
+    }
+}
+
+unsafe impl Send for VulkanBuffer {}
+unsafe impl Sync for VulkanBuffer {}
+
+impl VulkanBuffer {
+    pub fn new(
+        device: Arc<VulkanDevice>,
+        handle: vk::Buffer,
+        allocation: VulkanAllocation,
+        size: u64,
+        usage: BufferUsage,
+        mapped_ptr: Option<NonNull<u8>>,
+    ) -> Result<Self, Error> {
+        let address = if device.buffer_device_address_required(usage) {
+            unsafe {
+                device.device.get_buffer_device_address(
+                    &vk::BufferDeviceAddressInfo::default().buffer(handle),
+                )
+            }
+        } else {
+            0
+        };
+
+        Ok(Self {
+            handle,
+            lifetime: VulkanResourceLifetime::new(device, allocation),
+            size,
+            usage,
+            mapped_ptr,
+            address,
+            state: Mutex::new(VulkanBufferState::new()),
+        })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn device_address(&self) -> Result<u64, Error> {
+        if !self.usage.contains(BufferUsage::DEVICE_ADDRESS) {
+            return Err(Error::Unsupported(
+                "Buffer was not created with BufferUsage::DEVICE_ADDRESS",
+            ));
+        }
+
+        Ok(self.address)
+    }
+
+    pub fn mapped_slice(&self) -> Option<&[u8]> {
+        self.mapped_ptr
+            .map(|ptr| unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.size as usize) })
+    }
+
+    pub fn mapped_slice_mut(&mut self) -> Option<&mut [u8]> {
+        self.mapped_ptr
+            .map(|ptr| unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), self.size as usize) })
+    }
+}
+
+impl Drop for VulkanBuffer {
+    fn drop(&mut self) {
+        self.lifetime.retire(VulkanGarbage::Buffer(self.handle));
+    }
+}
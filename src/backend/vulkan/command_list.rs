@@ -0,0 +1,1251 @@
+use crate::backend::vulkan::*;
+use crate::{
+    BufferBarrier, Error, IndexType, LoadOp, QueueTransfer, QueueType, Rect, RenderPassDesc,
+    ResourceState, TextureBarrier, TextureSubresourceRange, Viewport,
+};
+use ash::vk;
+use std::sync::Arc;
+
+pub struct VulkanCommandList {
+    pub(crate) device: Arc<VulkanDevice>,
+
+    queue_type: QueueType,
+
+    command_pool: vk::CommandPool,
+    pub(crate) command_buffer: vk::CommandBuffer,
+
+    binding_layout: Option<Arc<VulkanBindingLayout>>,
+    bind_point: vk::PipelineBindPoint,
+
+    descriptor_buffers: Vec<(vk::DeviceAddress, vk::BufferUsageFlags)>,
+    descriptor_buffer_offsets: Vec<Option<(vk::DeviceAddress, u64)>>,
+
+    referenced_resources: Vec<Arc<VulkanResourceLifetime>>,
+
+    automatic_barriers: bool,
+    in_render_pass: bool,
+}
+
+impl VulkanCommandList {
+    pub fn new(device: Arc<VulkanDevice>, queue_type: QueueType) -> Result<Self, Error> {
+        let command_pool = unsafe {
+            device.device.create_command_pool(
+                &vk::CommandPoolCreateInfo::default()
+                    .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+                    .queue_family_index(device.queue_family_index(queue_type)),
+                None,
+            )
+        }?;
+
+        let command_buffer = unsafe {
+            device.device.allocate_command_buffers(
+                &vk::CommandBufferAllocateInfo::default()
+                    .command_pool(command_pool)
+                    .level(vk::CommandBufferLevel::PRIMARY)
+                    .command_buffer_count(1),
+            )
+        }?[0];
+
+        Ok(Self {
+            device,
+            queue_type,
+            command_pool,
+            command_buffer,
+            binding_layout: None,
+            bind_point: vk::PipelineBindPoint::GRAPHICS,
+            descriptor_buffers: Vec::new(),
+            descriptor_buffer_offsets: Vec::new(),
+            referenced_resources: Vec::new(),
+            automatic_barriers: true,
+            in_render_pass: false,
+        })
+    }
+
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    pub fn begin(&mut self) -> Result<(), Error> {
+        self.binding_layout = None;
+        self.in_render_pass = false;
+        self.descriptor_buffers.clear();
+        self.descriptor_buffer_offsets.clear();
+        self.referenced_resources.clear();
+
+        unsafe {
+            self.device
+                .device
+                .reset_command_pool(self.command_pool, vk::CommandPoolResetFlags::empty())?;
+
+            self.device.device.begin_command_buffer(
+                self.command_buffer,
+                &vk::CommandBufferBeginInfo::default()
+                    .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> Result<(), Error> {
+        unsafe { self.device.device.end_command_buffer(self.command_buffer)? };
+
+        Ok(())
+    }
+
+    pub fn set_automatic_barriers(&mut self, enabled: bool) {
+        self.automatic_barriers = enabled;
+    }
+
+    pub fn transition_buffer(&mut self, buffer: &VulkanBuffer, new_state: ResourceState) {
+        self.reference(&buffer.lifetime);
+
+        let Some((src_stage_mask, src_access_mask)) =
+            buffer.state.lock().unwrap().transition(new_state)
+        else {
+            return;
+        };
+
+        let buffer_memory_barrier =
+            buffer_memory_barrier(buffer, ResourceState::Undefined, new_state)
+                .src_stage_mask(src_stage_mask)
+                .src_access_mask(src_access_mask);
+        self.pipeline_barrier(&[buffer_memory_barrier], &[]);
+    }
+
+    pub fn transition_texture(
+        &mut self,
+        texture: &VulkanTexture,
+        range: Option<TextureSubresourceRange>,
+        new_state: ResourceState,
+    ) {
+        self.transition_texture_internal(texture, range, new_state, false);
+    }
+
+    pub fn barrier(
+        &mut self,
+        buffer_barriers: &[BufferBarrier],
+        texture_barriers: &[TextureBarrier],
+    ) {
+        for barrier in buffer_barriers {
+            self.reference(&barrier.buffer.as_vulkan_buffer().lifetime);
+        }
+        for barrier in texture_barriers {
+            self.reference(&barrier.texture.as_vulkan_texture().lifetime);
+        }
+
+        let buffer_memory_barriers = buffer_barriers
+            .iter()
+            .filter_map(|barrier| {
+                let buffer = barrier.buffer.as_vulkan_buffer();
+                buffer.state.lock().unwrap().reset(barrier.new_state);
+
+                let buffer_memory_barrier =
+                    buffer_memory_barrier(buffer, barrier.old_state, barrier.new_state);
+
+                match self.queue_transfer_families(barrier.queue_transfer) {
+                    QueueFamilyTransfer::Skip => None,
+                    QueueFamilyTransfer::None => Some(buffer_memory_barrier),
+                    QueueFamilyTransfer::Release(src_family, dst_family) => Some(
+                        buffer_memory_barrier
+                            .src_queue_family_index(src_family)
+                            .dst_queue_family_index(dst_family)
+                            .dst_stage_mask(vk::PipelineStageFlags2::NONE)
+                            .dst_access_mask(vk::AccessFlags2::NONE),
+                    ),
+                    QueueFamilyTransfer::Acquire(src_family, dst_family) => Some(
+                        buffer_memory_barrier
+                            .src_queue_family_index(src_family)
+                            .dst_queue_family_index(dst_family)
+                            .src_stage_mask(vk::PipelineStageFlags2::NONE)
+                            .src_access_mask(vk::AccessFlags2::NONE),
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let image_memory_barriers = texture_barriers
+            .iter()
+            .filter_map(|barrier| {
+                let texture = barrier.texture.as_vulkan_texture();
+                let range = barrier
+                    .range
+                    .unwrap_or_else(|| texture.desc().full_subresource_range());
+
+                let mut states = texture.states.lock().unwrap();
+                for index in subresource_indices(texture, range) {
+                    states[index] = barrier.new_state;
+                }
+
+                let image_memory_barrier = image_memory_barrier(
+                    texture,
+                    range,
+                    barrier.old_state,
+                    barrier.new_state,
+                    barrier.discard_contents,
+                );
+
+                match self.queue_transfer_families(barrier.queue_transfer) {
+                    QueueFamilyTransfer::Skip => None,
+                    QueueFamilyTransfer::None => Some(image_memory_barrier),
+                    QueueFamilyTransfer::Release(src_family, dst_family) => Some(
+                        image_memory_barrier
+                            .src_queue_family_index(src_family)
+                            .dst_queue_family_index(dst_family)
+                            .dst_stage_mask(vk::PipelineStageFlags2::NONE)
+                            .dst_access_mask(vk::AccessFlags2::NONE),
+                    ),
+                    QueueFamilyTransfer::Acquire(src_family, dst_family) => Some(
+                        image_memory_barrier
+                            .src_queue_family_index(src_family)
+                            .dst_queue_family_index(dst_family)
+                            .src_stage_mask(vk::PipelineStageFlags2::NONE)
+                            .src_access_mask(vk::AccessFlags2::NONE),
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.pipeline_barrier(&buffer_memory_barriers, &image_memory_barriers);
+    }
+
+    pub fn begin_render_pass(&mut self, desc: &RenderPassDesc) {
+        for attachment in desc.color_attachments {
+            self.reference(&attachment.texture.as_vulkan_texture().lifetime);
+
+            if let Some(resolve_target) = attachment.resolve_target {
+                self.reference(&resolve_target.as_vulkan_texture().lifetime);
+            }
+        }
+        if let Some(attachment) = &desc.depth_stencil_attachment {
+            self.reference(&attachment.texture.as_vulkan_texture().lifetime);
+        }
+
+        if self.automatic_barriers {
+            for attachment in desc.color_attachments {
+                self.transition_texture_internal(
+                    attachment.texture.as_vulkan_texture(),
+                    None,
+                    ResourceState::RenderTarget,
+                    attachment.load_op != LoadOp::Load,
+                );
+
+                if let Some(resolve_target) = attachment.resolve_target {
+                    self.transition_texture_internal(
+                        resolve_target.as_vulkan_texture(),
+                        None,
+                        ResourceState::RenderTarget,
+                        true,
+                    );
+                }
+            }
+
+            if let Some(attachment) = &desc.depth_stencil_attachment {
+                self.transition_texture_internal(
+                    attachment.texture.as_vulkan_texture(),
+                    None,
+                    ResourceState::DepthStencilWrite,
+                    attachment.depth_load_op != LoadOp::Load
+                        && attachment.stencil_load_op != LoadOp::Load,
+                );
+            }
+        }
+
+        let color_attachments = desc
+            .color_attachments
+            .iter()
+            .map(|attachment| {
+                let mut attachment_info = vk::RenderingAttachmentInfo::default()
+                    .image_view(attachment.texture.as_vulkan_texture().view)
+                    .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                    .load_op(vk_attachment_load_op(attachment.load_op))
+                    .store_op(vk_attachment_store_op(attachment.store_op))
+                    .clear_value(vk::ClearValue {
+                        color: vk::ClearColorValue {
+                            float32: attachment.clear_value,
+                        },
+                    });
+
+                if let Some(resolve_target) = attachment.resolve_target {
+                    attachment_info = attachment_info
+                        .resolve_mode(vk::ResolveModeFlags::AVERAGE)
+                        .resolve_image_view(resolve_target.as_vulkan_texture().view)
+                        .resolve_image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+                }
+
+                attachment_info
+            })
+            .collect::<Vec<_>>();
+
+        let depth_attachment = desc.depth_stencil_attachment.as_ref().map(|attachment| {
+            vk::RenderingAttachmentInfo::default()
+                .image_view(attachment.texture.as_vulkan_texture().view)
+                .image_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
+                .load_op(vk_attachment_load_op(attachment.depth_load_op))
+                .store_op(vk_attachment_store_op(attachment.depth_store_op))
+                .clear_value(vk::ClearValue {
+                    depth_stencil: vk::ClearDepthStencilValue {
+                        depth: attachment.clear_depth,
+                        stencil: attachment.clear_stencil,
+                    },
+                })
+        });
+
+        let stencil_attachment = desc
+            .depth_stencil_attachment
+            .as_ref()
+            .filter(|attachment| attachment.texture.desc().format.is_stencil())
+            .map(|attachment| {
+                vk::RenderingAttachmentInfo::default()
+                    .image_view(attachment.texture.as_vulkan_texture().view)
+                    .image_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
+                    .load_op(vk_attachment_load_op(attachment.stencil_load_op))
+                    .store_op(vk_attachment_store_op(attachment.stencil_store_op))
+                    .clear_value(vk::ClearValue {
+                        depth_stencil: vk::ClearDepthStencilValue {
+                            depth: attachment.clear_depth,
+                            stencil: attachment.clear_stencil,
+                        },
+                    })
+            });
+
+        let render_area = desc.render_area;
+
+        let mut rendering_info = vk::RenderingInfo::default()
+            .render_area(vk::Rect2D {
+                offset: vk::Offset2D {
+                    x: render_area.x,
+                    y: render_area.y,
+                },
+                extent: vk::Extent2D {
+                    width: render_area.width,
+                    height: render_area.height,
+                },
+            })
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+
+        if let Some(depth_attachment) = &depth_attachment {
+            rendering_info = rendering_info.depth_attachment(depth_attachment);
+        }
+        if let Some(stencil_attachment) = &stencil_attachment {
+            rendering_info = rendering_info.stencil_attachment(stencil_attachment);
+        }
+
+        unsafe {
+            self.device
+                .device
+                .cmd_begin_rendering(self.command_buffer, &rendering_info)
+        };
+        self.in_render_pass = true;
+
+        self.set_viewport(&Viewport {
+            x: render_area.x as f32,
+            y: render_area.y as f32,
+            width: render_area.width as f32,
+            height: render_area.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        });
+        self.set_scissor(&render_area);
+    }
+
+    pub fn end_render_pass(&mut self) {
+        unsafe { self.device.device.cmd_end_rendering(self.command_buffer) };
+        self.in_render_pass = false;
+    }
+
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        let viewport = vk::Viewport {
+            x: viewport.x,
+            y: viewport.y,
+            width: viewport.width,
+            height: viewport.height,
+            min_depth: viewport.min_depth,
+            max_depth: viewport.max_depth,
+        };
+
+        unsafe {
+            self.device
+                .device
+                .cmd_set_viewport(self.command_buffer, 0, &[viewport])
+        };
+    }
+
+    pub fn set_scissor(&mut self, scissor: &Rect) {
+        let scissor = vk::Rect2D {
+            offset: vk::Offset2D {
+                x: scissor.x,
+                y: scissor.y,
+            },
+            extent: vk::Extent2D {
+                width: scissor.width,
+                height: scissor.height,
+            },
+        };
+
+        unsafe {
+            self.device
+                .device
+                .cmd_set_scissor(self.command_buffer, 0, &[scissor])
+        };
+    }
+
+    pub fn bind_pipeline(&mut self, pipeline: &VulkanPipeline) -> Result<(), Error> {
+        unsafe {
+            self.device.device.cmd_bind_pipeline(
+                self.command_buffer,
+                pipeline.bind_point,
+                pipeline.handle,
+            )
+        };
+
+        self.binding_layout = Some(pipeline.binding_layout.clone());
+        self.bind_point = pipeline.bind_point;
+
+        if let Some(descriptor_buffer_device) = &self.device.descriptor_buffer_device {
+            for set in &pipeline.binding_layout.embedded_sampler_sets {
+                unsafe {
+                    descriptor_buffer_device.cmd_bind_descriptor_buffer_embedded_samplers(
+                        self.command_buffer,
+                        pipeline.bind_point,
+                        pipeline.binding_layout.pipeline_layout,
+                        *set,
+                    )
+                };
+            }
+        }
+
+        let Some(bindless) = self
+            .device
+            .bindless
+            .as_ref()
+            .filter(|_| pipeline.binding_layout.desc().bindless)
+        else {
+            return Ok(());
+        };
+
+        match &bindless.storage {
+            VulkanBindlessStorage::DescriptorSet { set, .. } => {
+                unsafe {
+                    self.device.device.cmd_bind_descriptor_sets(
+                        self.command_buffer,
+                        pipeline.bind_point,
+                        pipeline.binding_layout.pipeline_layout,
+                        BINDLESS_DESCRIPTOR_SET,
+                        &[*set],
+                        &[],
+                    )
+                };
+
+                Ok(())
+            }
+            VulkanBindlessStorage::DescriptorBuffer { buffer, .. } => self
+                .set_descriptor_buffer_offset(
+                    pipeline.binding_layout.pipeline_layout,
+                    BINDLESS_DESCRIPTOR_SET,
+                    buffer.address,
+                    0,
+                ),
+        }
+    }
+
+    pub fn bind_descriptor_set(
+        &mut self,
+        descriptor_set: &VulkanDescriptorSet,
+    ) -> Result<(), Error> {
+        match &descriptor_set.storage {
+            VulkanDescriptorSetStorage::Set(handle) => {
+                unsafe {
+                    self.device.device.cmd_bind_descriptor_sets(
+                        self.command_buffer,
+                        self.bind_point,
+                        descriptor_set.binding_layout.pipeline_layout,
+                        descriptor_set.table(),
+                        &[*handle],
+                        &[],
+                    )
+                };
+
+                Ok(())
+            }
+            VulkanDescriptorSetStorage::Buffer { block, offset } => {
+                if descriptor_set
+                    .binding_layout
+                    .embedded_sampler_sets
+                    .contains(&descriptor_set.table())
+                {
+                    return Ok(());
+                }
+
+                self.set_descriptor_buffer_offset(
+                    descriptor_set.binding_layout.pipeline_layout,
+                    descriptor_set.table(),
+                    block.buffer.address,
+                    *offset,
+                )
+            }
+        }
+    }
+
+    pub fn set_push_constants(&mut self, data: &[u8]) -> Result<(), Error> {
+        let binding_layout = self.binding_layout.as_ref().ok_or(Error::Unsupported(
+            "A pipeline must be bound before setting push constants",
+        ))?;
+
+        let push_constants = binding_layout
+            .desc()
+            .push_constants
+            .ok_or(Error::Unsupported(
+                "The bound pipeline does not declare push constants",
+            ))?;
+
+        let max_push_constants_size = self
+            .device
+            .physical_device
+            .properties
+            .limits
+            .max_push_constants_size;
+
+        let max_size = push_constants.size.min(max_push_constants_size);
+
+        if data.len() % 4 != 0 || data.len() > max_size as usize {
+            return Err(Error::InvalidPushConstantSize(data.len(), max_size));
+        }
+
+        unsafe {
+            self.device.device.cmd_push_constants(
+                self.command_buffer,
+                binding_layout.pipeline_layout,
+                binding_layout.push_constant_stages,
+                0,
+                data,
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn bind_vertex_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: &[(&VulkanBuffer, u64)],
+    ) -> Result<(), Error> {
+        let vertex_buffers = buffers
+            .iter()
+            .map(|(buffer, _)| *buffer)
+            .collect::<Vec<_>>();
+        self.use_buffers(&vertex_buffers, ResourceState::VertexBuffer)?;
+
+        let handles = buffers
+            .iter()
+            .map(|(buffer, _)| buffer.handle)
+            .collect::<Vec<_>>();
+        let offsets = buffers
+            .iter()
+            .map(|(_, offset)| *offset)
+            .collect::<Vec<_>>();
+
+        unsafe {
+            self.device.device.cmd_bind_vertex_buffers(
+                self.command_buffer,
+                first_binding,
+                &handles,
+                &offsets,
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn bind_index_buffer(
+        &mut self,
+        buffer: &VulkanBuffer,
+        offset: u64,
+        index_type: IndexType,
+    ) -> Result<(), Error> {
+        self.use_buffers(&[buffer], ResourceState::IndexBuffer)?;
+
+        unsafe {
+            self.device.device.cmd_bind_index_buffer(
+                self.command_buffer,
+                buffer.handle,
+                offset,
+                vk_index_type(index_type),
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.device.device.cmd_draw(
+                self.command_buffer,
+                vertex_count,
+                instance_count,
+                first_vertex,
+                first_instance,
+            )
+        };
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.device.device.cmd_draw_indexed(
+                self.command_buffer,
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset,
+                first_instance,
+            )
+        };
+    }
+
+    pub fn draw_indirect(
+        &mut self,
+        buffer: &VulkanBuffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        self.use_buffers(&[buffer], ResourceState::IndirectArgument)?;
+
+        unsafe {
+            self.device.device.cmd_draw_indirect(
+                self.command_buffer,
+                buffer.handle,
+                offset,
+                draw_count,
+                stride,
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn draw_indexed_indirect(
+        &mut self,
+        buffer: &VulkanBuffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        self.use_buffers(&[buffer], ResourceState::IndirectArgument)?;
+
+        unsafe {
+            self.device.device.cmd_draw_indexed_indirect(
+                self.command_buffer,
+                buffer.handle,
+                offset,
+                draw_count,
+                stride,
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn draw_mesh_tasks(
+        &mut self,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    ) -> Result<(), Error> {
+        let device = self.device.clone();
+        let mesh_shader_device = mesh_shader_device(&device)?;
+
+        unsafe {
+            mesh_shader_device.cmd_draw_mesh_tasks(
+                self.command_buffer,
+                group_count_x,
+                group_count_y,
+                group_count_z,
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &VulkanBuffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        let device = self.device.clone();
+        let mesh_shader_device = mesh_shader_device(&device)?;
+
+        self.use_buffers(&[buffer], ResourceState::IndirectArgument)?;
+
+        unsafe {
+            mesh_shader_device.cmd_draw_mesh_tasks_indirect(
+                self.command_buffer,
+                buffer.handle,
+                offset,
+                draw_count,
+                stride,
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        buffer: &VulkanBuffer,
+        offset: u64,
+        count_buffer: &VulkanBuffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        let device = self.device.clone();
+        let mesh_shader_device = mesh_shader_device(&device)?;
+
+        if !device.physical_device.draw_indirect_count_supported {
+            return Err(Error::Unsupported(
+                "Indirect count draws are not supported by this device",
+            ));
+        }
+
+        self.use_buffers(&[buffer, count_buffer], ResourceState::IndirectArgument)?;
+
+        unsafe {
+            mesh_shader_device.cmd_draw_mesh_tasks_indirect_count(
+                self.command_buffer,
+                buffer.handle,
+                offset,
+                count_buffer.handle,
+                count_buffer_offset,
+                max_draw_count,
+                stride,
+            )
+        };
+
+        Ok(())
+    }
+
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        texture: &VulkanTexture,
+        mip_level: u32,
+        array_layer: u32,
+        buffer: &VulkanBuffer,
+        buffer_offset: u64,
+        bytes_per_row: u32,
+    ) {
+        self.reference(&texture.lifetime);
+        self.reference(&buffer.lifetime);
+
+        if self.automatic_barriers {
+            self.transition_texture_internal(
+                texture,
+                Some(TextureSubresourceRange {
+                    base_mip_level: mip_level,
+                    mip_level_count: 1,
+                    base_array_layer: array_layer,
+                    array_layer_count: 1,
+                }),
+                ResourceState::TransferSrc,
+                false,
+            );
+            self.transition_buffer(buffer, ResourceState::TransferDst);
+        }
+
+        let desc = texture.desc();
+
+        let aspect_mask = if desc.format.is_depth() {
+            vk::ImageAspectFlags::DEPTH
+        } else {
+            vk::ImageAspectFlags::COLOR
+        };
+
+        let region = vk::BufferImageCopy::default()
+            .buffer_offset(buffer_offset)
+            .buffer_row_length(bytes_per_row / desc.format.bytes_per_pixel())
+            .buffer_image_height(0)
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(aspect_mask)
+                    .mip_level(mip_level)
+                    .base_array_layer(array_layer)
+                    .layer_count(1),
+            )
+            .image_extent(vk::Extent3D {
+                width: (desc.width >> mip_level).max(1),
+                height: (desc.height >> mip_level).max(1),
+                depth: (desc.depth >> mip_level).max(1),
+            });
+
+        unsafe {
+            self.device.device.cmd_copy_image_to_buffer(
+                self.command_buffer,
+                texture.handle,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                buffer.handle,
+                &[region],
+            )
+        };
+    }
+}
+
+impl VulkanCommandList {
+    // Barriers cannot be recorded inside dynamic rendering, so buffers used by draws must already
+    // be in a compatible state when the render pass begins.
+    fn use_buffers(
+        &mut self,
+        buffers: &[&VulkanBuffer],
+        new_state: ResourceState,
+    ) -> Result<(), Error> {
+        for buffer in buffers {
+            self.reference(&buffer.lifetime);
+        }
+
+        if !self.automatic_barriers {
+            return Ok(());
+        }
+
+        if self.in_render_pass
+            && buffers.iter().any(|buffer| {
+                let mut state = *buffer.state.lock().unwrap();
+                state.transition(new_state).is_some()
+            })
+        {
+            return Err(Error::Unsupported(
+                "Buffers used inside a render pass must be transitioned before begin_render_pass",
+            ));
+        }
+
+        for buffer in buffers {
+            self.transition_buffer(buffer, new_state);
+        }
+
+        Ok(())
+    }
+
+    fn reference(&mut self, lifetime: &Arc<VulkanResourceLifetime>) {
+        if !self
+            .referenced_resources
+            .last()
+            .is_some_and(|last| Arc::ptr_eq(last, lifetime))
+        {
+            self.referenced_resources.push(lifetime.clone());
+        }
+    }
+
+    fn transition_texture_internal(
+        &mut self,
+        texture: &VulkanTexture,
+        range: Option<TextureSubresourceRange>,
+        new_state: ResourceState,
+        discard_contents: bool,
+    ) {
+        self.reference(&texture.lifetime);
+
+        let range = range.unwrap_or_else(|| texture.desc().full_subresource_range());
+
+        let mut states = texture.states.lock().unwrap();
+        let old_states = subresource_indices(texture, range)
+            .map(|index| std::mem::replace(&mut states[index], new_state))
+            .collect::<Vec<_>>();
+        drop(states);
+
+        let needs_barrier = |old_state: ResourceState| {
+            old_state != new_state || new_state.is_write() || discard_contents
+        };
+
+        let image_memory_barriers = if old_states.iter().all(|state| *state == old_states[0]) {
+            if !needs_barrier(old_states[0]) {
+                return;
+            }
+
+            vec![image_memory_barrier(
+                texture,
+                range,
+                old_states[0],
+                new_state,
+                discard_contents,
+            )]
+        } else {
+            let mut image_memory_barriers = Vec::new();
+            let mut old_states = old_states.into_iter();
+
+            for array_layer in
+                range.base_array_layer..range.base_array_layer + range.array_layer_count
+            {
+                for mip_level in range.base_mip_level..range.base_mip_level + range.mip_level_count
+                {
+                    let old_state = old_states.next().unwrap();
+
+                    if !needs_barrier(old_state) {
+                        continue;
+                    }
+
+                    image_memory_barriers.push(image_memory_barrier(
+                        texture,
+                        TextureSubresourceRange {
+                            base_mip_level: mip_level,
+                            mip_level_count: 1,
+                            base_array_layer: array_layer,
+                            array_layer_count: 1,
+                        },
+                        old_state,
+                        new_state,
+                        discard_contents,
+                    ));
+                }
+            }
+
+            image_memory_barriers
+        };
+
+        self.pipeline_barrier(&[], &image_memory_barriers);
+    }
+
+    fn set_descriptor_buffer_offset(
+        &mut self,
+        pipeline_layout: vk::PipelineLayout,
+        set: u32,
+        address: vk::DeviceAddress,
+        offset: u64,
+    ) -> Result<(), Error> {
+        let Some(descriptor_buffer_device) = &self.device.descriptor_buffer_device else {
+            return Ok(());
+        };
+
+        let buffer_index = match self
+            .descriptor_buffers
+            .iter()
+            .position(|(buffer_address, _)| *buffer_address == address)
+        {
+            Some(buffer_index) => buffer_index,
+            None => {
+                let max_bindings = self
+                    .device
+                    .physical_device
+                    .descriptor_buffer_properties
+                    .max_descriptor_buffer_bindings as usize;
+
+                if self.descriptor_buffers.len() == max_bindings {
+                    // Only keep the buffers still referenced by the other bound sets.
+                    let live_addresses = self
+                        .descriptor_buffer_offsets
+                        .iter()
+                        .enumerate()
+                        .filter(|(other_set, _)| *other_set != set as usize)
+                        .filter_map(|(_, other_offset)| other_offset.map(|(address, _)| address))
+                        .collect::<Vec<_>>();
+
+                    self.descriptor_buffers
+                        .retain(|(address, _)| live_addresses.contains(address));
+
+                    if self.descriptor_buffers.len() == max_bindings {
+                        return Err(Error::Unsupported(
+                            "Bound descriptor sets use more descriptor buffers than the device supports",
+                        ));
+                    }
+                }
+
+                self.descriptor_buffers
+                    .push((address, descriptor_buffer_usage()));
+
+                let binding_infos = self
+                    .descriptor_buffers
+                    .iter()
+                    .map(|(address, usage)| {
+                        vk::DescriptorBufferBindingInfoEXT::default()
+                            .address(*address)
+                            .usage(*usage)
+                    })
+                    .collect::<Vec<_>>();
+
+                unsafe {
+                    descriptor_buffer_device
+                        .cmd_bind_descriptor_buffers(self.command_buffer, &binding_infos)
+                };
+
+                // Rebinding the descriptor buffers invalidates every offset set so far.
+                for (other_set, other_offset) in self.descriptor_buffer_offsets.iter().enumerate() {
+                    let Some((other_address, other_offset)) = other_offset else {
+                        continue;
+                    };
+
+                    let Some(other_index) = self
+                        .descriptor_buffers
+                        .iter()
+                        .position(|(address, _)| address == other_address)
+                    else {
+                        continue;
+                    };
+
+                    if other_set != set as usize {
+                        unsafe {
+                            descriptor_buffer_device.cmd_set_descriptor_buffer_offsets(
+                                self.command_buffer,
+                                self.bind_point,
+                                pipeline_layout,
+                                other_set as u32,
+                                &[other_index as u32],
+                                &[*other_offset],
+                            )
+                        };
+                    }
+                }
+
+                self.descriptor_buffers.len() - 1
+            }
+        } as u32;
+
+        unsafe {
+            descriptor_buffer_device.cmd_set_descriptor_buffer_offsets(
+                self.command_buffer,
+                self.bind_point,
+                pipeline_layout,
+                set,
+                &[buffer_index],
+                &[offset],
+            )
+        };
+
+        if self.descriptor_buffer_offsets.len() <= set as usize {
+            self.descriptor_buffer_offsets
+                .resize(set as usize + 1, None);
+        }
+        self.descriptor_buffer_offsets[set as usize] = Some((address, offset));
+
+        Ok(())
+    }
+
+    fn queue_transfer_families(
+        &self,
+        queue_transfer: Option<QueueTransfer>,
+    ) -> QueueFamilyTransfer {
+        let Some(queue_transfer) = queue_transfer else {
+            return QueueFamilyTransfer::None;
+        };
+
+        let (src_queue, dst_queue) = match queue_transfer {
+            QueueTransfer::Release { dst_queue } => (self.queue_type, dst_queue),
+            QueueTransfer::Acquire { src_queue } => (src_queue, self.queue_type),
+        };
+
+        let src_family = self.device.queue_family_index(src_queue);
+        let dst_family = self.device.queue_family_index(dst_queue);
+
+        // Within one family the acquiring side records an ordinary barrier on its own.
+        match queue_transfer {
+            QueueTransfer::Release { .. } if src_family == dst_family => QueueFamilyTransfer::Skip,
+            QueueTransfer::Acquire { .. } if src_family == dst_family => QueueFamilyTransfer::None,
+            QueueTransfer::Release { .. } => QueueFamilyTransfer::Release(src_family, dst_family),
+            QueueTransfer::Acquire { .. } => QueueFamilyTransfer::Acquire(src_family, dst_family),
+        }
+    }
+
+    fn pipeline_barrier(
+        &mut self,
+        buffer_memory_barriers: &[vk::BufferMemoryBarrier2],
+        image_memory_barriers: &[vk::ImageMemoryBarrier2],
+    ) {
+        if buffer_memory_barriers.is_empty() && image_memory_barriers.is_empty() {
+            return;
+        }
+
+        debug_assert!(
+            !self.in_render_pass,
+            "Resource transitions must be recorded outside of a render pass"
+        );
+
+        let queue_flags = self.device.queue_flags(self.queue_type);
+
+        let buffer_memory_barriers = buffer_memory_barriers
+            .iter()
+            .map(|barrier| {
+                let (src_stage_mask, src_access_mask) =
+                    clamp_to_queue(barrier.src_stage_mask, barrier.src_access_mask, queue_flags);
+                let (dst_stage_mask, dst_access_mask) =
+                    clamp_to_queue(barrier.dst_stage_mask, barrier.dst_access_mask, queue_flags);
+
+                barrier
+                    .src_stage_mask(src_stage_mask)
+                    .src_access_mask(src_access_mask)
+                    .dst_stage_mask(dst_stage_mask)
+                    .dst_access_mask(dst_access_mask)
+            })
+            .collect::<Vec<_>>();
+
+        let image_memory_barriers = image_memory_barriers
+            .iter()
+            .map(|barrier| {
+                let (src_stage_mask, src_access_mask) =
+                    clamp_to_queue(barrier.src_stage_mask, barrier.src_access_mask, queue_flags);
+                let (dst_stage_mask, dst_access_mask) =
+                    clamp_to_queue(barrier.dst_stage_mask, barrier.dst_access_mask, queue_flags);
+
+                barrier
+                    .src_stage_mask(src_stage_mask)
+                    .src_access_mask(src_access_mask)
+                    .dst_stage_mask(dst_stage_mask)
+                    .dst_access_mask(dst_access_mask)
+            })
+            .collect::<Vec<_>>();
+
+        unsafe {
+            self.device.device.cmd_pipeline_barrier2(
+                self.command_buffer,
+                &vk::DependencyInfo::default()
+                    .buffer_memory_barriers(&buffer_memory_barriers)
+                    .image_memory_barriers(&image_memory_barriers),
+            )
+        };
+    }
+}
+
+enum QueueFamilyTransfer {
+    None,
+    Skip,
+    Release(u32, u32),
+    Acquire(u32, u32),
+}
+
+// Stages from other queues are already covered by the semaphore wait that orders this queue after
+// them, so unsupported stages widen to ALL_COMMANDS and their accesses are dropped.
+fn clamp_to_queue(
+    stage_mask: vk::PipelineStageFlags2,
+    access_mask: vk::AccessFlags2,
+    queue_flags: vk::QueueFlags,
+) -> (vk::PipelineStageFlags2, vk::AccessFlags2) {
+    if queue_flags.contains(vk::QueueFlags::GRAPHICS) {
+        return (stage_mask, access_mask);
+    }
+
+    let mut supported_stages = vk::PipelineStageFlags2::TOP_OF_PIPE
+        | vk::PipelineStageFlags2::BOTTOM_OF_PIPE
+        | vk::PipelineStageFlags2::ALL_TRANSFER
+        | vk::PipelineStageFlags2::COPY
+        | vk::PipelineStageFlags2::CLEAR
+        | vk::PipelineStageFlags2::HOST
+        | vk::PipelineStageFlags2::ALL_COMMANDS;
+    let mut supported_access = vk::AccessFlags2::TRANSFER_READ
+        | vk::AccessFlags2::TRANSFER_WRITE
+        | vk::AccessFlags2::HOST_READ
+        | vk::AccessFlags2::HOST_WRITE
+        | vk::AccessFlags2::MEMORY_READ
+        | vk::AccessFlags2::MEMORY_WRITE;
+
+    if queue_flags.contains(vk::QueueFlags::COMPUTE) {
+        supported_stages |=
+            vk::PipelineStageFlags2::DRAW_INDIRECT | vk::PipelineStageFlags2::COMPUTE_SHADER;
+        supported_access |= vk::AccessFlags2::INDIRECT_COMMAND_READ
+            | vk::AccessFlags2::UNIFORM_READ
+            | vk::AccessFlags2::SHADER_SAMPLED_READ
+            | vk::AccessFlags2::SHADER_STORAGE_READ
+            | vk::AccessFlags2::SHADER_STORAGE_WRITE;
+    }
+
+    let stage_mask = if stage_mask.intersects(!supported_stages) {
+        vk::PipelineStageFlags2::ALL_COMMANDS
+    } else {
+        stage_mask
+    };
+
+    (stage_mask, access_mask & supported_access)
+}
+
+fn mesh_shader_device(device: &VulkanDevice) -> Result<&ash::ext::mesh_shader::Device, Error> {
+    device.mesh_shader_device.as_ref().ok_or(Error::Unsupported(
+        "Mesh shaders are not supported by this device",
+    ))
+}
+
+fn buffer_memory_barrier(
+    buffer: &VulkanBuffer,
+    old_state: ResourceState,
+    new_state: ResourceState,
+) -> vk::BufferMemoryBarrier2<'static> {
+    let (src_stage_mask, src_access_mask, _) = vk_resource_state(old_state);
+    let (dst_stage_mask, dst_access_mask, _) = vk_resource_state(new_state);
+
+    vk::BufferMemoryBarrier2::default()
+        .src_stage_mask(src_stage_mask)
+        .src_access_mask(src_access_mask)
+        .dst_stage_mask(dst_stage_mask)
+        .dst_access_mask(dst_access_mask)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .buffer(buffer.handle)
+        .offset(0)
+        .size(vk::WHOLE_SIZE)
+}
+
+fn image_memory_barrier(
+    texture: &VulkanTexture,
+    range: TextureSubresourceRange,
+    old_state: ResourceState,
+    new_state: ResourceState,
+    discard_contents: bool,
+) -> vk::ImageMemoryBarrier2<'static> {
+    let (src_stage_mask, src_access_mask, old_layout) = vk_resource_state(old_state);
+    let (dst_stage_mask, dst_access_mask, new_layout) = vk_resource_state(new_state);
+
+    let old_layout = if discard_contents {
+        vk::ImageLayout::UNDEFINED
+    } else {
+        old_layout
+    };
+
+    vk::ImageMemoryBarrier2::default()
+        .src_stage_mask(src_stage_mask)
+        .src_access_mask(src_access_mask)
+        .dst_stage_mask(dst_stage_mask)
+        .dst_access_mask(dst_access_mask)
+        .old_layout(old_layout)
+        .new_layout(new_layout)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(texture.handle)
+        .subresource_range(
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(vk_image_aspect(texture.desc().format))
+                .base_mip_level(range.base_mip_level)
+                .level_count(range.mip_level_count)
+                .base_array_layer(range.base_array_layer)
+                .layer_count(range.array_layer_count),
+        )
+}
+
+fn subresource_indices(
+    texture: &VulkanTexture,
+    range: TextureSubresourceRange,
+) -> impl Iterator<Item = usize> {
+    let mip_levels = texture.desc().mip_levels;
+
+    (range.base_array_layer..range.base_array_layer + range.array_layer_count).flat_map(
+        move |array_layer| {
+            (range.base_mip_level..range.base_mip_level + range.mip_level_count)
+                .map(move |mip_level| (array_layer * mip_levels + mip_level) as usize)
+        },
+    )
+}
+
+impl Drop for VulkanCommandList {
+    fn drop(&mut self) {
+        self.device
+            .destroy_deferred(VulkanGarbage::CommandPool(self.command_pool));
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{
+    AddressMode, BlendFactor, BlendOp, BorderColor, BufferUsage, ColorSpace, ColorWriteMask,
+    CompareOp, CullMode, DescriptorType, Filter, Format, FrontFace, IndexType, LoadOp, PolygonMode,
+    PresentMode, PrimitiveTopology, ResourceState, ShaderKind, ShaderStages, StencilOp, StoreOp,
+    TextureDimension, TextureUsage, VertexStepMode,
+};
+use ash::vk;
+
+pub(crate) fn vk_format(format: Format) -> vk::Format {
+    match format {
+        Format::R8Unorm => vk::Format::R8_UNORM,
+        Format::R8G8Unorm => vk::Format::R8G8_UNORM,
+        Format::R8G8B8A8Unorm => vk::Format::R8G8B8A8_UNORM,
+        Format::R8G8B8A8Srgb => vk::Format::R8G8B8A8_SRGB,
+        Format::B8G8R8A8Unorm => vk::Format::B8G8R8A8_UNORM,
+        Format::B8G8R8A8Srgb => vk::Format::B8G8R8A8_SRGB,
+        Format::A2B10G10R10Unorm => vk::Format::A2B10G10R10_UNORM_PACK32,
+        Format::R16Float => vk::Format::R16_SFLOAT,
+        Format::R16G16Float => vk::Format::R16G16_SFLOAT,
+        Format::R16G16B16A16Float => vk::Format::R16G16B16A16_SFLOAT,
+        Format::R32Uint => vk::Format::R32_UINT,
+        Format::R32Float => vk::Format::R32_SFLOAT,
+        Format::R32G32Float => vk::Format::R32G32_SFLOAT,
+        Format::R32G32B32Float => vk::Format::R32G32B32_SFLOAT,
+        Format::R32G32B32A32Float => vk::Format::R32G32B32A32_SFLOAT,
+        Format::D16Unorm => vk::Format::D16_UNORM,
+        Format::D32Float => vk::Format::D32_SFLOAT,
+        Format::D24UnormS8Uint => vk::Format::D24_UNORM_S8_UINT,
+        Format::D32FloatS8Uint => vk::Format::D32_SFLOAT_S8_UINT,
+    }
+}
+
+pub(crate) fn vk_shader_stage(kind: ShaderKind) -> vk::ShaderStageFlags {
+    match kind {
+        ShaderKind::Vertex => vk::ShaderStageFlags::VERTEX,
+        ShaderKind::Task => vk::ShaderStageFlags::TASK_EXT,
+        ShaderKind::Mesh => vk::ShaderStageFlags::MESH_EXT,
+        ShaderKind::Fragment => vk::ShaderStageFlags::FRAGMENT,
+        ShaderKind::Compute => vk::ShaderStageFlags::COMPUTE,
+    }
+}
+
+pub(crate) fn vk_shader_stages(stages: ShaderStages) -> vk::ShaderStageFlags {
+    if stages.contains(ShaderStages::ALL) {
+        return vk::ShaderStageFlags::ALL;
+    }
+
+    let mut flags = vk::ShaderStageFlags::empty();
+
+    for kind in [
+        ShaderKind::Vertex,
+        ShaderKind::Task,
+        ShaderKind::Mesh,
+        ShaderKind::Fragment,
+        ShaderKind::Compute,
+    ] {
+        if stages.contains(kind.into()) {
+            flags |= vk_shader_stage(kind);
+        }
+    }
+
+    flags
+}
+
+pub(crate) fn vk_descriptor_type(descriptor_type: DescriptorType) -> vk::DescriptorType {
+    match descriptor_type {
+        DescriptorType::UniformBuffer => vk::DescriptorType::UNIFORM_BUFFER,
+        DescriptorType::StorageBuffer | DescriptorType::ReadOnlyStorageBuffer => {
+            vk::DescriptorType::STORAGE_BUFFER
+        }
+        DescriptorType::SampledTexture => vk::DescriptorType::SAMPLED_IMAGE,
+        DescriptorType::StorageTexture => vk::DescriptorType::STORAGE_IMAGE,
+        DescriptorType::UniformTexelBuffer => vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
+        DescriptorType::StorageTexelBuffer => vk::DescriptorType::STORAGE_TEXEL_BUFFER,
+        DescriptorType::Sampler => vk::DescriptorType::SAMPLER,
+    }
+}
+
+pub(crate) fn vk_filter(filter: Filter) -> vk::Filter {
+    match filter {
+        Filter::Nearest => vk::Filter::NEAREST,
+        Filter::Linear => vk::Filter::LINEAR,
+    }
+}
+
+pub(crate) fn vk_sampler_mipmap_mode(filter: Filter) -> vk::SamplerMipmapMode {
+    match filter {
+        Filter::Nearest => vk::SamplerMipmapMode::NEAREST,
+        Filter::Linear => vk::SamplerMipmapMode::LINEAR,
+    }
+}
+
+pub(crate) fn vk_sampler_address_mode(address_mode: AddressMode) -> vk::SamplerAddressMode {
+    match address_mode {
+        AddressMode::Repeat => vk::SamplerAddressMode::REPEAT,
+        AddressMode::MirroredRepeat => vk::SamplerAddressMode::MIRRORED_REPEAT,
+        AddressMode::ClampToEdge => vk::SamplerAddressMode::CLAMP_TO_EDGE,
+        AddressMode::ClampToBorder => vk::SamplerAddressMode::CLAMP_TO_BORDER,
+    }
+}
+
+pub(crate) fn vk_border_color(border_color: BorderColor) -> vk::BorderColor {
+    match border_color {
+        BorderColor::TransparentBlack => vk::BorderColor::FLOAT_TRANSPARENT_BLACK,
+        BorderColor::OpaqueBlack => vk::BorderColor::FLOAT_OPAQUE_BLACK,
+        BorderColor::OpaqueWhite => vk::BorderColor::FLOAT_OPAQUE_WHITE,
+    }
+}
+
+pub(crate) fn vk_sample_count(sample_count: u32) -> vk::SampleCountFlags {
+    match sample_count {
+        2 => vk::SampleCountFlags::TYPE_2,
+        4 => vk::SampleCountFlags::TYPE_4,
+        8 => vk::SampleCountFlags::TYPE_8,
+        16 => vk::SampleCountFlags::TYPE_16,
+        _ => vk::SampleCountFlags::TYPE_1,
+    }
+}
+
+pub(crate) fn vk_buffer_usage(usage: BufferUsage) -> vk::BufferUsageFlags {
+    let mut flags = vk::BufferUsageFlags::empty();
+
+    if usage.contains(BufferUsage::TRANSFER_SRC) {
+        flags |= vk::BufferUsageFlags::TRANSFER_SRC;
+    }
+    if usage.contains(BufferUsage::TRANSFER_DST) {
+        flags |= vk::BufferUsageFlags::TRANSFER_DST;
+    }
+    if usage.contains(BufferUsage::UNIFORM) {
+        flags |= vk::BufferUsageFlags::UNIFORM_BUFFER;
+    }
+    if usage.contains(BufferUsage::STORAGE) {
+        flags |= vk::BufferUsageFlags::STORAGE_BUFFER;
+    }
+    if usage.contains(BufferUsage::INDEX) {
+        flags |= vk::BufferUsageFlags::INDEX_BUFFER;
+    }
+    if usage.contains(BufferUsage::VERTEX) {
+        flags |= vk::BufferUsageFlags::VERTEX_BUFFER;
+    }
+    if usage.contains(BufferUsage::INDIRECT) {
+        flags |= vk::BufferUsageFlags::INDIRECT_BUFFER;
+    }
+    if usage.contains(BufferUsage::DEVICE_ADDRESS) {
+        flags |= vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS;
+    }
+
+    flags
+}
+
+pub(crate) fn vk_image_usage(usage: TextureUsage) -> vk::ImageUsageFlags {
+    let mut flags = vk::ImageUsageFlags::empty();
+
+    if usage.contains(TextureUsage::TRANSFER_SRC) {
+        flags |= vk::ImageUsageFlags::TRANSFER_SRC;
+    }
+    if usage.contains(TextureUsage::TRANSFER_DST) {
+        flags |= vk::ImageUsageFlags::TRANSFER_DST;
+    }
+    if usage.contains(TextureUsage::SAMPLED) {
+        flags |= vk::ImageUsageFlags::SAMPLED;
+    }
+    if usage.contains(TextureUsage::STORAGE) {
+        flags |= vk::ImageUsageFlags::STORAGE;
+    }
+    if usage.contains(TextureUsage::RENDER_TARGET) {
+        flags |= vk::ImageUsageFlags::COLOR_ATTACHMENT;
+    }
+    if usage.contains(TextureUsage::DEPTH_STENCIL) {
+        flags |= vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT;
+    }
+
+    flags
+}
+
+pub(crate) fn vk_image_type(dimension: TextureDimension) -> vk::ImageType {
+    match dimension {
+        TextureDimension::D1 => vk::ImageType::TYPE_1D,
+        TextureDimension::D2 => vk::ImageType::TYPE_2D,
+        TextureDimension::D3 => vk::ImageType::TYPE_3D,
+    }
+}
+
+pub(crate) fn vk_image_view_type(
+    dimension: TextureDimension,
+    array_layers: u32,
+) -> vk::ImageViewType {
+    match (dimension, array_layers > 1) {
+        (TextureDimension::D1, false) => vk::ImageViewType::TYPE_1D,
+        (TextureDimension::D1, true) => vk::ImageViewType::TYPE_1D_ARRAY,
+        (TextureDimension::D2, false) => vk::ImageViewType::TYPE_2D,
+        (TextureDimension::D2, true) => vk::ImageViewType::TYPE_2D_ARRAY,
+        (TextureDimension::D3, _) => vk::ImageViewType::TYPE_3D,
+    }
+}
+
+pub(crate) fn vk_image_aspect(format: Format) -> vk::ImageAspectFlags {
+    if format.is_stencil() {
+        vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL
+    } else if format.is_depth() {
+        vk::ImageAspectFlags::DEPTH
+    } else {
+        vk::ImageAspectFlags::COLOR
+    }
+}
+
+pub(crate) fn vk_resource_state(
+    state: ResourceState,
+) -> (vk::PipelineStageFlags2, vk::AccessFlags2, vk::ImageLayout) {
+    match state {
+        ResourceState::Undefined => (
+            vk::PipelineStageFlags2::NONE,
+            vk::AccessFlags2::NONE,
+            vk::ImageLayout::UNDEFINED,
+        ),
+        ResourceState::General => (
+            vk::PipelineStageFlags2::ALL_COMMANDS,
+            vk::AccessFlags2::MEMORY_READ | vk::AccessFlags2::MEMORY_WRITE,
+            vk::ImageLayout::GENERAL,
+        ),
+        ResourceState::VertexBuffer => (
+            vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT,
+            vk::AccessFlags2::VERTEX_ATTRIBUTE_READ,
+            vk::ImageLayout::UNDEFINED,
+        ),
+        ResourceState::IndexBuffer => (
+            vk::PipelineStageFlags2::INDEX_INPUT,
+            vk::AccessFlags2::INDEX_READ,
+            vk::ImageLayout::UNDEFINED,
+        ),
+        ResourceState::IndirectArgument => (
+            vk::PipelineStageFlags2::DRAW_INDIRECT,
+            vk::AccessFlags2::INDIRECT_COMMAND_READ,
+            vk::ImageLayout::UNDEFINED,
+        ),
+        ResourceState::UniformBuffer => (
+            vk::PipelineStageFlags2::ALL_COMMANDS,
+            vk::AccessFlags2::UNIFORM_READ,
+            vk::ImageLayout::UNDEFINED,
+        ),
+        ResourceState::ShaderResource => (
+            vk::PipelineStageFlags2::ALL_COMMANDS,
+            vk::AccessFlags2::SHADER_SAMPLED_READ | vk::AccessFlags2::SHADER_STORAGE_READ,
+            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        ),
+        ResourceState::UnorderedAccess => (
+            vk::PipelineStageFlags2::ALL_COMMANDS,
+            vk::AccessFlags2::SHADER_STORAGE_READ | vk::AccessFlags2::SHADER_STORAGE_WRITE,
+            vk::ImageLayout::GENERAL,
+        ),
+        ResourceState::RenderTarget => (
+            vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            vk::AccessFlags2::COLOR_ATTACHMENT_READ | vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        ),
+        ResourceState::DepthStencilWrite => (
+            vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+                | vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+            vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ
+                | vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+        ),
+        ResourceState::DepthStencilRead => (
+            vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+                | vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS
+                | vk::PipelineStageFlags2::FRAGMENT_SHADER,
+            vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ | vk::AccessFlags2::SHADER_SAMPLED_READ,
+            vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+        ),
+        ResourceState::TransferSrc => (
+            vk::PipelineStageFlags2::ALL_TRANSFER,
+            vk::AccessFlags2::TRANSFER_READ,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        ),
+        ResourceState::TransferDst => (
+            vk::PipelineStageFlags2::ALL_TRANSFER,
+            vk::AccessFlags2::TRANSFER_WRITE,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+        ),
+        ResourceState::Present => (
+            vk::PipelineStageFlags2::NONE,
+            vk::AccessFlags2::NONE,
+            vk::ImageLayout::PRESENT_SRC_KHR,
+        ),
+    }
+}
+
+pub(crate) fn vk_attachment_load_op(load_op: LoadOp) -> vk::AttachmentLoadOp {
+    match load_op {
+        LoadOp::Load => vk::AttachmentLoadOp::LOAD,
+        LoadOp::Clear => vk::AttachmentLoadOp::CLEAR,
+        LoadOp::DontCare => vk::AttachmentLoadOp::DONT_CARE,
+    }
+}
+
+pub(crate) fn vk_attachment_store_op(store_op: StoreOp) -> vk::AttachmentStoreOp {
+    match store_op {
+        StoreOp::Store => vk::AttachmentStoreOp::STORE,
+        StoreOp::DontCare => vk::AttachmentStoreOp::DONT_CARE,
+    }
+}
+
+pub(crate) fn vk_vertex_input_rate(step_mode: VertexStepMode) -> vk::VertexInputRate {
+    match step_mode {
+        VertexStepMode::Vertex => vk::VertexInputRate::VERTEX,
+        VertexStepMode::Instance => vk::VertexInputRate::INSTANCE,
+    }
+}
+
+pub(crate) fn vk_primitive_topology(
+    primitive_topology: PrimitiveTopology,
+) -> vk::PrimitiveTopology {
+    match primitive_topology {
+        PrimitiveTopology::PointList => vk::PrimitiveTopology::POINT_LIST,
+        PrimitiveTopology::LineList => vk::PrimitiveTopology::LINE_LIST,
+        PrimitiveTopology::LineStrip => vk::PrimitiveTopology::LINE_STRIP,
+        PrimitiveTopology::TriangleList => vk::PrimitiveTopology::TRIANGLE_LIST,
+        PrimitiveTopology::TriangleStrip => vk::PrimitiveTopology::TRIANGLE_STRIP,
+    }
+}
+
+pub(crate) fn vk_index_type(index_type: IndexType) -> vk::IndexType {
+    match index_type {
+        IndexType::U16 => vk::IndexType::UINT16,
+        IndexType::U32 => vk::IndexType::UINT32,
+    }
+}
+
+pub(crate) fn vk_polygon_mode(polygon_mode: PolygonMode) -> vk::PolygonMode {
+    match polygon_mode {
+        PolygonMode::Fill => vk::PolygonMode::FILL,
+        PolygonMode::Line => vk::PolygonMode::LINE,
+    }
+}
+
+pub(crate) fn vk_cull_mode(cull_mode: CullMode) -> vk::CullModeFlags {
+    match cull_mode {
+        CullMode::None => vk::CullModeFlags::NONE,
+        CullMode::Front => vk::CullModeFlags::FRONT,
+        CullMode::Back => vk::CullModeFlags::BACK,
+    }
+}
+
+pub(crate) fn vk_front_face(front_face: FrontFace) -> vk::FrontFace {
+    match front_face {
+        FrontFace::Clockwise => vk::FrontFace::CLOCKWISE,
+        FrontFace::CounterClockwise => vk::FrontFace::COUNTER_CLOCKWISE,
+    }
+}
+
+pub(crate) fn vk_compare_op(compare_op: CompareOp) -> vk::CompareOp {
+    match compare_op {
+        CompareOp::Never => vk::CompareOp::NEVER,
+        CompareOp::Less => vk::CompareOp::LESS,
+        CompareOp::Equal => vk::CompareOp::EQUAL,
+        CompareOp::LessOrEqual => vk::CompareOp::LESS_OR_EQUAL,
+        CompareOp::Greater => vk::CompareOp::GREATER,
+        CompareOp::NotEqual => vk::CompareOp::NOT_EQUAL,
+        CompareOp::GreaterOrEqual => vk::CompareOp::GREATER_OR_EQUAL,
+        CompareOp::Always => vk::CompareOp::ALWAYS,
+    }
+}
+
+pub(crate) fn vk_stencil_op(stencil_op: StencilOp) -> vk::StencilOp {
+    match stencil_op {
+        StencilOp::Keep => vk::StencilOp::KEEP,
+        StencilOp::Zero => vk::StencilOp::ZERO,
+        StencilOp::Replace => vk::StencilOp::REPLACE,
+        StencilOp::IncrementAndClamp => vk::StencilOp::INCREMENT_AND_CLAMP,
+        StencilOp::DecrementAndClamp => vk::StencilOp::DECREMENT_AND_CLAMP,
+        StencilOp::Invert => vk::StencilOp::INVERT,
+        StencilOp::IncrementAndWrap => vk::StencilOp::INCREMENT_AND_WRAP,
+        StencilOp::DecrementAndWrap => vk::StencilOp::DECREMENT_AND_WRAP,
+    }
+}
+
+pub(crate) fn vk_blend_factor(blend_factor: BlendFactor) -> vk::BlendFactor {
+    match blend_factor {
+        BlendFactor::Zero => vk::BlendFactor::ZERO,
+        BlendFactor::One => vk::BlendFactor::ONE,
+        BlendFactor::SrcColor => vk::BlendFactor::SRC_COLOR,
+        BlendFactor::OneMinusSrcColor => vk::BlendFactor::ONE_MINUS_SRC_COLOR,
+        BlendFactor::DstColor => vk::BlendFactor::DST_COLOR,
+        BlendFactor::OneMinusDstColor => vk::BlendFactor::ONE_MINUS_DST_COLOR,
+        BlendFactor::SrcAlpha => vk::BlendFactor::SRC_ALPHA,
+        BlendFactor::OneMinusSrcAlpha => vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+        BlendFactor::DstAlpha => vk::BlendFactor::DST_ALPHA,
+        BlendFactor::OneMinusDstAlpha => vk::BlendFactor::ONE_MINUS_DST_ALPHA,
+    }
+}
+
+pub(crate) fn vk_blend_op(blend_op: BlendOp) -> vk::BlendOp {
+    match blend_op {
+        BlendOp::Add => vk::BlendOp::ADD,
+        BlendOp::Subtract => vk::BlendOp::SUBTRACT,
+        BlendOp::ReverseSubtract => vk::BlendOp::REVERSE_SUBTRACT,
+        BlendOp::Min => vk::BlendOp::MIN,
+        BlendOp::Max => vk::BlendOp::MAX,
+    }
+}
+
+pub(crate) fn vk_color_write_mask(write_mask: ColorWriteMask) -> vk::ColorComponentFlags {
+    let mut flags = vk::ColorComponentFlags::empty();
+
+    if write_mask.contains(ColorWriteMask::R) {
+        flags |= vk::ColorComponentFlags::R;
+    }
+    if write_mask.contains(ColorWriteMask::G) {
+        flags |= vk::ColorComponentFlags::G;
+    }
+    if write_mask.contains(ColorWriteMask::B) {
+        flags |= vk::ColorComponentFlags::B;
+    }
+    if write_mask.contains(ColorWriteMask::A) {
+        flags |= vk::ColorComponentFlags::A;
+    }
+
+    flags
+}
+
+pub(crate) fn vk_present_mode(present_mode: PresentMode) -> vk::PresentModeKHR {
+    match present_mode {
+        PresentMode::Fifo => vk::PresentModeKHR::FIFO,
+        PresentMode::FifoRelaxed => vk::PresentModeKHR::FIFO_RELAXED,
+        PresentMode::Mailbox => vk::PresentModeKHR::MAILBOX,
+        PresentMode::Immediate => vk::PresentModeKHR::IMMEDIATE,
+    }
+}
+
+pub(crate) fn vk_color_space(color_space: ColorSpace) -> vk::ColorSpaceKHR {
+    match color_space {
+        ColorSpace::SrgbNonLinear => vk::ColorSpaceKHR::SRGB_NONLINEAR,
+        ColorSpace::Hdr10St2084 => vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+        ColorSpace::ExtendedSrgbLinear => vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT,
+    }
+}
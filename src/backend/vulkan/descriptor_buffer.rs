@@ -0,0 +1,155 @@
+use crate::Error;
+use crate::backend::vulkan::{VulkanDevice, VulkanGarbage};
+use ash::vk;
+use std::ptr::NonNull;
+
+pub(crate) struct VulkanDescriptorBuffer {
+    pub(crate) handle: vk::Buffer,
+    memory: vk::DeviceMemory,
+    mapped_ptr: NonNull<u8>,
+
+    pub(crate) address: vk::DeviceAddress,
+    pub(crate) size: u64,
+}
+
+unsafe impl Send for VulkanDescriptorBuffer {}
+unsafe impl Sync for VulkanDescriptorBuffer {}
+
+impl VulkanDescriptorBuffer {
+    pub(crate) fn new(device: &VulkanDevice, size: u64) -> Result<Self, Error> {
+        let handle = unsafe {
+            device.device.create_buffer(
+                &vk::BufferCreateInfo::default()
+                    .size(size)
+                    .usage(descriptor_buffer_usage() | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS)
+                    .sharing_mode(vk::SharingMode::EXCLUSIVE),
+                None,
+            )
+        }?;
+
+        let memory_requirements = unsafe { device.device.get_buffer_memory_requirements(handle) };
+
+        let memory_type_index = device
+            .find_memory_type_index(
+                memory_requirements.memory_type_bits,
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            )
+            .ok_or(Error::VulkanMemoryTypeNotFound)?;
+
+        let mut allocate_flags_info =
+            vk::MemoryAllocateFlagsInfo::default().flags(vk::MemoryAllocateFlags::DEVICE_ADDRESS);
+
+        let memory = unsafe {
+            device.device.allocate_memory(
+                &vk::MemoryAllocateInfo::default()
+                    .allocation_size(memory_requirements.size)
+                    .memory_type_index(memory_type_index)
+                    .push_next(&mut allocate_flags_info),
+                None,
+            )
+        }?;
+
+        unsafe { device.device.bind_buffer_memory(handle, memory, 0)? };
+
+        let mapped_ptr = unsafe {
+            device
+                .device
+                .map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())?
+        };
+
+        let address = unsafe {
+            device
+                .device
+                .get_buffer_device_address(&vk::BufferDeviceAddressInfo::default().buffer(handle))
+        };
+
+        Ok(Self {
+            handle,
+            memory,
+            mapped_ptr: NonNull::new(mapped_ptr as *mut u8).ok_or(Error::Unknown)?,
+            address,
+            size,
+        })
+    }
+
+    pub(crate) fn write_descriptor(
+        &self,
+        device: &VulkanDevice,
+        offset: u64,
+        descriptor: &VulkanDescriptor,
+    ) {
+        let Some(descriptor_buffer_device) = &device.descriptor_buffer_device else {
+            return;
+        };
+
+        let descriptor_type = descriptor.descriptor_type();
+        let size = device.descriptor_size(descriptor_type);
+
+        debug_assert!(offset + size <= self.size);
+
+        let data = match descriptor {
+            VulkanDescriptor::Sampler(sampler) => vk::DescriptorDataEXT { p_sampler: sampler },
+            VulkanDescriptor::SampledImage(image_info) => vk::DescriptorDataEXT {
+                p_sampled_image: image_info,
+            },
+            VulkanDescriptor::StorageImage(image_info) => vk::DescriptorDataEXT {
+                p_storage_image: image_info,
+            },
+            VulkanDescriptor::UniformBuffer(address_info) => vk::DescriptorDataEXT {
+                p_uniform_buffer: address_info,
+            },
+            VulkanDescriptor::StorageBuffer(address_info) => vk::DescriptorDataEXT {
+                p_storage_buffer: address_info,
+            },
+        };
+
+        unsafe {
+            descriptor_buffer_device.get_descriptor(
+                &vk::DescriptorGetInfoEXT::default()
+                    .ty(descriptor_type)
+                    .data(data),
+                std::slice::from_raw_parts_mut(
+                    self.mapped_ptr.as_ptr().add(offset as usize),
+                    size as usize,
+                ),
+            )
+        };
+    }
+
+    pub(crate) fn destroy_deferred(&self, device: &VulkanDevice) {
+        device.destroy_deferred(VulkanGarbage::Buffer(self.handle));
+        device.destroy_deferred(VulkanGarbage::Memory(self.memory));
+    }
+
+    pub(crate) unsafe fn destroy(&self, device: &ash::Device) {
+        unsafe {
+            device.destroy_buffer(self.handle, None);
+            device.free_memory(self.memory, None);
+        }
+    }
+}
+
+pub(crate) enum VulkanDescriptor {
+    Sampler(vk::Sampler),
+    SampledImage(vk::DescriptorImageInfo),
+    StorageImage(vk::DescriptorImageInfo),
+    UniformBuffer(vk::DescriptorAddressInfoEXT<'static>),
+    StorageBuffer(vk::DescriptorAddressInfoEXT<'static>),
+}
+
+impl VulkanDescriptor {
+    pub(crate) fn descriptor_type(&self) -> vk::DescriptorType {
+        match self {
+            VulkanDescriptor::Sampler(_) => vk::DescriptorType::SAMPLER,
+            VulkanDescriptor::SampledImage(_) => vk::DescriptorType::SAMPLED_IMAGE,
+            VulkanDescriptor::StorageImage(_) => vk::DescriptorType::STORAGE_IMAGE,
+            VulkanDescriptor::UniformBuffer(_) => vk::DescriptorType::UNIFORM_BUFFER,
+            VulkanDescriptor::StorageBuffer(_) => vk::DescriptorType::STORAGE_BUFFER,
+        }
+    }
+}
+
+pub(crate) fn descriptor_buffer_usage() -> vk::BufferUsageFlags {
+    vk::BufferUsageFlags::RESOURCE_DESCRIPTOR_BUFFER_EXT
+        | vk::BufferUsageFlags::SAMPLER_DESCRIPTOR_BUFFER_EXT
+}
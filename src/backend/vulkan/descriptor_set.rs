@@ -0,0 +1,472 @@
+use crate::backend::vulkan::*;
+use crate::{
+    DescriptorPoolDesc, DescriptorResource, DescriptorSet, DescriptorType, DescriptorWrite, Error,
+};
+use ash::vk;
+use std::sync::Arc;
+
+pub struct VulkanDescriptorPool {
+    device: Arc<VulkanDevice>,
+
+    desc: DescriptorPoolDesc,
+
+    pools: Vec<vk::DescriptorPool>,
+    current_pool: usize,
+
+    blocks: Vec<Arc<VulkanDescriptorBlock>>,
+    block_offset: u64,
+}
+
+impl VulkanDescriptorPool {
+    pub fn new(device: Arc<VulkanDevice>, desc: &DescriptorPoolDesc) -> Result<Self, Error> {
+        if desc.sets_per_block == 0 || desc.descriptors_per_block == 0 {
+            return Err(Error::Unsupported(
+                "Descriptor pool blocks must hold at least one set and one descriptor",
+            ));
+        }
+
+        Ok(Self {
+            device,
+            desc: desc.clone(),
+            pools: Vec::new(),
+            current_pool: 0,
+            blocks: Vec::new(),
+            block_offset: 0,
+        })
+    }
+
+    pub fn desc(&self) -> &DescriptorPoolDesc {
+        &self.desc
+    }
+
+    pub fn allocate(
+        &mut self,
+        binding_layout: &Arc<VulkanBindingLayout>,
+        table: u32,
+    ) -> Result<DescriptorSet, Error> {
+        if binding_layout.desc().bindless && table == BINDLESS_DESCRIPTOR_SET {
+            return Err(Error::Unsupported(
+                "The bindless descriptor table cannot be allocated from a descriptor pool",
+            ));
+        }
+
+        let set_layout =
+            *binding_layout
+                .set_layouts
+                .get(table as usize)
+                .ok_or(Error::Unsupported(
+                    "Descriptor table index is out of range for the binding layout",
+                ))?;
+
+        if let Some(descriptor_buffer_device) = &self.device.descriptor_buffer_device {
+            let size =
+                unsafe { descriptor_buffer_device.get_descriptor_set_layout_size(set_layout) };
+            let (block, offset) = self.allocate_block_range(size)?;
+
+            let descriptor_set = VulkanDescriptorSet::new(
+                self.device.clone(),
+                binding_layout.clone(),
+                table,
+                VulkanDescriptorSetStorage::Buffer { block, offset },
+            )?;
+            descriptor_set.write_static_samplers();
+
+            return Ok(DescriptorSet::Vulkan(descriptor_set));
+        }
+
+        loop {
+            let new_pool = self.current_pool == self.pools.len();
+
+            if new_pool {
+                let pool = self.create_pool()?;
+                self.pools.push(pool);
+            }
+
+            let result = unsafe {
+                self.device.device.allocate_descriptor_sets(
+                    &vk::DescriptorSetAllocateInfo::default()
+                        .descriptor_pool(self.pools[self.current_pool])
+                        .set_layouts(std::slice::from_ref(&set_layout)),
+                )
+            };
+
+            match result {
+                Ok(sets) => {
+                    return Ok(DescriptorSet::Vulkan(VulkanDescriptorSet::new(
+                        self.device.clone(),
+                        binding_layout.clone(),
+                        table,
+                        VulkanDescriptorSetStorage::Set(sets[0]),
+                    )?));
+                }
+                Err(vk::Result::ERROR_OUT_OF_POOL_MEMORY | vk::Result::ERROR_FRAGMENTED_POOL)
+                    if !new_pool =>
+                {
+                    self.current_pool += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    pub fn reset(&mut self) -> Result<(), Error> {
+        for pool in &self.pools {
+            unsafe {
+                self.device
+                    .device
+                    .reset_descriptor_pool(*pool, vk::DescriptorPoolResetFlags::empty())?
+            };
+        }
+
+        self.current_pool = 0;
+        self.block_offset = 0;
+
+        Ok(())
+    }
+
+    fn allocate_block_range(
+        &mut self,
+        size: u64,
+    ) -> Result<(Arc<VulkanDescriptorBlock>, u64), Error> {
+        let alignment = self
+            .device
+            .physical_device
+            .descriptor_buffer_properties
+            .descriptor_buffer_offset_alignment
+            .max(1);
+
+        loop {
+            if self.current_pool == self.blocks.len() {
+                let max_descriptor_size = [
+                    vk::DescriptorType::SAMPLER,
+                    vk::DescriptorType::SAMPLED_IMAGE,
+                    vk::DescriptorType::STORAGE_IMAGE,
+                    vk::DescriptorType::UNIFORM_BUFFER,
+                    vk::DescriptorType::STORAGE_BUFFER,
+                    vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
+                    vk::DescriptorType::STORAGE_TEXEL_BUFFER,
+                ]
+                .into_iter()
+                .map(|descriptor_type| self.device.descriptor_size(descriptor_type))
+                .max()
+                .unwrap_or(0);
+
+                let block_size =
+                    (self.desc.descriptors_per_block as u64 * max_descriptor_size).max(size);
+
+                self.blocks.push(Arc::new(VulkanDescriptorBlock {
+                    device: self.device.clone(),
+                    buffer: VulkanDescriptorBuffer::new(&self.device, block_size)?,
+                }));
+                self.block_offset = 0;
+            }
+
+            let block = &self.blocks[self.current_pool];
+            let offset = self.block_offset.next_multiple_of(alignment);
+
+            if offset + size <= block.buffer.size {
+                self.block_offset = offset + size;
+                return Ok((block.clone(), offset));
+            }
+
+            self.current_pool += 1;
+            self.block_offset = 0;
+        }
+    }
+
+    fn create_pool(&self) -> Result<vk::DescriptorPool, Error> {
+        let pool_sizes = [
+            vk::DescriptorType::SAMPLER,
+            vk::DescriptorType::SAMPLED_IMAGE,
+            vk::DescriptorType::STORAGE_IMAGE,
+            vk::DescriptorType::UNIFORM_BUFFER,
+            vk::DescriptorType::STORAGE_BUFFER,
+            vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
+            vk::DescriptorType::STORAGE_TEXEL_BUFFER,
+        ]
+        .map(|ty| {
+            vk::DescriptorPoolSize::default()
+                .ty(ty)
+                .descriptor_count(self.desc.descriptors_per_block)
+        });
+
+        Ok(unsafe {
+            self.device.device.create_descriptor_pool(
+                &vk::DescriptorPoolCreateInfo::default()
+                    .max_sets(self.desc.sets_per_block)
+                    .pool_sizes(&pool_sizes),
+                None,
+            )
+        }?)
+    }
+}
+
+impl Drop for VulkanDescriptorPool {
+    fn drop(&mut self) {
+        for pool in self.pools.drain(..) {
+            self.device
+                .destroy_deferred(VulkanGarbage::DescriptorPool(pool));
+        }
+    }
+}
+
+pub struct VulkanDescriptorSet {
+    device: Arc<VulkanDevice>,
+
+    pub(crate) binding_layout: Arc<VulkanBindingLayout>,
+    table: u32,
+
+    pub(crate) storage: VulkanDescriptorSetStorage,
+}
+
+pub(crate) enum VulkanDescriptorSetStorage {
+    Set(vk::DescriptorSet),
+    Buffer {
+        block: Arc<VulkanDescriptorBlock>,
+        offset: u64,
+    },
+}
+
+pub(crate) struct VulkanDescriptorBlock {
+    device: Arc<VulkanDevice>,
+    pub(crate) buffer: VulkanDescriptorBuffer,
+}
+
+impl Drop for VulkanDescriptorBlock {
+    fn drop(&mut self) {
+        self.buffer.destroy_deferred(&self.device);
+    }
+}
+
+impl VulkanDescriptorSet {
+    pub(crate) fn new(
+        device: Arc<VulkanDevice>,
+        binding_layout: Arc<VulkanBindingLayout>,
+        table: u32,
+        storage: VulkanDescriptorSetStorage,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            device,
+            binding_layout,
+            table,
+            storage,
+        })
+    }
+
+    pub fn table(&self) -> u32 {
+        self.table
+    }
+
+    pub fn write(&self, writes: &[DescriptorWrite]) -> Result<(), Error> {
+        let bindings = self
+            .binding_layout
+            .desc()
+            .tables
+            .get(self.table as usize)
+            .map(|table| table.bindings.as_slice())
+            .unwrap_or_default();
+
+        let infos = writes
+            .iter()
+            .map(|write| {
+                let binding = bindings
+                    .iter()
+                    .find(|binding| binding.binding == write.binding)
+                    .ok_or(Error::Unsupported(
+                        "Descriptor write targets a binding missing from the binding layout",
+                    ))?;
+
+                if write.array_element >= binding.count {
+                    return Err(Error::Unsupported(
+                        "Descriptor write array element is out of range",
+                    ));
+                }
+
+                let info = match (write.resource, binding.descriptor_type) {
+                    (
+                        DescriptorResource::UniformBuffer {
+                            buffer,
+                            offset,
+                            size,
+                        },
+                        DescriptorType::UniformBuffer,
+                    )
+                    | (
+                        DescriptorResource::StorageBuffer {
+                            buffer,
+                            offset,
+                            size,
+                        },
+                        DescriptorType::StorageBuffer | DescriptorType::ReadOnlyStorageBuffer,
+                    ) => DescriptorInfo::Buffer(
+                        vk::DescriptorBufferInfo::default()
+                            .buffer(buffer.as_vulkan_buffer().handle)
+                            .offset(offset)
+                            .range(size),
+                    ),
+                    (
+                        DescriptorResource::SampledTexture(texture),
+                        DescriptorType::SampledTexture,
+                    ) => DescriptorInfo::Image(
+                        vk::DescriptorImageInfo::default()
+                            .image_view(texture.as_vulkan_texture().view)
+                            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL),
+                    ),
+                    (
+                        DescriptorResource::StorageTexture(texture),
+                        DescriptorType::StorageTexture,
+                    ) => DescriptorInfo::Image(
+                        vk::DescriptorImageInfo::default()
+                            .image_view(texture.as_vulkan_texture().view)
+                            .image_layout(vk::ImageLayout::GENERAL),
+                    ),
+                    (DescriptorResource::Sampler(sampler), DescriptorType::Sampler) => {
+                        DescriptorInfo::Image(
+                            vk::DescriptorImageInfo::default()
+                                .sampler(sampler.as_vulkan_sampler().handle),
+                        )
+                    }
+                    _ => {
+                        return Err(Error::Unsupported(
+                            "Descriptor resource does not match the binding's descriptor type",
+                        ));
+                    }
+                };
+
+                Ok((write, vk_descriptor_type(binding.descriptor_type), info))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        match &self.storage {
+            VulkanDescriptorSetStorage::Set(handle) => {
+                let descriptor_writes = infos
+                    .iter()
+                    .map(|(write, descriptor_type, info)| {
+                        let descriptor_write = vk::WriteDescriptorSet::default()
+                            .dst_set(*handle)
+                            .dst_binding(write.binding)
+                            .dst_array_element(write.array_element)
+                            .descriptor_type(*descriptor_type);
+
+                        match info {
+                            DescriptorInfo::Buffer(buffer_info) => {
+                                descriptor_write.buffer_info(std::slice::from_ref(buffer_info))
+                            }
+                            DescriptorInfo::Image(image_info) => {
+                                descriptor_write.image_info(std::slice::from_ref(image_info))
+                            }
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                unsafe {
+                    self.device
+                        .device
+                        .update_descriptor_sets(&descriptor_writes, &[])
+                };
+            }
+            VulkanDescriptorSetStorage::Buffer { .. } => {
+                for (write, descriptor_type, info) in &infos {
+                    let descriptor = match info {
+                        DescriptorInfo::Buffer(buffer_info) => {
+                            let address_info = vk::DescriptorAddressInfoEXT::default()
+                                .address(
+                                    unsafe {
+                                        self.device.device.get_buffer_device_address(
+                                            &vk::BufferDeviceAddressInfo::default()
+                                                .buffer(buffer_info.buffer),
+                                        )
+                                    } + buffer_info.offset,
+                                )
+                                .range(buffer_info.range);
+
+                            if *descriptor_type == vk::DescriptorType::UNIFORM_BUFFER {
+                                VulkanDescriptor::UniformBuffer(address_info)
+                            } else {
+                                VulkanDescriptor::StorageBuffer(address_info)
+                            }
+                        }
+                        DescriptorInfo::Image(image_info) => match *descriptor_type {
+                            vk::DescriptorType::SAMPLER => {
+                                VulkanDescriptor::Sampler(image_info.sampler)
+                            }
+                            vk::DescriptorType::STORAGE_IMAGE => {
+                                VulkanDescriptor::StorageImage(*image_info)
+                            }
+                            _ => VulkanDescriptor::SampledImage(*image_info),
+                        },
+                    };
+
+                    self.write_descriptor(
+                        write.binding,
+                        write.array_element,
+                        *descriptor_type,
+                        &descriptor,
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_static_samplers(&self) {
+        if self
+            .binding_layout
+            .embedded_sampler_sets
+            .contains(&self.table)
+        {
+            return;
+        }
+
+        for (static_sampler, sampler) in self
+            .binding_layout
+            .desc()
+            .static_samplers
+            .iter()
+            .zip(&self.binding_layout.static_samplers)
+            .filter(|(static_sampler, _)| static_sampler.space == self.table)
+        {
+            self.write_descriptor(
+                static_sampler.binding,
+                0,
+                vk::DescriptorType::SAMPLER,
+                &VulkanDescriptor::Sampler(*sampler),
+            );
+        }
+    }
+
+    fn write_descriptor(
+        &self,
+        binding: u32,
+        array_element: u32,
+        descriptor_type: vk::DescriptorType,
+        descriptor: &VulkanDescriptor,
+    ) {
+        let (Some(descriptor_buffer_device), VulkanDescriptorSetStorage::Buffer { block, offset }) =
+            (&self.device.descriptor_buffer_device, &self.storage)
+        else {
+            return;
+        };
+
+        let binding_offset = unsafe {
+            descriptor_buffer_device.get_descriptor_set_layout_binding_offset(
+                self.binding_layout.set_layouts[self.table as usize],
+                binding,
+            )
+        };
+
+        block.buffer.write_descriptor(
+            &self.device,
+            offset
+                + binding_offset
+                + array_element as u64 * self.device.descriptor_size(descriptor_type),
+            descriptor,
+        );
+    }
+}
+
+enum DescriptorInfo {
+    Buffer(vk::DescriptorBufferInfo),
+    Image(vk::DescriptorImageInfo),
+}
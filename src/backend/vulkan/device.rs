@@ -1,21 +1,41 @@
-use crate::backend::vulkan::{VulkanPhysicalDevice, VulkanQueue, VulkanShaderModule};
+use crate::backend::vulkan::*;
 use crate::shader_module::ShaderDesc;
-use crate::{Error, Queue, ShaderModule, util};
+use crate::shader_reflection::reflect_spirv;
+use crate::{
+    BindingLayout, BindingLayoutDesc, BindlessResource, BlendState, Buffer, BufferDesc,
+    BufferUsage, CommandList, DepthStencilState, DescriptorIndex, DescriptorPool,
+    DescriptorPoolDesc, Error, GraphicsPipelineDesc, Heap, HeapDesc, MemoryLocation,
+    MemoryRequirements, MeshPipelineDesc, Pipeline, Queue, QueueType, RasterizerState,
+    RenderTargetFormats, Sampler, SamplerDesc, ShaderKind, ShaderModule, StencilFaceState,
+    Swapchain, SwapchainDesc, Texture, TextureDesc, util,
+};
 use ash::vk;
-use std::sync::Arc;
+use std::ffi::CString;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+
+const SPIRV_MAGIC: u32 = 0x07230203;
+const MIN_SPIRV_VERSION: u32 = 0x00010000;
+const MAX_SPIRV_VERSION: u32 = 0x00010600;
 
 pub struct VulkanDevice {
-    physical_device: Arc<VulkanPhysicalDevice>,
+    pub(crate) physical_device: Arc<VulkanPhysicalDevice>,
 
     pub(crate) device: ash::Device,
+    pub(crate) mesh_shader_device: Option<ash::ext::mesh_shader::Device>,
+    pub(crate) descriptor_buffer_device: Option<ash::ext::descriptor_buffer::Device>,
 
     direct_queue_family_index: u32,
     compute_queue_family_index: u32,
     transfer_queue_family_index: u32,
 
-    direct_queue: Arc<VulkanQueue>,
+    pub(crate) direct_queue: Arc<VulkanQueue>,
     compute_queue: Arc<VulkanQueue>,
     transfer_queue: Arc<VulkanQueue>,
+
+    pub(crate) bindless: Option<VulkanBindlessHeap>,
+
+    garbage: Mutex<Vec<(VulkanGarbage, [u64; 3])>>,
 }
 
 impl VulkanDevice {
@@ -27,38 +47,594 @@ impl VulkanDevice {
         compute_queue_family_index: u32,
         transfer_queue_family_index: u32,
     ) -> Result<Self, Error> {
-        let direct_queue = unsafe { device.get_device_queue(direct_queue_family_index, 0) };
-        let compute_queue = unsafe { device.get_device_queue(compute_queue_family_index, 0) };
-        let transfer_queue = unsafe { device.get_device_queue(transfer_queue_family_index, 0) };
+        let queue_handles = queue_handles(
+            &device,
+            [
+                direct_queue_family_index,
+                compute_queue_family_index,
+                transfer_queue_family_index,
+            ],
+        );
+
+        let mesh_shader_device = physical_device.mesh_shader_supported.then(|| {
+            ash::ext::mesh_shader::Device::new(&physical_device.instance.instance, &device)
+        });
 
-        Ok(Self {
+        let descriptor_buffer_device = physical_device.descriptor_buffer_supported.then(|| {
+            ash::ext::descriptor_buffer::Device::new(&physical_device.instance.instance, &device)
+        });
+
+        let mut vulkan_device = Self {
+            direct_queue: Arc::new(VulkanQueue::new(
+                device.clone(),
+                queue_handles[0].clone(),
+                QueueType::Direct,
+            )?),
+            compute_queue: Arc::new(VulkanQueue::new(
+                device.clone(),
+                queue_handles[1].clone(),
+                QueueType::Compute,
+            )?),
+            transfer_queue: Arc::new(VulkanQueue::new(
+                device.clone(),
+                queue_handles[2].clone(),
+                QueueType::Transfer,
+            )?),
             physical_device,
             device,
+            mesh_shader_device,
+            descriptor_buffer_device,
             direct_queue_family_index,
             compute_queue_family_index,
             transfer_queue_family_index,
-            direct_queue: Arc::new(VulkanQueue::new(direct_queue)),
-            compute_queue: Arc::new(VulkanQueue::new(compute_queue)),
-            transfer_queue: Arc::new(VulkanQueue::new(transfer_queue)),
-        })
+            bindless: None,
+            garbage: Mutex::new(Vec::new()),
+        };
+
+        if vulkan_device.physical_device.bindless_supported {
+            vulkan_device.bindless = Some(VulkanBindlessHeap::new(&vulkan_device)?);
+        }
+
+        Ok(vulkan_device)
     }
 
     pub fn create_shader_module(
         self: &Arc<Self>,
         desc: &ShaderDesc,
     ) -> Result<ShaderModule, Error> {
-        let spir_v = util::compile_hlsl(desc, true)?;
+        let spir_v = util::spirv_code(desc)?;
+        validate_spirv(&spir_v)?;
+
+        let reflection = reflect_spirv(&spir_v, desc.kind, &desc.entry_point)?;
 
         let handle = unsafe {
-            self.device.create_shader_module(
-                &vk::ShaderModuleCreateInfo::default().code(bytemuck::cast_slice(&spir_v)),
+            self.device
+                .create_shader_module(&vk::ShaderModuleCreateInfo::default().code(&spir_v), None)
+        }?;
+
+        Ok(ShaderModule::Vulkan(VulkanShaderModule::new(
+            self.clone(),
+            handle,
+            desc.kind,
+            CString::new(desc.entry_point.as_str())?,
+            reflection,
+        )?))
+    }
+
+    pub fn create_buffer(self: &Arc<Self>, desc: &BufferDesc) -> Result<Buffer, Error> {
+        let handle = unsafe {
+            self.device
+                .create_buffer(&self.buffer_create_info(desc), None)
+        }?;
+
+        let (memory, mapped_ptr) = match self.allocate_buffer_memory(handle, desc) {
+            Ok(allocation) => allocation,
+            Err(err) => {
+                unsafe { self.device.destroy_buffer(handle, None) };
+                return Err(err);
+            }
+        };
+
+        Ok(Buffer::Vulkan(VulkanBuffer::new(
+            self.clone(),
+            handle,
+            VulkanAllocation::Dedicated(memory),
+            desc.size,
+            desc.usage,
+            mapped_ptr,
+        )?))
+    }
+
+    pub fn create_texture(self: &Arc<Self>, desc: &TextureDesc) -> Result<Texture, Error> {
+        let handle = unsafe { self.device.create_image(&image_create_info(desc), None) }?;
+
+        let memory = match self.allocate_image_memory(handle) {
+            Ok(memory) => memory,
+            Err(err) => {
+                unsafe { self.device.destroy_image(handle, None) };
+                return Err(err);
+            }
+        };
+
+        let view = match self.create_image_view(handle, desc) {
+            Ok(view) => view,
+            Err(err) => {
+                unsafe {
+                    self.device.destroy_image(handle, None);
+                    self.device.free_memory(memory, None);
+                }
+                return Err(err);
+            }
+        };
+
+        Ok(Texture::Vulkan(VulkanTexture::new(
+            self.clone(),
+            handle,
+            view,
+            VulkanAllocation::Dedicated(memory),
+            desc.clone(),
+        )?))
+    }
+
+    pub fn create_heap(self: &Arc<Self>, desc: &HeapDesc) -> Result<Heap, Error> {
+        let memory_type_index = self
+            .find_memory_type_index(desc.memory_type_bits, vk::MemoryPropertyFlags::DEVICE_LOCAL)
+            .or_else(|| {
+                self.find_memory_type_index(desc.memory_type_bits, vk::MemoryPropertyFlags::empty())
+            })
+            .ok_or(Error::VulkanMemoryTypeNotFound)?;
+
+        let mut allocate_flags_info =
+            vk::MemoryAllocateFlagsInfo::default().flags(vk::MemoryAllocateFlags::DEVICE_ADDRESS);
+
+        let memory = unsafe {
+            self.device.allocate_memory(
+                &vk::MemoryAllocateInfo::default()
+                    .allocation_size(desc.size)
+                    .memory_type_index(memory_type_index)
+                    .push_next(&mut allocate_flags_info),
                 None,
             )
         }?;
 
-        Ok(ShaderModule::Vulkan(VulkanShaderModule::new(
+        Ok(Heap::Vulkan(Arc::new(VulkanHeap::new(
+            self.clone(),
+            memory,
+            desc.size,
+        )?)))
+    }
+
+    pub fn buffer_memory_requirements(&self, desc: &BufferDesc) -> MemoryRequirements {
+        let buffer_create_info = self.buffer_create_info(desc);
+        let mut memory_requirements = vk::MemoryRequirements2::default();
+
+        unsafe {
+            self.device.get_device_buffer_memory_requirements(
+                &vk::DeviceBufferMemoryRequirements::default().create_info(&buffer_create_info),
+                &mut memory_requirements,
+            )
+        };
+
+        MemoryRequirements {
+            size: memory_requirements.memory_requirements.size,
+            alignment: memory_requirements.memory_requirements.alignment,
+            memory_type_bits: memory_requirements.memory_requirements.memory_type_bits,
+        }
+    }
+
+    pub fn texture_memory_requirements(&self, desc: &TextureDesc) -> MemoryRequirements {
+        let image_create_info = image_create_info(desc);
+        let mut memory_requirements = vk::MemoryRequirements2::default();
+
+        unsafe {
+            self.device.get_device_image_memory_requirements(
+                &vk::DeviceImageMemoryRequirements::default().create_info(&image_create_info),
+                &mut memory_requirements,
+            )
+        };
+
+        MemoryRequirements {
+            size: memory_requirements.memory_requirements.size,
+            alignment: memory_requirements.memory_requirements.alignment,
+            memory_type_bits: memory_requirements.memory_requirements.memory_type_bits,
+        }
+    }
+
+    pub fn create_placed_buffer(
+        self: &Arc<Self>,
+        heap: &Arc<VulkanHeap>,
+        offset: u64,
+        desc: &BufferDesc,
+    ) -> Result<Buffer, Error> {
+        if desc.memory_location != MemoryLocation::GpuOnly {
+            return Err(Error::Unsupported(
+                "Placed buffers must use MemoryLocation::GpuOnly",
+            ));
+        }
+
+        let handle = unsafe {
+            self.device
+                .create_buffer(&self.buffer_create_info(desc), None)
+        }?;
+
+        if let Err(err) = unsafe { self.device.bind_buffer_memory(handle, heap.memory, offset) } {
+            unsafe { self.device.destroy_buffer(handle, None) };
+            return Err(err.into());
+        }
+
+        Ok(Buffer::Vulkan(VulkanBuffer::new(
             self.clone(),
             handle,
+            VulkanAllocation::Placed(heap.clone()),
+            desc.size,
+            desc.usage,
+            None,
+        )?))
+    }
+
+    pub fn create_placed_texture(
+        self: &Arc<Self>,
+        heap: &Arc<VulkanHeap>,
+        offset: u64,
+        desc: &TextureDesc,
+    ) -> Result<Texture, Error> {
+        let handle = unsafe { self.device.create_image(&image_create_info(desc), None) }?;
+
+        if let Err(err) = unsafe { self.device.bind_image_memory(handle, heap.memory, offset) } {
+            unsafe { self.device.destroy_image(handle, None) };
+            return Err(err.into());
+        }
+
+        let view = match self.create_image_view(handle, desc) {
+            Ok(view) => view,
+            Err(err) => {
+                unsafe { self.device.destroy_image(handle, None) };
+                return Err(err);
+            }
+        };
+
+        Ok(Texture::Vulkan(VulkanTexture::new(
+            self.clone(),
+            handle,
+            view,
+            VulkanAllocation::Placed(heap.clone()),
+            desc.clone(),
+        )?))
+    }
+
+    pub fn create_mesh_pipeline(
+        self: &Arc<Self>,
+        desc: &MeshPipelineDesc,
+    ) -> Result<Pipeline, Error> {
+        if self.mesh_shader_device.is_none() {
+            return Err(Error::Unsupported(
+                "Mesh shaders are not supported by this device",
+            ));
+        }
+
+        if desc.task_shader.is_some() && !self.physical_device.task_shader_supported {
+            return Err(Error::Unsupported(
+                "Task shaders are not supported by this device",
+            ));
+        }
+
+        let mut stages = vec![];
+
+        if let Some(task_shader) = desc.task_shader {
+            stages.push(shader_stage(task_shader, ShaderKind::Task)?);
+        }
+
+        stages.push(shader_stage(desc.mesh_shader, ShaderKind::Mesh)?);
+
+        if let Some(fragment_shader) = desc.fragment_shader {
+            stages.push(shader_stage(fragment_shader, ShaderKind::Fragment)?);
+        }
+
+        let binding_layout = self.pipeline_binding_layout(
+            desc.binding_layout,
+            &desc.name,
+            &[
+                desc.task_shader,
+                Some(desc.mesh_shader),
+                desc.fragment_shader,
+            ],
+        )?;
+
+        self.create_graphics_pipeline_internal(
+            &stages,
+            &vk::PipelineVertexInputStateCreateInfo::default(),
+            None,
+            GraphicsPipelineState {
+                binding_layout,
+                rasterizer: &desc.rasterizer,
+                depth_stencil: &desc.depth_stencil,
+                blend: &desc.blend,
+                render_target_formats: &desc.render_target_formats,
+            },
+        )
+    }
+
+    pub fn create_graphics_pipeline(
+        self: &Arc<Self>,
+        desc: &GraphicsPipelineDesc,
+    ) -> Result<Pipeline, Error> {
+        let mut stages = vec![shader_stage(desc.vertex_shader, ShaderKind::Vertex)?];
+
+        if let Some(fragment_shader) = desc.fragment_shader {
+            stages.push(shader_stage(fragment_shader, ShaderKind::Fragment)?);
+        }
+
+        let vertex_binding_descriptions = desc
+            .vertex_input
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| {
+                vk::VertexInputBindingDescription::default()
+                    .binding(binding as u32)
+                    .stride(buffer.stride)
+                    .input_rate(vk_vertex_input_rate(buffer.step_mode))
+            })
+            .collect::<Vec<_>>();
+
+        let vertex_attribute_descriptions = desc
+            .vertex_input
+            .attributes
+            .iter()
+            .map(|attribute| {
+                vk::VertexInputAttributeDescription::default()
+                    .location(attribute.location)
+                    .binding(attribute.buffer_index)
+                    .format(vk_format(attribute.format))
+                    .offset(attribute.offset)
+            })
+            .collect::<Vec<_>>();
+
+        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&vertex_binding_descriptions)
+            .vertex_attribute_descriptions(&vertex_attribute_descriptions);
+
+        let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo::default()
+            .topology(vk_primitive_topology(desc.primitive_topology))
+            .primitive_restart_enable(desc.primitive_restart);
+
+        let binding_layout = self.pipeline_binding_layout(
+            desc.binding_layout,
+            &desc.name,
+            &[Some(desc.vertex_shader), desc.fragment_shader],
+        )?;
+
+        self.create_graphics_pipeline_internal(
+            &stages,
+            &vertex_input_state,
+            Some(&input_assembly_state),
+            GraphicsPipelineState {
+                binding_layout,
+                rasterizer: &desc.rasterizer,
+                depth_stencil: &desc.depth_stencil,
+                blend: &desc.blend,
+                render_target_formats: &desc.render_target_formats,
+            },
+        )
+    }
+
+    fn create_graphics_pipeline_internal(
+        self: &Arc<Self>,
+        stages: &[vk::PipelineShaderStageCreateInfo],
+        vertex_input_state: &vk::PipelineVertexInputStateCreateInfo,
+        input_assembly_state: Option<&vk::PipelineInputAssemblyStateCreateInfo>,
+        state: GraphicsPipelineState,
+    ) -> Result<Pipeline, Error> {
+        let binding_layout = state.binding_layout;
+
+        let rasterizer = state.rasterizer;
+        let depth_bias_enable =
+            rasterizer.depth_bias != 0.0 || rasterizer.depth_bias_slope_scale != 0.0;
+        let rasterization_state = vk::PipelineRasterizationStateCreateInfo::default()
+            .depth_clamp_enable(rasterizer.depth_clamp)
+            .polygon_mode(vk_polygon_mode(rasterizer.polygon_mode))
+            .cull_mode(vk_cull_mode(rasterizer.cull_mode))
+            .front_face(vk_front_face(rasterizer.front_face))
+            .depth_bias_enable(depth_bias_enable)
+            .depth_bias_constant_factor(rasterizer.depth_bias)
+            .depth_bias_slope_factor(rasterizer.depth_bias_slope_scale)
+            .depth_bias_clamp(rasterizer.depth_bias_clamp)
+            .line_width(1.0);
+
+        let render_target_formats = state.render_target_formats;
+        let multisample_state = vk::PipelineMultisampleStateCreateInfo::default()
+            .rasterization_samples(vk_sample_count(render_target_formats.sample_count))
+            .alpha_to_coverage_enable(state.blend.alpha_to_coverage);
+
+        let depth_stencil = state.depth_stencil;
+        let stencil_op_state = |face: &StencilFaceState| {
+            vk::StencilOpState::default()
+                .fail_op(vk_stencil_op(face.fail_op))
+                .pass_op(vk_stencil_op(face.pass_op))
+                .depth_fail_op(vk_stencil_op(face.depth_fail_op))
+                .compare_op(vk_compare_op(face.compare_op))
+                .compare_mask(depth_stencil.stencil_read_mask as u32)
+                .write_mask(depth_stencil.stencil_write_mask as u32)
+        };
+        let depth_stencil_state = vk::PipelineDepthStencilStateCreateInfo::default()
+            .depth_test_enable(depth_stencil.depth_test)
+            .depth_write_enable(depth_stencil.depth_write)
+            .depth_compare_op(vk_compare_op(depth_stencil.depth_compare_op))
+            .stencil_test_enable(depth_stencil.stencil_test)
+            .front(stencil_op_state(&depth_stencil.stencil_front))
+            .back(stencil_op_state(&depth_stencil.stencil_back))
+            .min_depth_bounds(0.0)
+            .max_depth_bounds(1.0);
+
+        let color_blend_attachments = (0..render_target_formats.color_formats.len())
+            .map(|i| {
+                let attachment = state.blend.attachments.get(i).copied().unwrap_or_default();
+
+                vk::PipelineColorBlendAttachmentState::default()
+                    .blend_enable(attachment.blend_enable)
+                    .src_color_blend_factor(vk_blend_factor(attachment.src_color_blend_factor))
+                    .dst_color_blend_factor(vk_blend_factor(attachment.dst_color_blend_factor))
+                    .color_blend_op(vk_blend_op(attachment.color_blend_op))
+                    .src_alpha_blend_factor(vk_blend_factor(attachment.src_alpha_blend_factor))
+                    .dst_alpha_blend_factor(vk_blend_factor(attachment.dst_alpha_blend_factor))
+                    .alpha_blend_op(vk_blend_op(attachment.alpha_blend_op))
+                    .color_write_mask(vk_color_write_mask(attachment.write_mask))
+            })
+            .collect::<Vec<_>>();
+
+        let color_blend_state =
+            vk::PipelineColorBlendStateCreateInfo::default().attachments(&color_blend_attachments);
+
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state =
+            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+        let color_formats = render_target_formats
+            .color_formats
+            .iter()
+            .map(|format| vk_format(*format))
+            .collect::<Vec<_>>();
+
+        let (depth_format, stencil_format) = match render_target_formats.depth_stencil_format {
+            Some(format) if format.is_stencil() => (vk_format(format), vk_format(format)),
+            Some(format) => (vk_format(format), vk::Format::UNDEFINED),
+            None => (vk::Format::UNDEFINED, vk::Format::UNDEFINED),
+        };
+
+        let mut rendering_create_info = vk::PipelineRenderingCreateInfo::default()
+            .color_attachment_formats(&color_formats)
+            .depth_attachment_format(depth_format)
+            .stencil_attachment_format(stencil_format);
+
+        let mut create_info = vk::GraphicsPipelineCreateInfo::default()
+            .stages(stages)
+            .vertex_input_state(vertex_input_state)
+            .rasterization_state(&rasterization_state)
+            .multisample_state(&multisample_state)
+            .depth_stencil_state(&depth_stencil_state)
+            .color_blend_state(&color_blend_state)
+            .viewport_state(&viewport_state)
+            .dynamic_state(&dynamic_state)
+            .layout(binding_layout.pipeline_layout)
+            .push_next(&mut rendering_create_info);
+
+        if self.descriptor_buffer_device.is_some() {
+            create_info = create_info.flags(vk::PipelineCreateFlags::DESCRIPTOR_BUFFER_EXT);
+        }
+
+        if let Some(input_assembly_state) = input_assembly_state {
+            create_info = create_info.input_assembly_state(input_assembly_state);
+        }
+
+        let handle = unsafe {
+            self.device
+                .create_graphics_pipelines(vk::PipelineCache::null(), &[create_info], None)
+        }
+        .map_err(|(_, result)| result)?[0];
+
+        Ok(Pipeline::Vulkan(VulkanPipeline::new(
+            self.clone(),
+            handle,
+            binding_layout,
+            vk::PipelineBindPoint::GRAPHICS,
+        )?))
+    }
+
+    fn pipeline_binding_layout(
+        self: &Arc<Self>,
+        binding_layout: Option<&BindingLayout>,
+        name: &str,
+        shaders: &[Option<&ShaderModule>],
+    ) -> Result<Arc<VulkanBindingLayout>, Error> {
+        if let Some(binding_layout) = binding_layout {
+            return Ok(binding_layout.as_vulkan_binding_layout().clone());
+        }
+
+        let reflections = shaders
+            .iter()
+            .flatten()
+            .map(|shader| shader.as_vulkan_shader_module().reflection())
+            .collect::<Vec<_>>();
+
+        Ok(Arc::new(VulkanBindingLayout::new(
+            self.clone(),
+            &BindingLayoutDesc::from_reflection(name.to_owned().into(), &reflections)?,
+        )?))
+    }
+
+    pub fn create_binding_layout(
+        self: &Arc<Self>,
+        desc: &BindingLayoutDesc,
+    ) -> Result<BindingLayout, Error> {
+        Ok(BindingLayout::Vulkan(Arc::new(VulkanBindingLayout::new(
+            self.clone(),
+            desc,
+        )?)))
+    }
+
+    pub fn create_sampler(self: &Arc<Self>, desc: &SamplerDesc) -> Result<Sampler, Error> {
+        let handle = self.create_sampler_handle(desc)?;
+
+        Ok(Sampler::Vulkan(VulkanSampler::new(
+            self.clone(),
+            handle,
+            *desc,
+        )?))
+    }
+
+    pub fn allocate_descriptor(
+        &self,
+        resource: BindlessResource,
+    ) -> Result<DescriptorIndex, Error> {
+        self.bindless_heap()?
+            .allocate_resource(self, resource, self.completed_values()?)
+    }
+
+    pub fn allocate_sampler_descriptor(
+        &self,
+        sampler: &VulkanSampler,
+    ) -> Result<DescriptorIndex, Error> {
+        self.bindless_heap()?
+            .allocate_sampler(self, sampler.handle, self.completed_values()?)
+    }
+
+    pub fn free_descriptor(&self, index: DescriptorIndex) {
+        if let Some(bindless) = &self.bindless {
+            bindless.free_resource(index, self.submitted_values());
+        }
+    }
+
+    pub fn free_sampler_descriptor(&self, index: DescriptorIndex) {
+        if let Some(bindless) = &self.bindless {
+            bindless.free_sampler(index, self.submitted_values());
+        }
+    }
+
+    pub fn create_descriptor_pool(
+        self: &Arc<Self>,
+        desc: &DescriptorPoolDesc,
+    ) -> Result<DescriptorPool, Error> {
+        Ok(DescriptorPool::Vulkan(VulkanDescriptorPool::new(
+            self.clone(),
+            desc,
+        )?))
+    }
+
+    pub fn create_swapchain(self: &Arc<Self>, desc: &SwapchainDesc) -> Result<Swapchain, Error> {
+        Ok(Swapchain::Vulkan(VulkanSwapchain::new(self.clone(), desc)?))
+    }
+
+    pub fn create_command_list(
+        self: &Arc<Self>,
+        queue_type: QueueType,
+    ) -> Result<CommandList, Error> {
+        Ok(CommandList::Vulkan(VulkanCommandList::new(
+            self.clone(),
+            queue_type,
         )?))
     }
 
@@ -73,4 +649,387 @@ impl VulkanDevice {
     pub fn get_transfer_queue(&self) -> Queue {
         Queue::Vulkan(self.transfer_queue.clone())
     }
+
+    pub fn collect_garbage(&self) -> Result<(), Error> {
+        let completed_values = self.completed_values()?;
+
+        let mut garbage = self.garbage.lock().unwrap();
+
+        for (garbage, _) in garbage.extract_if(.., |(_, values)| {
+            values
+                .iter()
+                .zip(&completed_values)
+                .all(|(value, completed_value)| value <= completed_value)
+        }) {
+            unsafe { garbage.destroy(&self.device) };
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn destroy_deferred(&self, garbage: VulkanGarbage) {
+        let values = self.submitted_values();
+
+        self.garbage.lock().unwrap().push((garbage, values));
+    }
+
+    #[cfg(test)]
+    pub(crate) fn deferred_values(&self, garbage: VulkanGarbage) -> Option<[u64; 3]> {
+        self.garbage
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(deferred, _)| *deferred == garbage)
+            .map(|(_, values)| *values)
+    }
+
+    fn completed_values(&self) -> Result<[u64; 3], Error> {
+        Ok([
+            self.direct_queue.completed_value()?,
+            self.compute_queue.completed_value()?,
+            self.transfer_queue.completed_value()?,
+        ])
+    }
+
+    fn submitted_values(&self) -> [u64; 3] {
+        [
+            self.direct_queue.submitted_value(),
+            self.compute_queue.submitted_value(),
+            self.transfer_queue.submitted_value(),
+        ]
+    }
+
+    fn bindless_heap(&self) -> Result<&VulkanBindlessHeap, Error> {
+        self.bindless.as_ref().ok_or(Error::Unsupported(
+            "Bindless descriptors are not supported by this device",
+        ))
+    }
+
+    pub(crate) fn queue_family_index(&self, queue_type: QueueType) -> u32 {
+        match queue_type {
+            QueueType::Direct => self.direct_queue_family_index,
+            QueueType::Compute => self.compute_queue_family_index,
+            QueueType::Transfer => self.transfer_queue_family_index,
+        }
+    }
+
+    pub(crate) fn queue_flags(&self, queue_type: QueueType) -> vk::QueueFlags {
+        self.physical_device.queue_family_properties[self.queue_family_index(queue_type) as usize]
+            .queue_flags
+    }
+
+    pub(crate) fn create_image_view(
+        &self,
+        handle: vk::Image,
+        desc: &TextureDesc,
+    ) -> Result<vk::ImageView, Error> {
+        Ok(unsafe {
+            self.device.create_image_view(
+                &vk::ImageViewCreateInfo::default()
+                    .image(handle)
+                    .view_type(vk_image_view_type(desc.dimension, desc.array_layers))
+                    .format(vk_format(desc.format))
+                    .subresource_range(
+                        vk::ImageSubresourceRange::default()
+                            .aspect_mask(vk_image_aspect(desc.format))
+                            .level_count(desc.mip_levels)
+                            .layer_count(desc.array_layers),
+                    ),
+                None,
+            )
+        }?)
+    }
+
+    pub(crate) fn create_sampler_handle(&self, desc: &SamplerDesc) -> Result<vk::Sampler, Error> {
+        let mut create_info = vk::SamplerCreateInfo::default()
+            .mag_filter(vk_filter(desc.mag_filter))
+            .min_filter(vk_filter(desc.min_filter))
+            .mipmap_mode(vk_sampler_mipmap_mode(desc.mipmap_filter))
+            .address_mode_u(vk_sampler_address_mode(desc.address_mode_u))
+            .address_mode_v(vk_sampler_address_mode(desc.address_mode_v))
+            .address_mode_w(vk_sampler_address_mode(desc.address_mode_w))
+            .mip_lod_bias(desc.mip_lod_bias)
+            .min_lod(desc.min_lod)
+            .max_lod(desc.max_lod)
+            .border_color(vk_border_color(desc.border_color));
+
+        if let Some(max_anisotropy) = desc.max_anisotropy {
+            create_info = create_info
+                .anisotropy_enable(true)
+                .max_anisotropy(max_anisotropy);
+        }
+
+        if let Some(compare_op) = desc.compare_op {
+            create_info = create_info
+                .compare_enable(true)
+                .compare_op(vk_compare_op(compare_op));
+        }
+
+        Ok(unsafe { self.device.create_sampler(&create_info, None) }?)
+    }
+
+    // Uniform and storage buffers are referenced by address from descriptor buffers.
+    pub(crate) fn buffer_device_address_required(&self, usage: BufferUsage) -> bool {
+        usage.contains(BufferUsage::DEVICE_ADDRESS)
+            || (self.descriptor_buffer_device.is_some()
+                && (usage.contains(BufferUsage::UNIFORM) || usage.contains(BufferUsage::STORAGE)))
+    }
+
+    fn buffer_create_info(&self, desc: &BufferDesc) -> vk::BufferCreateInfo<'static> {
+        let mut usage = vk_buffer_usage(desc.usage);
+
+        if self.buffer_device_address_required(desc.usage) {
+            usage |= vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS;
+        }
+
+        vk::BufferCreateInfo::default()
+            .size(desc.size)
+            .usage(usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+    }
+
+    pub(crate) fn descriptor_size(&self, descriptor_type: vk::DescriptorType) -> u64 {
+        let properties = &self.physical_device.descriptor_buffer_properties;
+
+        (match descriptor_type {
+            vk::DescriptorType::SAMPLER => properties.sampler_descriptor_size,
+            vk::DescriptorType::SAMPLED_IMAGE => properties.sampled_image_descriptor_size,
+            vk::DescriptorType::STORAGE_IMAGE => properties.storage_image_descriptor_size,
+            vk::DescriptorType::UNIFORM_BUFFER => properties.uniform_buffer_descriptor_size,
+            vk::DescriptorType::STORAGE_BUFFER => properties.storage_buffer_descriptor_size,
+            vk::DescriptorType::UNIFORM_TEXEL_BUFFER => {
+                properties.uniform_texel_buffer_descriptor_size
+            }
+            vk::DescriptorType::STORAGE_TEXEL_BUFFER => {
+                properties.storage_texel_buffer_descriptor_size
+            }
+            _ => 0,
+        }) as u64
+    }
+
+    fn allocate_buffer_memory(
+        &self,
+        handle: vk::Buffer,
+        desc: &BufferDesc,
+    ) -> Result<(vk::DeviceMemory, Option<NonNull<u8>>), Error> {
+        let memory_requirements = unsafe { self.device.get_buffer_memory_requirements(handle) };
+
+        let memory_property_flags = match desc.memory_location {
+            MemoryLocation::GpuOnly => vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            MemoryLocation::CpuToGpu => {
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT
+            }
+            MemoryLocation::GpuToCpu => {
+                vk::MemoryPropertyFlags::HOST_VISIBLE
+                    | vk::MemoryPropertyFlags::HOST_COHERENT
+                    | vk::MemoryPropertyFlags::HOST_CACHED
+            }
+        };
+
+        let memory_type_index = self
+            .find_memory_type_index(memory_requirements.memory_type_bits, memory_property_flags)
+            .or_else(|| {
+                self.find_memory_type_index(
+                    memory_requirements.memory_type_bits,
+                    memory_property_flags & !vk::MemoryPropertyFlags::HOST_CACHED,
+                )
+            })
+            .ok_or(Error::VulkanMemoryTypeNotFound)?;
+
+        let mut allocate_flags_info = vk::MemoryAllocateFlagsInfo::default();
+
+        if self.buffer_device_address_required(desc.usage) {
+            allocate_flags_info =
+                allocate_flags_info.flags(vk::MemoryAllocateFlags::DEVICE_ADDRESS);
+        }
+
+        let memory = unsafe {
+            self.device.allocate_memory(
+                &vk::MemoryAllocateInfo::default()
+                    .allocation_size(memory_requirements.size)
+                    .memory_type_index(memory_type_index)
+                    .push_next(&mut allocate_flags_info),
+                None,
+            )
+        }?;
+
+        if let Err(err) = unsafe { self.device.bind_buffer_memory(handle, memory, 0) } {
+            unsafe { self.device.free_memory(memory, None) };
+            return Err(err.into());
+        }
+
+        let mapped_ptr = if desc.memory_location != MemoryLocation::GpuOnly {
+            match unsafe {
+                self.device
+                    .map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())
+            } {
+                Ok(ptr) => NonNull::new(ptr as *mut u8),
+                Err(err) => {
+                    unsafe { self.device.free_memory(memory, None) };
+                    return Err(err.into());
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok((memory, mapped_ptr))
+    }
+
+    fn allocate_image_memory(&self, handle: vk::Image) -> Result<vk::DeviceMemory, Error> {
+        let memory_requirements = unsafe { self.device.get_image_memory_requirements(handle) };
+
+        let memory_type_index = self
+            .find_memory_type_index(
+                memory_requirements.memory_type_bits,
+                vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            )
+            .ok_or(Error::VulkanMemoryTypeNotFound)?;
+
+        let memory = unsafe {
+            self.device.allocate_memory(
+                &vk::MemoryAllocateInfo::default()
+                    .allocation_size(memory_requirements.size)
+                    .memory_type_index(memory_type_index),
+                None,
+            )
+        }?;
+
+        if let Err(err) = unsafe { self.device.bind_image_memory(handle, memory, 0) } {
+            unsafe { self.device.free_memory(memory, None) };
+            return Err(err.into());
+        }
+
+        Ok(memory)
+    }
+
+    pub(crate) fn find_memory_type_index(
+        &self,
+        memory_type_bits: u32,
+        flags: vk::MemoryPropertyFlags,
+    ) -> Option<u32> {
+        let memory_properties = &self.physical_device.memory_properties;
+
+        (0..memory_properties.memory_type_count).find(|i| {
+            (memory_type_bits & (1 << i)) != 0
+                && memory_properties.memory_types[*i as usize]
+                    .property_flags
+                    .contains(flags)
+        })
+    }
+}
+
+impl Drop for VulkanDevice {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.device.device_wait_idle();
+
+            for (garbage, _) in self.garbage.get_mut().unwrap().drain(..) {
+                garbage.destroy(&self.device);
+            }
+
+            if let Some(bindless) = &self.bindless {
+                bindless.destroy(self);
+            }
+        }
+    }
+}
+
+fn queue_handles(device: &ash::Device, family_indices: [u32; 3]) -> [Arc<Mutex<vk::Queue>>; 3] {
+    let mut handles: Vec<(u32, Arc<Mutex<vk::Queue>>)> = Vec::new();
+
+    family_indices.map(|family_index| {
+        if let Some((_, handle)) = handles.iter().find(|(index, _)| *index == family_index) {
+            return handle.clone();
+        }
+
+        let handle = Arc::new(Mutex::new(unsafe {
+            device.get_device_queue(family_index, 0)
+        }));
+        handles.push((family_index, handle.clone()));
+
+        handle
+    })
+}
+
+fn image_create_info(desc: &TextureDesc) -> vk::ImageCreateInfo<'static> {
+    vk::ImageCreateInfo::default()
+        .image_type(vk_image_type(desc.dimension))
+        .format(vk_format(desc.format))
+        .extent(vk::Extent3D {
+            width: desc.width,
+            height: desc.height,
+            depth: desc.depth,
+        })
+        .mip_levels(desc.mip_levels)
+        .array_layers(desc.array_layers)
+        .samples(vk_sample_count(desc.sample_count))
+        .tiling(vk::ImageTiling::OPTIMAL)
+        .usage(vk_image_usage(desc.usage))
+        .sharing_mode(vk::SharingMode::EXCLUSIVE)
+        .initial_layout(vk::ImageLayout::UNDEFINED)
+}
+
+struct GraphicsPipelineState<'a> {
+    binding_layout: Arc<VulkanBindingLayout>,
+    rasterizer: &'a RasterizerState,
+    depth_stencil: &'a DepthStencilState,
+    blend: &'a BlendState,
+    render_target_formats: &'a RenderTargetFormats,
+}
+
+fn validate_spirv(code: &[u32]) -> Result<(), Error> {
+    if code.len() < 5 || code[0] != SPIRV_MAGIC {
+        return Err(Error::InvalidSpirV("missing SPIR-V magic number"));
+    }
+
+    // The version word is laid out as 0x00MMmm00.
+    if code[1] & 0xff0000ff != 0 || code[1] < MIN_SPIRV_VERSION {
+        return Err(Error::InvalidSpirV("malformed SPIR-V version"));
+    }
+
+    if code[1] > MAX_SPIRV_VERSION {
+        return Err(Error::InvalidSpirV(
+            "SPIR-V version is newer than Vulkan 1.3 supports",
+        ));
+    }
+
+    if code[3] == 0 {
+        return Err(Error::InvalidSpirV("SPIR-V id bound is zero"));
+    }
+
+    let mut words = &code[5..];
+
+    while let Some(word) = words.first() {
+        let word_count = (word >> 16) as usize;
+
+        if word_count == 0 || word_count > words.len() {
+            return Err(Error::InvalidSpirV(
+                "SPIR-V instruction word count is out of range",
+            ));
+        }
+
+        words = &words[word_count..];
+    }
+
+    Ok(())
+}
+
+fn shader_stage(
+    shader_module: &ShaderModule,
+    expected_kind: ShaderKind,
+) -> Result<vk::PipelineShaderStageCreateInfo<'_>, Error> {
+    let shader_module = shader_module.as_vulkan_shader_module();
+
+    if shader_module.kind() != expected_kind {
+        return Err(Error::InvalidShaderKind(
+            shader_module.kind(),
+            expected_kind,
+        ));
+    }
+
+    Ok(vk::PipelineShaderStageCreateInfo::default()
+        .stage(vk_shader_stage(shader_module.kind()))
+        .module(shader_module.handle)
+        .name(shader_module.entry_point()))
 }
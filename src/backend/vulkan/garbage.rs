@@ -0,0 +1,151 @@
+use crate::backend::vulkan::{VulkanAllocation, VulkanDevice};
+use ash::vk;
+use std::sync::{Arc, Mutex};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum VulkanGarbage {
+    Buffer(vk::Buffer),
+    Image(vk::Image),
+    ImageView(vk::ImageView),
+    Memory(vk::DeviceMemory),
+    ShaderModule(vk::ShaderModule),
+    Pipeline(vk::Pipeline),
+    PipelineLayout(vk::PipelineLayout),
+    CommandPool(vk::CommandPool),
+    DescriptorSetLayout(vk::DescriptorSetLayout),
+    DescriptorPool(vk::DescriptorPool),
+    Sampler(vk::Sampler),
+}
+
+impl VulkanGarbage {
+    pub(crate) unsafe fn destroy(self, device: &ash::Device) {
+        unsafe {
+            match self {
+                VulkanGarbage::Buffer(buffer) => device.destroy_buffer(buffer, None),
+                VulkanGarbage::Image(image) => device.destroy_image(image, None),
+                VulkanGarbage::ImageView(view) => device.destroy_image_view(view, None),
+                VulkanGarbage::Memory(memory) => device.free_memory(memory, None),
+                VulkanGarbage::ShaderModule(shader_module) => {
+                    device.destroy_shader_module(shader_module, None)
+                }
+                VulkanGarbage::Pipeline(pipeline) => device.destroy_pipeline(pipeline, None),
+                VulkanGarbage::PipelineLayout(layout) => {
+                    device.destroy_pipeline_layout(layout, None)
+                }
+                VulkanGarbage::CommandPool(command_pool) => {
+                    device.destroy_command_pool(command_pool, None)
+                }
+                VulkanGarbage::DescriptorSetLayout(layout) => {
+                    device.destroy_descriptor_set_layout(layout, None)
+                }
+                VulkanGarbage::DescriptorPool(pool) => device.destroy_descriptor_pool(pool, None),
+                VulkanGarbage::Sampler(sampler) => device.destroy_sampler(sampler, None),
+            }
+        }
+    }
+}
+
+// Shared between a resource and every command list that recorded it. The resource's handles are
+// only queued for destruction once the last holder lets go, which for a command list happens after
+// it has been submitted and reset.
+pub(crate) struct VulkanResourceLifetime {
+    device: Arc<VulkanDevice>,
+
+    pub(crate) allocation: VulkanAllocation,
+    garbage: Mutex<Vec<VulkanGarbage>>,
+}
+
+impl VulkanResourceLifetime {
+    pub(crate) fn new(device: Arc<VulkanDevice>, allocation: VulkanAllocation) -> Arc<Self> {
+        Arc::new(Self {
+            device,
+            allocation,
+            garbage: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub(crate) fn retire(&self, garbage: VulkanGarbage) {
+        self.garbage.lock().unwrap().push(garbage);
+    }
+}
+
+impl Drop for VulkanResourceLifetime {
+    fn drop(&mut self) {
+        for garbage in self.garbage.get_mut().unwrap().drain(..) {
+            self.device.destroy_deferred(garbage);
+        }
+
+        if let VulkanAllocation::Dedicated(memory) = self.allocation {
+            self.device.destroy_deferred(VulkanGarbage::Memory(memory));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BackendType, BufferDesc, BufferUsage, Device, DeviceDesc, Instance, InstanceDesc,
+        MemoryLocation, QueueType, ResourceState, WindowHandle,
+    };
+
+    fn test_device() -> Option<Device> {
+        let instance = Instance::new(&InstanceDesc {
+            backend_type: BackendType::Vulkan,
+            debug: false,
+            engine_name: "Test Engine".into(),
+            application_name: "Test App".into(),
+            window_handle: WindowHandle::Headless,
+        })
+        .ok()?;
+
+        let physical_devices = instance.get_physical_devices().ok()?;
+
+        instance
+            .create_device(&DeviceDesc {
+                physical_device: physical_devices.first()?,
+            })
+            .ok()
+    }
+
+    #[test]
+    fn buffer_dropped_before_submit_waits_for_submission() {
+        let Some(device) = test_device() else {
+            eprintln!("No Vulkan device available, skipping");
+            return;
+        };
+        let vulkan_device = device.as_vulkan_device().clone();
+        let direct_queue = device.get_direct_queue();
+
+        let buffer = device
+            .create_buffer(&BufferDesc {
+                name: "Dropped buffer".into(),
+                size: 256,
+                usage: BufferUsage::TRANSFER_DST,
+                memory_location: MemoryLocation::GpuOnly,
+            })
+            .unwrap();
+        let handle = VulkanGarbage::Buffer(buffer.as_vulkan_buffer().handle);
+
+        let mut command_list = device.create_command_list(QueueType::Direct).unwrap();
+        command_list.begin().unwrap();
+        command_list.transition_buffer(&buffer, ResourceState::TransferDst);
+
+        drop(buffer);
+        vulkan_device.collect_garbage().unwrap();
+        assert_eq!(vulkan_device.deferred_values(handle), None);
+
+        command_list.end().unwrap();
+        let value = direct_queue.submit(&[&command_list]).unwrap();
+        assert_eq!(vulkan_device.deferred_values(handle), None);
+
+        direct_queue.wait_for_value(value).unwrap();
+        command_list.begin().unwrap();
+
+        let values = vulkan_device.deferred_values(handle).unwrap();
+        assert!(values[0] >= value);
+
+        vulkan_device.collect_garbage().unwrap();
+        assert_eq!(vulkan_device.deferred_values(handle), None);
+    }
+}
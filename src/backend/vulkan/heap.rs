@@ -0,0 +1,42 @@
+use crate::Error;
+use crate::backend::vulkan::{VulkanDevice, VulkanGarbage};
+use ash::vk;
+use std::sync::Arc;
+
+pub struct VulkanHeap {
+    device: Arc<VulkanDevice>,
+
+    pub(crate) memory: vk::DeviceMemory,
+    size: u64,
+}
+
+impl VulkanHeap {
+    pub fn new(
+        device: Arc<VulkanDevice>,
+        memory: vk::DeviceMemory,
+        size: u64,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            device,
+            memory,
+            size,
+        })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Drop for VulkanHeap {
+    fn drop(&mut self) {
+        self.device
+            .destroy_deferred(VulkanGarbage::Memory(self.memory));
+    }
+}
+
+pub enum VulkanAllocation {
+    Dedicated(vk::DeviceMemory),
+    Placed(Arc<VulkanHeap>),
+    Swapchain,
+}
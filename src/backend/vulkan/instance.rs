@@ -2,13 +2,16 @@ use crate::backend::vulkan::{VulkanDevice, VulkanPhysicalDevice};
 use crate::{Device, DeviceDesc, Error, InstanceDesc, PhysicalDevice, WindowHandle};
 use ash::vk;
 use libc::strcmp;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct VulkanInstance {
     pub(crate) entry: ash::Entry,
     pub(crate) instance: ash::Instance,
+
+    pub(crate) swapchain_colorspace_supported: bool,
+    pub(crate) headless_surface_supported: bool,
 }
 
 impl VulkanInstance {
@@ -21,6 +24,8 @@ impl VulkanInstance {
             .engine_name(engine_name.as_c_str())
             .application_name(application_name.as_c_str());
 
+        let entry = unsafe { ash::Entry::load()? };
+
         let mut platform_extensions = vec![];
 
         let mut enabled_layer_names = vec![];
@@ -39,60 +44,84 @@ impl VulkanInstance {
                     .map(|ext| Ok(CString::new(ext)?))
                     .collect::<Result<Vec<CString>, Error>>()?;
             }
+            WindowHandle::Headless => {
+                platform_extensions = vec![c"VK_KHR_surface".to_owned()];
+            }
         }
 
         for extension in &platform_extensions {
             enabled_extension_names.push(extension.as_c_str().as_ptr());
         }
 
+        let instance_extensions = unsafe { entry.enumerate_instance_extension_properties(None)? };
+
+        let extension_supported = |name: &CStr| {
+            instance_extensions.iter().any(|extension| {
+                let result = unsafe { strcmp(name.as_ptr(), extension.extension_name.as_ptr()) };
+
+                result == 0
+            })
+        };
+
+        let swapchain_colorspace_supported = extension_supported(c"VK_EXT_swapchain_colorspace");
+
+        if swapchain_colorspace_supported {
+            enabled_extension_names.push(c"VK_EXT_swapchain_colorspace".as_ptr());
+        }
+
+        let headless_surface_supported = extension_supported(c"VK_EXT_headless_surface");
+
+        if headless_surface_supported {
+            enabled_extension_names.push(c"VK_EXT_headless_surface".as_ptr());
+        }
+
         let instance_create_info = vk::InstanceCreateInfo::default()
             .application_info(&application_info)
             .enabled_layer_names(&enabled_layer_names)
             .enabled_extension_names(&enabled_extension_names);
 
-        let entry = unsafe { ash::Entry::load()? };
-
         let instance = unsafe { entry.create_instance(&instance_create_info, None)? };
 
-        Ok(Self { entry, instance })
+        Ok(Self {
+            entry,
+            instance,
+            swapchain_colorspace_supported,
+            headless_surface_supported,
+        })
     }
 
     pub fn get_physical_devices(self: &Arc<VulkanInstance>) -> Result<Vec<PhysicalDevice>, Error> {
         let physical_devices = unsafe { self.instance.enumerate_physical_devices()? }
             .into_iter()
+            .filter(|physical_device| {
+                let properties = unsafe {
+                    self.instance
+                        .get_physical_device_properties(*physical_device)
+                };
+
+                properties.api_version >= vk::API_VERSION_1_3
+            })
             .map(|physical_device| {
                 let extensions = unsafe {
                     self.instance
                         .enumerate_device_extension_properties(physical_device)?
                 };
 
-                let mut has_mesh_shader = false;
-
-                for extension in &extensions {
-                    if unsafe {
-                        strcmp(
-                            c"VK_EXT_mesh_shader".as_ptr(),
-                            extension.extension_name.as_ptr(),
-                        )
-                    } == 0
-                    {
-                        has_mesh_shader = true;
-                    }
-                }
-
-                let supported = has_mesh_shader;
+                let extension_supported = |name: &CStr| {
+                    extensions.iter().any(|extension| {
+                        let result =
+                            unsafe { strcmp(name.as_ptr(), extension.extension_name.as_ptr()) };
 
-                Ok((physical_device, supported))
-            })
-            .collect::<Result<Vec<_>, vk::Result>>()?;
+                        result == 0
+                    })
+                };
 
-        let physical_devices = physical_devices
-            .into_iter()
-            .filter(|(_, supported)| *supported)
-            .map(|(physical_device, _)| {
                 Ok(PhysicalDevice::Vulkan(Arc::new(VulkanPhysicalDevice::new(
                     self.clone(),
                     physical_device,
+                    extension_supported(c"VK_EXT_mesh_shader"),
+                    extension_supported(c"VK_EXT_mutable_descriptor_type"),
+                    extension_supported(c"VK_EXT_descriptor_buffer"),
                 )?)))
             })
             .collect::<Result<Vec<_>, Error>>()?;
@@ -135,7 +164,9 @@ impl VulkanInstance {
             );
         }
 
-        if transfer_queue_family_index != direct_queue_family_index {
+        if transfer_queue_family_index != direct_queue_family_index
+            && transfer_queue_family_index != compute_queue_family_index
+        {
             device_queue_create_infos.push(
                 vk::DeviceQueueCreateInfo::default()
                     .queue_family_index(transfer_queue_family_index)
@@ -143,11 +174,77 @@ impl VulkanInstance {
             );
         }
 
-        let extensions = [c"VK_KHR_swapchain".as_ptr(), c"VK_EXT_mesh_shader".as_ptr()];
+        let mut extensions = vec![c"VK_KHR_swapchain".as_ptr()];
+
+        if vulkan_physical_device.mesh_shader_supported {
+            extensions.push(c"VK_EXT_mesh_shader".as_ptr());
+        }
+
+        if vulkan_physical_device.bindless_supported {
+            extensions.push(c"VK_EXT_mutable_descriptor_type".as_ptr());
+        }
+
+        if vulkan_physical_device.descriptor_buffer_supported {
+            extensions.push(c"VK_EXT_descriptor_buffer".as_ptr());
+        }
 
-        let device_create_info = vk::DeviceCreateInfo::default()
+        let mut vulkan_12_features = vk::PhysicalDeviceVulkan12Features::default()
+            .draw_indirect_count(vulkan_physical_device.draw_indirect_count_supported)
+            .timeline_semaphore(true)
+            .buffer_device_address(true);
+
+        if vulkan_physical_device.bindless_supported {
+            vulkan_12_features = vulkan_12_features
+                .runtime_descriptor_array(true)
+                .descriptor_binding_partially_bound(true)
+                .descriptor_binding_sampled_image_update_after_bind(true)
+                .descriptor_binding_storage_image_update_after_bind(true)
+                .descriptor_binding_storage_buffer_update_after_bind(true)
+                .descriptor_binding_uniform_buffer_update_after_bind(
+                    vulkan_physical_device.bindless_uniform_buffers_supported,
+                )
+                .shader_sampled_image_array_non_uniform_indexing(true)
+                .shader_storage_image_array_non_uniform_indexing(true)
+                .shader_storage_buffer_array_non_uniform_indexing(true)
+                .shader_uniform_buffer_array_non_uniform_indexing(
+                    vulkan_physical_device.bindless_uniform_buffers_supported,
+                );
+        }
+
+        let mut vulkan_13_features = vk::PhysicalDeviceVulkan13Features::default()
+            .dynamic_rendering(true)
+            .synchronization2(true)
+            .maintenance4(true);
+
+        let mut mesh_shader_features = vk::PhysicalDeviceMeshShaderFeaturesEXT::default()
+            .task_shader(vulkan_physical_device.task_shader_supported)
+            .mesh_shader(true);
+
+        let mut mutable_descriptor_type_features =
+            vk::PhysicalDeviceMutableDescriptorTypeFeaturesEXT::default()
+                .mutable_descriptor_type(true);
+
+        let mut descriptor_buffer_features =
+            vk::PhysicalDeviceDescriptorBufferFeaturesEXT::default().descriptor_buffer(true);
+
+        let mut device_create_info = vk::DeviceCreateInfo::default()
             .enabled_extension_names(&extensions)
-            .queue_create_infos(&device_queue_create_infos);
+            .queue_create_infos(&device_queue_create_infos)
+            .push_next(&mut vulkan_12_features)
+            .push_next(&mut vulkan_13_features);
+
+        if vulkan_physical_device.mesh_shader_supported {
+            device_create_info = device_create_info.push_next(&mut mesh_shader_features);
+        }
+
+        if vulkan_physical_device.bindless_supported {
+            device_create_info =
+                device_create_info.push_next(&mut mutable_descriptor_type_features);
+        }
+
+        if vulkan_physical_device.descriptor_buffer_supported {
+            device_create_info = device_create_info.push_next(&mut descriptor_buffer_features);
+        }
 
         let device = unsafe {
             self.instance
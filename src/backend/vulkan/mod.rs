@@ -1,11 +1,37 @@
+mod binding_layout;
+mod bindless;
+mod buffer;
+mod command_list;
+mod conv;
+mod descriptor_buffer;
+mod descriptor_set;
 mod device;
+mod garbage;
+mod heap;
 mod instance;
 mod physical_device;
+mod pipeline;
 mod queue;
+mod sampler;
 mod shader_module;
+mod swapchain;
+mod texture;
 
+pub use binding_layout::*;
+pub(crate) use bindless::*;
+pub use buffer::*;
+pub use command_list::*;
+pub(crate) use conv::*;
+pub(crate) use descriptor_buffer::*;
+pub use descriptor_set::*;
 pub use device::*;
+pub(crate) use garbage::*;
+pub use heap::*;
 pub use instance::*;
 pub use physical_device::*;
+pub use pipeline::*;
 pub use queue::*;
+pub use sampler::*;
 pub use shader_module::*;
+pub use swapchain::*;
+pub use texture::*;
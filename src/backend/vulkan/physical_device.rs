@@ -5,27 +5,128 @@ use std::ffi::CStr;
 use std::sync::Arc;
 
 pub struct VulkanPhysicalDevice {
-    instance: Arc<VulkanInstance>,
+    pub(crate) instance: Arc<VulkanInstance>,
 
     pub(crate) handle: vk::PhysicalDevice,
     pub(crate) properties: vk::PhysicalDeviceProperties,
+    pub(crate) memory_properties: vk::PhysicalDeviceMemoryProperties,
+    pub(crate) queue_family_properties: Vec<vk::QueueFamilyProperties>,
 
     pub(crate) name: String,
+
+    pub(crate) mesh_shader_supported: bool,
+    pub(crate) task_shader_supported: bool,
+    pub(crate) draw_indirect_count_supported: bool,
+    pub(crate) bindless_supported: bool,
+    pub(crate) bindless_uniform_buffers_supported: bool,
+    pub(crate) descriptor_buffer_supported: bool,
+    pub(crate) descriptor_buffer_properties:
+        vk::PhysicalDeviceDescriptorBufferPropertiesEXT<'static>,
 }
 
 impl VulkanPhysicalDevice {
-    pub fn new(instance: Arc<VulkanInstance>, handle: vk::PhysicalDevice) -> Result<Self, Error> {
+    pub fn new(
+        instance: Arc<VulkanInstance>,
+        handle: vk::PhysicalDevice,
+        mesh_shader_extension_supported: bool,
+        mutable_descriptor_type_supported: bool,
+        descriptor_buffer_extension_supported: bool,
+    ) -> Result<Self, Error> {
         let properties = unsafe { instance.instance.get_physical_device_properties(handle) };
+        let memory_properties = unsafe {
+            instance
+                .instance
+                .get_physical_device_memory_properties(handle)
+        };
+        let queue_family_properties = unsafe {
+            instance
+                .instance
+                .get_physical_device_queue_family_properties(handle)
+        };
 
         let name = unsafe { CStr::from_ptr(properties.device_name.as_ptr()) }
             .to_string_lossy()
             .to_string();
 
+        let mut vulkan_12_features = vk::PhysicalDeviceVulkan12Features::default();
+        let mut mesh_shader_features = vk::PhysicalDeviceMeshShaderFeaturesEXT::default();
+        let mut mutable_descriptor_type_features =
+            vk::PhysicalDeviceMutableDescriptorTypeFeaturesEXT::default();
+        let mut descriptor_buffer_features =
+            vk::PhysicalDeviceDescriptorBufferFeaturesEXT::default();
+
+        let mut features =
+            vk::PhysicalDeviceFeatures2::default().push_next(&mut vulkan_12_features);
+
+        if mesh_shader_extension_supported {
+            features = features.push_next(&mut mesh_shader_features);
+        }
+
+        if mutable_descriptor_type_supported {
+            features = features.push_next(&mut mutable_descriptor_type_features);
+        }
+
+        if descriptor_buffer_extension_supported {
+            features = features.push_next(&mut descriptor_buffer_features);
+        }
+
+        unsafe {
+            instance
+                .instance
+                .get_physical_device_features2(handle, &mut features)
+        };
+
+        let mesh_shader_supported = mesh_shader_features.mesh_shader == vk::TRUE;
+        let task_shader_supported =
+            mesh_shader_supported && mesh_shader_features.task_shader == vk::TRUE;
+        let draw_indirect_count_supported = vulkan_12_features.draw_indirect_count == vk::TRUE;
+
+        let bindless_supported = mutable_descriptor_type_features.mutable_descriptor_type
+            == vk::TRUE
+            && vulkan_12_features.runtime_descriptor_array == vk::TRUE
+            && vulkan_12_features.descriptor_binding_partially_bound == vk::TRUE
+            && vulkan_12_features.descriptor_binding_sampled_image_update_after_bind == vk::TRUE
+            && vulkan_12_features.descriptor_binding_storage_image_update_after_bind == vk::TRUE
+            && vulkan_12_features.descriptor_binding_storage_buffer_update_after_bind == vk::TRUE
+            && vulkan_12_features.shader_sampled_image_array_non_uniform_indexing == vk::TRUE
+            && vulkan_12_features.shader_storage_image_array_non_uniform_indexing == vk::TRUE
+            && vulkan_12_features.shader_storage_buffer_array_non_uniform_indexing == vk::TRUE;
+
+        let bindless_uniform_buffers_supported = bindless_supported
+            && vulkan_12_features.descriptor_binding_uniform_buffer_update_after_bind == vk::TRUE
+            && vulkan_12_features.shader_uniform_buffer_array_non_uniform_indexing == vk::TRUE;
+
+        let descriptor_buffer_supported = bindless_supported
+            && vulkan_12_features.buffer_device_address == vk::TRUE
+            && descriptor_buffer_features.descriptor_buffer == vk::TRUE;
+
+        let mut descriptor_buffer_properties =
+            vk::PhysicalDeviceDescriptorBufferPropertiesEXT::default();
+
+        if descriptor_buffer_supported {
+            unsafe {
+                instance.instance.get_physical_device_properties2(
+                    handle,
+                    &mut vk::PhysicalDeviceProperties2::default()
+                        .push_next(&mut descriptor_buffer_properties),
+                )
+            };
+        }
+
         Ok(Self {
             instance,
             handle,
             properties,
+            memory_properties,
+            queue_family_properties,
             name,
+            mesh_shader_supported,
+            task_shader_supported,
+            draw_indirect_count_supported,
+            bindless_supported,
+            bindless_uniform_buffers_supported,
+            descriptor_buffer_supported,
+            descriptor_buffer_properties,
         })
     }
 }
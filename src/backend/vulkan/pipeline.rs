@@ -0,0 +1,39 @@
+use crate::Error;
+use crate::backend::vulkan::{VulkanBindingLayout, VulkanDevice, VulkanGarbage};
+use ash::vk;
+use std::sync::Arc;
+
+pub struct VulkanPipeline {
+    device: Arc<VulkanDevice>,
+
+    pub(crate) handle: vk::Pipeline,
+    pub(crate) binding_layout: Arc<VulkanBindingLayout>,
+    pub(crate) bind_point: vk::PipelineBindPoint,
+}
+
+impl VulkanPipeline {
+    pub fn new(
+        device: Arc<VulkanDevice>,
+        handle: vk::Pipeline,
+        binding_layout: Arc<VulkanBindingLayout>,
+        bind_point: vk::PipelineBindPoint,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            device,
+            handle,
+            binding_layout,
+            bind_point,
+        })
+    }
+
+    pub fn binding_layout(&self) -> &Arc<VulkanBindingLayout> {
+        &self.binding_layout
+    }
+}
+
+impl Drop for VulkanPipeline {
+    fn drop(&mut self) {
+        self.device
+            .destroy_deferred(VulkanGarbage::Pipeline(self.handle));
+    }
+}
@@ -1,11 +1,180 @@
+use crate::backend::vulkan::{VulkanCommandList, VulkanSwapchain};
+use crate::{Error, QueueType};
 use ash::vk;
+use std::sync::{Arc, Mutex};
 
 pub struct VulkanQueue {
-    handle: vk::Queue,
+    device: ash::Device,
+
+    // Queue types that resolve to the same family share one `VkQueue`, and with it this lock.
+    pub(crate) handle: Arc<Mutex<vk::Queue>>,
+    queue_type: QueueType,
+
+    pub(crate) timeline_semaphore: vk::Semaphore,
+    timeline_value: Mutex<u64>,
+
+    pending_waits: Mutex<Vec<vk::Semaphore>>,
 }
 
 impl VulkanQueue {
-    pub fn new(handle: vk::Queue) -> Self {
-        Self { handle }
+    pub fn new(
+        device: ash::Device,
+        handle: Arc<Mutex<vk::Queue>>,
+        queue_type: QueueType,
+    ) -> Result<Self, Error> {
+        let timeline_semaphore = unsafe {
+            device.create_semaphore(
+                &vk::SemaphoreCreateInfo::default().push_next(
+                    &mut vk::SemaphoreTypeCreateInfo::default()
+                        .semaphore_type(vk::SemaphoreType::TIMELINE)
+                        .initial_value(0),
+                ),
+                None,
+            )
+        }?;
+
+        Ok(Self {
+            device,
+            handle,
+            queue_type,
+            timeline_semaphore,
+            timeline_value: Mutex::new(0),
+            pending_waits: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    pub fn submit(
+        &self,
+        command_lists: &[&VulkanCommandList],
+        waits: &[(&VulkanQueue, u64)],
+    ) -> Result<u64, Error> {
+        let command_buffer_infos = command_lists
+            .iter()
+            .map(|command_list| {
+                vk::CommandBufferSubmitInfo::default().command_buffer(command_list.command_buffer)
+            })
+            .collect::<Vec<_>>();
+
+        let wait_semaphore_infos = waits
+            .iter()
+            .map(|(queue, value)| {
+                vk::SemaphoreSubmitInfo::default()
+                    .semaphore(queue.timeline_semaphore)
+                    .value(*value)
+                    .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+            })
+            .collect::<Vec<_>>();
+
+        let signal_value =
+            self.submit_internal(&command_buffer_infos, wait_semaphore_infos, &[])?;
+
+        if let Some(command_list) = command_lists.first() {
+            command_list.device.collect_garbage()?;
+        }
+
+        Ok(signal_value)
+    }
+
+    pub(crate) fn submit_internal(
+        &self,
+        command_buffer_infos: &[vk::CommandBufferSubmitInfo],
+        mut wait_semaphore_infos: Vec<vk::SemaphoreSubmitInfo>,
+        binary_signals: &[vk::Semaphore],
+    ) -> Result<u64, Error> {
+        let pending_waits = std::mem::take(&mut *self.pending_waits.lock().unwrap());
+
+        wait_semaphore_infos.extend(pending_waits.iter().map(|semaphore| {
+            vk::SemaphoreSubmitInfo::default()
+                .semaphore(*semaphore)
+                .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+        }));
+
+        let mut timeline_value = self.timeline_value.lock().unwrap();
+        let signal_value = *timeline_value + 1;
+
+        let mut signal_semaphore_infos = vec![
+            vk::SemaphoreSubmitInfo::default()
+                .semaphore(self.timeline_semaphore)
+                .value(signal_value)
+                .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS),
+        ];
+
+        signal_semaphore_infos.extend(binary_signals.iter().map(|semaphore| {
+            vk::SemaphoreSubmitInfo::default()
+                .semaphore(*semaphore)
+                .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+        }));
+
+        let submit_info = vk::SubmitInfo2::default()
+            .wait_semaphore_infos(&wait_semaphore_infos)
+            .command_buffer_infos(command_buffer_infos)
+            .signal_semaphore_infos(&signal_semaphore_infos);
+
+        let handle = self.handle.lock().unwrap();
+
+        unsafe {
+            self.device
+                .queue_submit2(*handle, &[submit_info], vk::Fence::null())?
+        };
+
+        *timeline_value = signal_value;
+
+        Ok(signal_value)
+    }
+
+    pub(crate) fn add_pending_wait(&self, semaphore: vk::Semaphore) {
+        self.pending_waits.lock().unwrap().push(semaphore);
+    }
+
+    pub fn present(&self, swapchain: &VulkanSwapchain) -> Result<(), Error> {
+        if self.queue_type != QueueType::Direct {
+            return Err(Error::Unsupported(
+                "Presentation is only supported on the direct queue",
+            ));
+        }
+
+        swapchain.present(self)
+    }
+
+    pub fn wait_for_value(&self, value: u64) -> Result<(), Error> {
+        unsafe {
+            self.device.wait_semaphores(
+                &vk::SemaphoreWaitInfo::default()
+                    .semaphores(std::slice::from_ref(&self.timeline_semaphore))
+                    .values(std::slice::from_ref(&value)),
+                u64::MAX,
+            )?
+        };
+
+        Ok(())
+    }
+
+    pub fn submitted_value(&self) -> u64 {
+        *self.timeline_value.lock().unwrap()
+    }
+
+    pub fn completed_value(&self) -> Result<u64, Error> {
+        Ok(unsafe {
+            self.device
+                .get_semaphore_counter_value(self.timeline_semaphore)?
+        })
+    }
+
+    pub fn wait_idle(&self) -> Result<(), Error> {
+        let handle = self.handle.lock().unwrap();
+
+        unsafe { self.device.queue_wait_idle(*handle)? };
+
+        Ok(())
+    }
+}
+
+impl Drop for VulkanQueue {
+    fn drop(&mut self) {
+        unsafe { self.device.destroy_semaphore(self.timeline_semaphore, None) };
     }
 }
@@ -0,0 +1,37 @@
+use crate::backend::vulkan::{VulkanDevice, VulkanGarbage};
+use crate::{Error, SamplerDesc};
+use ash::vk;
+use std::sync::Arc;
+
+pub struct VulkanSampler {
+    device: Arc<VulkanDevice>,
+
+    pub(crate) handle: vk::Sampler,
+
+    desc: SamplerDesc,
+}
+
+impl VulkanSampler {
+    pub fn new(
+        device: Arc<VulkanDevice>,
+        handle: vk::Sampler,
+        desc: SamplerDesc,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            device,
+            handle,
+            desc,
+        })
+    }
+
+    pub fn desc(&self) -> &SamplerDesc {
+        &self.desc
+    }
+}
+
+impl Drop for VulkanSampler {
+    fn drop(&mut self) {
+        self.device
+            .destroy_deferred(VulkanGarbage::Sampler(self.handle));
+    }
+}
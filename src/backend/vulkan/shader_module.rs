@@ -1,22 +1,52 @@
-use crate::Error;
-use crate::backend::vulkan::VulkanDevice;
+use crate::backend::vulkan::{VulkanDevice, VulkanGarbage};
+use crate::{Error, ShaderKind, ShaderReflection};
 use ash::vk;
+use std::ffi::{CStr, CString};
 use std::sync::Arc;
 
 pub struct VulkanShaderModule {
     device: Arc<VulkanDevice>,
 
-    handle: vk::ShaderModule,
+    pub(crate) handle: vk::ShaderModule,
+
+    kind: ShaderKind,
+    entry_point: CString,
+    reflection: ShaderReflection,
 }
 
 impl VulkanShaderModule {
-    pub fn new(device: Arc<VulkanDevice>, handle: vk::ShaderModule) -> Result<Self, Error> {
-        Ok(VulkanShaderModule { device, handle })
+    pub fn new(
+        device: Arc<VulkanDevice>,
+        handle: vk::ShaderModule,
+        kind: ShaderKind,
+        entry_point: CString,
+        reflection: ShaderReflection,
+    ) -> Result<Self, Error> {
+        Ok(VulkanShaderModule {
+            device,
+            handle,
+            kind,
+            entry_point,
+            reflection,
+        })
+    }
+
+    pub fn kind(&self) -> ShaderKind {
+        self.kind
+    }
+
+    pub fn entry_point(&self) -> &CStr {
+        &self.entry_point
+    }
+
+    pub fn reflection(&self) -> &ShaderReflection {
+        &self.reflection
     }
 }
 
 impl Drop for VulkanShaderModule {
     fn drop(&mut self) {
-        unsafe { self.device.device.destroy_shader_module(self.handle, None) };
+        self.device
+            .destroy_deferred(VulkanGarbage::ShaderModule(self.handle));
     }
 }
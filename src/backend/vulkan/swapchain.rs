@@ -0,0 +1,545 @@
+use crate::backend::vulkan::*;
+use crate::{
+    ColorSpace, Error, Format, PresentMode, QueueType, SwapchainDesc, SwapchainImage, Texture,
+    TextureDesc, TextureDimension, TextureUsage, WindowHandle,
+};
+use ash::vk;
+use ash::vk::Handle;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+pub struct VulkanSwapchain {
+    device: Arc<VulkanDevice>,
+
+    surface_instance: ash::khr::surface::Instance,
+    swapchain_device: ash::khr::swapchain::Device,
+
+    surface: vk::SurfaceKHR,
+
+    format: Format,
+    color_space: ColorSpace,
+
+    requested_present_mode: PresentMode,
+    requested_image_count: u32,
+
+    state: Mutex<VulkanSwapchainState>,
+}
+
+struct VulkanSwapchainState {
+    requested_extent: vk::Extent2D,
+    needs_recreate: bool,
+    resized: bool,
+
+    images: Option<VulkanSwapchainImages>,
+
+    current_image: Option<(u32, usize)>,
+}
+
+struct VulkanSwapchainImages {
+    handle: vk::SwapchainKHR,
+    extent: vk::Extent2D,
+    present_mode: PresentMode,
+
+    textures: Vec<Arc<Texture>>,
+
+    acquire_semaphores: Vec<vk::Semaphore>,
+    acquire_values: Vec<u64>,
+    next_acquire_semaphore: usize,
+
+    present_semaphores: Vec<vk::Semaphore>,
+}
+
+impl VulkanSwapchain {
+    pub fn new(device: Arc<VulkanDevice>, desc: &SwapchainDesc) -> Result<Self, Error> {
+        let instance = &device.physical_device.instance;
+
+        let surface_instance =
+            ash::khr::surface::Instance::new(&instance.entry, &instance.instance);
+        let swapchain_device = ash::khr::swapchain::Device::new(&instance.instance, &device.device);
+
+        let surface = match desc.window_handle {
+            WindowHandle::Sdl(sdl_window) => vk::SurfaceKHR::from_raw(
+                sdl_window.vulkan_create_surface(instance.instance.handle().as_raw() as _)? as u64,
+            ),
+            WindowHandle::Headless => {
+                if !instance.headless_surface_supported {
+                    return Err(Error::Unsupported(
+                        "VK_EXT_headless_surface is not supported",
+                    ));
+                }
+
+                let headless_surface_instance =
+                    ash::ext::headless_surface::Instance::new(&instance.entry, &instance.instance);
+
+                unsafe {
+                    headless_surface_instance
+                        .create_headless_surface(&vk::HeadlessSurfaceCreateInfoEXT::default(), None)
+                }?
+            }
+        };
+
+        let physical_device = device.physical_device.handle;
+        let queue_family_index = device.queue_family_index(QueueType::Direct);
+
+        let present_supported = unsafe {
+            surface_instance.get_physical_device_surface_support(
+                physical_device,
+                queue_family_index,
+                surface,
+            )
+        }?;
+
+        if !present_supported {
+            unsafe { surface_instance.destroy_surface(surface, None) };
+
+            return Err(Error::Unsupported(
+                "The direct queue cannot present to this surface",
+            ));
+        }
+
+        let surface_formats = unsafe {
+            surface_instance.get_physical_device_surface_formats(physical_device, surface)
+        }?;
+
+        let mut candidates = Vec::new();
+
+        if instance.swapchain_colorspace_supported {
+            match desc.color_space {
+                ColorSpace::SrgbNonLinear => {}
+                ColorSpace::Hdr10St2084 => candidates.extend([
+                    (desc.format, ColorSpace::Hdr10St2084),
+                    (Format::A2B10G10R10Unorm, ColorSpace::Hdr10St2084),
+                ]),
+                ColorSpace::ExtendedSrgbLinear => candidates.extend([
+                    (desc.format, ColorSpace::ExtendedSrgbLinear),
+                    (Format::R16G16B16A16Float, ColorSpace::ExtendedSrgbLinear),
+                ]),
+            }
+        }
+
+        candidates.extend(
+            [
+                desc.format,
+                Format::B8G8R8A8Srgb,
+                Format::R8G8B8A8Srgb,
+                Format::B8G8R8A8Unorm,
+                Format::R8G8B8A8Unorm,
+            ]
+            .map(|format| (format, ColorSpace::SrgbNonLinear)),
+        );
+
+        let surface_format = candidates.into_iter().find(|(format, color_space)| {
+            surface_formats.iter().any(|surface_format| {
+                surface_format.format == vk_format(*format)
+                    && surface_format.color_space == vk_color_space(*color_space)
+            })
+        });
+
+        let Some((format, color_space)) = surface_format else {
+            unsafe { surface_instance.destroy_surface(surface, None) };
+
+            return Err(Error::Unsupported("No supported swapchain format"));
+        };
+
+        let swapchain = Self {
+            device,
+            surface_instance,
+            swapchain_device,
+            surface,
+            format,
+            color_space,
+            requested_present_mode: desc.present_mode,
+            requested_image_count: desc.image_count,
+            state: Mutex::new(VulkanSwapchainState {
+                requested_extent: vk::Extent2D {
+                    width: desc.width,
+                    height: desc.height,
+                },
+                needs_recreate: false,
+                resized: false,
+                images: None,
+                current_image: None,
+            }),
+        };
+
+        {
+            let mut state = swapchain.state.lock().unwrap();
+
+            swapchain.recreate(&mut state)?;
+            state.resized = false;
+        }
+
+        Ok(swapchain)
+    }
+
+    pub fn acquire_next_image(&self) -> Result<Option<SwapchainImage>, Error> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        if state.needs_recreate || state.images.is_none() {
+            self.recreate(state)?;
+        }
+
+        loop {
+            let Some(images) = state.images.as_mut() else {
+                return Ok(None);
+            };
+
+            let acquire_index = images.next_acquire_semaphore;
+            let acquire_semaphore = images.acquire_semaphores[acquire_index];
+
+            self.device
+                .direct_queue
+                .wait_for_value(images.acquire_values[acquire_index])?;
+
+            let result = unsafe {
+                self.swapchain_device.acquire_next_image(
+                    images.handle,
+                    u64::MAX,
+                    acquire_semaphore,
+                    vk::Fence::null(),
+                )
+            };
+
+            let (image_index, suboptimal) = match result {
+                Ok(result) => result,
+                Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                    self.recreate(state)?;
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            self.device.direct_queue.add_pending_wait(acquire_semaphore);
+
+            images.next_acquire_semaphore = (acquire_index + 1) % images.acquire_semaphores.len();
+
+            let image = SwapchainImage {
+                texture: images.textures[image_index as usize].clone(),
+                width: images.extent.width,
+                height: images.extent.height,
+                resized: std::mem::take(&mut state.resized),
+            };
+
+            state.needs_recreate |= suboptimal;
+            state.current_image = Some((image_index, acquire_index));
+
+            return Ok(Some(image));
+        }
+    }
+
+    pub(crate) fn present(&self, queue: &VulkanQueue) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let (image_index, acquire_index) = state
+            .current_image
+            .take()
+            .ok_or(Error::Unsupported("No swapchain image was acquired"))?;
+
+        let images = state.images.as_mut().unwrap();
+
+        debug_assert!(
+            *images.textures[image_index as usize]
+                .as_vulkan_texture()
+                .states
+                .lock()
+                .unwrap()
+                .first()
+                .unwrap()
+                == crate::ResourceState::Present,
+            "Swapchain images must be transitioned to ResourceState::Present before presenting"
+        );
+
+        let present_semaphore = images.present_semaphores[image_index as usize];
+
+        images.acquire_values[acquire_index] =
+            queue.submit_internal(&[], Vec::new(), &[present_semaphore])?;
+
+        let queue_handle = queue.handle.lock().unwrap();
+
+        let result = unsafe {
+            self.swapchain_device.queue_present(
+                *queue_handle,
+                &vk::PresentInfoKHR::default()
+                    .wait_semaphores(std::slice::from_ref(&present_semaphore))
+                    .swapchains(std::slice::from_ref(&images.handle))
+                    .image_indices(std::slice::from_ref(&image_index)),
+            )
+        };
+
+        match result {
+            Ok(suboptimal) => state.needs_recreate |= suboptimal,
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => state.needs_recreate = true,
+            Err(err) => return Err(err.into()),
+        }
+
+        Ok(())
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        let mut state = self.state.lock().unwrap();
+
+        state.requested_extent = vk::Extent2D { width, height };
+        state.needs_recreate = true;
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn width(&self) -> u32 {
+        let state = self.state.lock().unwrap();
+
+        state
+            .images
+            .as_ref()
+            .map_or(0, |images| images.extent.width)
+    }
+
+    pub fn height(&self) -> u32 {
+        let state = self.state.lock().unwrap();
+
+        state
+            .images
+            .as_ref()
+            .map_or(0, |images| images.extent.height)
+    }
+
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        let state = self.state.lock().unwrap();
+
+        state
+            .images
+            .as_ref()
+            .map_or(self.requested_present_mode, |images| images.present_mode)
+    }
+
+    pub fn image_count(&self) -> u32 {
+        let state = self.state.lock().unwrap();
+
+        state
+            .images
+            .as_ref()
+            .map_or(0, |images| images.textures.len() as u32)
+    }
+
+    fn recreate(&self, state: &mut VulkanSwapchainState) -> Result<(), Error> {
+        self.device.direct_queue.wait_idle()?;
+
+        let old_images = state.images.take();
+
+        let images = self.create_images(
+            state.requested_extent,
+            old_images
+                .as_ref()
+                .map_or(vk::SwapchainKHR::null(), |images| images.handle),
+        );
+
+        if let Some(old_images) = old_images {
+            self.destroy_images(old_images);
+        }
+
+        state.images = images?;
+        state.needs_recreate = false;
+        state.resized = state.images.is_some();
+        state.current_image = None;
+
+        Ok(())
+    }
+
+    fn create_images(
+        &self,
+        requested_extent: vk::Extent2D,
+        old_swapchain: vk::SwapchainKHR,
+    ) -> Result<Option<VulkanSwapchainImages>, Error> {
+        let physical_device = self.device.physical_device.handle;
+
+        let capabilities = unsafe {
+            self.surface_instance
+                .get_physical_device_surface_capabilities(physical_device, self.surface)
+        }?;
+
+        let present_modes = unsafe {
+            self.surface_instance
+                .get_physical_device_surface_present_modes(physical_device, self.surface)
+        }?;
+
+        let present_mode = present_mode_fallbacks(self.requested_present_mode)
+            .iter()
+            .copied()
+            .find(|present_mode| present_modes.contains(&vk_present_mode(*present_mode)))
+            .unwrap_or(PresentMode::Fifo);
+
+        let extent = if capabilities.current_extent.width != u32::MAX {
+            capabilities.current_extent
+        } else {
+            vk::Extent2D {
+                width: requested_extent.width.clamp(
+                    capabilities.min_image_extent.width,
+                    capabilities.max_image_extent.width,
+                ),
+                height: requested_extent.height.clamp(
+                    capabilities.min_image_extent.height,
+                    capabilities.max_image_extent.height,
+                ),
+            }
+        };
+
+        if extent.width == 0 || extent.height == 0 {
+            return Ok(None);
+        }
+
+        let mut image_count = self.requested_image_count.max(capabilities.min_image_count);
+        if capabilities.max_image_count != 0 {
+            image_count = image_count.min(capabilities.max_image_count);
+        }
+
+        let mut usage = TextureUsage::RENDER_TARGET | TextureUsage::TRANSFER_DST;
+        if capabilities
+            .supported_usage_flags
+            .contains(vk::ImageUsageFlags::TRANSFER_SRC)
+        {
+            usage |= TextureUsage::TRANSFER_SRC;
+        }
+
+        let composite_alpha = [
+            vk::CompositeAlphaFlagsKHR::OPAQUE,
+            vk::CompositeAlphaFlagsKHR::INHERIT,
+            vk::CompositeAlphaFlagsKHR::PRE_MULTIPLIED,
+            vk::CompositeAlphaFlagsKHR::POST_MULTIPLIED,
+        ]
+        .into_iter()
+        .find(|composite_alpha| {
+            capabilities
+                .supported_composite_alpha
+                .contains(*composite_alpha)
+        })
+        .unwrap_or(vk::CompositeAlphaFlagsKHR::OPAQUE);
+
+        let handle = unsafe {
+            self.swapchain_device.create_swapchain(
+                &vk::SwapchainCreateInfoKHR::default()
+                    .surface(self.surface)
+                    .min_image_count(image_count)
+                    .image_format(vk_format(self.format))
+                    .image_color_space(vk_color_space(self.color_space))
+                    .image_extent(extent)
+                    .image_array_layers(1)
+                    .image_usage(vk_image_usage(usage))
+                    .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
+                    .pre_transform(capabilities.current_transform)
+                    .composite_alpha(composite_alpha)
+                    .present_mode(vk_present_mode(present_mode))
+                    .clipped(true)
+                    .old_swapchain(old_swapchain),
+                None,
+            )
+        }?;
+
+        let images = unsafe { self.swapchain_device.get_swapchain_images(handle) }?;
+
+        let textures = images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| {
+                let desc = TextureDesc {
+                    name: Cow::Owned(format!("Swapchain image {index}")),
+                    dimension: TextureDimension::D2,
+                    width: extent.width,
+                    height: extent.height,
+                    depth: 1,
+                    array_layers: 1,
+                    mip_levels: 1,
+                    sample_count: 1,
+                    format: self.format,
+                    usage,
+                };
+
+                let view = self.device.create_image_view(*image, &desc)?;
+
+                Ok(Arc::new(Texture::Vulkan(VulkanTexture::new(
+                    self.device.clone(),
+                    *image,
+                    view,
+                    VulkanAllocation::Swapchain,
+                    desc,
+                )?)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let create_semaphore = || unsafe {
+            self.device
+                .device
+                .create_semaphore(&vk::SemaphoreCreateInfo::default(), None)
+        };
+
+        let acquire_semaphores = (0..=images.len())
+            .map(|_| create_semaphore())
+            .collect::<Result<Vec<_>, vk::Result>>()?;
+
+        let present_semaphores = (0..images.len())
+            .map(|_| create_semaphore())
+            .collect::<Result<Vec<_>, vk::Result>>()?;
+
+        Ok(Some(VulkanSwapchainImages {
+            handle,
+            extent,
+            present_mode,
+            textures,
+            acquire_values: vec![0; acquire_semaphores.len()],
+            acquire_semaphores,
+            next_acquire_semaphore: 0,
+            present_semaphores,
+        }))
+    }
+
+    fn destroy_images(&self, mut images: VulkanSwapchainImages) {
+        images.textures.clear();
+
+        unsafe {
+            for semaphore in images
+                .acquire_semaphores
+                .drain(..)
+                .chain(images.present_semaphores.drain(..))
+            {
+                self.device.device.destroy_semaphore(semaphore, None);
+            }
+
+            self.swapchain_device.destroy_swapchain(images.handle, None);
+        }
+    }
+}
+
+impl Drop for VulkanSwapchain {
+    fn drop(&mut self) {
+        let _ = self.device.direct_queue.wait_idle();
+
+        if let Some(images) = self.state.get_mut().unwrap().images.take() {
+            self.destroy_images(images);
+        }
+
+        unsafe { self.surface_instance.destroy_surface(self.surface, None) };
+    }
+}
+
+fn present_mode_fallbacks(present_mode: PresentMode) -> &'static [PresentMode] {
+    match present_mode {
+        PresentMode::Fifo => &[PresentMode::Fifo],
+        PresentMode::FifoRelaxed => &[PresentMode::FifoRelaxed, PresentMode::Fifo],
+        PresentMode::Mailbox => &[
+            PresentMode::Mailbox,
+            PresentMode::Immediate,
+            PresentMode::Fifo,
+        ],
+        PresentMode::Immediate => &[
+            PresentMode::Immediate,
+            PresentMode::Mailbox,
+            PresentMode::Fifo,
+        ],
+    }
+}
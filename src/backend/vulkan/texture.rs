@@ -0,0 +1,51 @@
+use crate::backend::vulkan::{
+    VulkanAllocation, VulkanDevice, VulkanGarbage, VulkanResourceLifetime,
+};
+use crate::{Error, ResourceState, TextureDesc};
+use ash::vk;
+use std::sync::{Arc, Mutex};
+
+pub struct VulkanTexture {
+    pub(crate) handle: vk::Image,
+    pub(crate) view: vk::ImageView,
+    pub(crate) lifetime: Arc<VulkanResourceLifetime>,
+
+    pub(crate) states: Mutex<Vec<ResourceState>>,
+
+    desc: TextureDesc,
+}
+
+impl VulkanTexture {
+    pub fn new(
+        device: Arc<VulkanDevice>,
+        handle: vk::Image,
+        view: vk::ImageView,
+        allocation: VulkanAllocation,
+        desc: TextureDesc,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            handle,
+            view,
+            lifetime: VulkanResourceLifetime::new(device, allocation),
+            states: Mutex::new(vec![
+                ResourceState::Undefined;
+                (desc.mip_levels * desc.array_layers) as usize
+            ]),
+            desc,
+        })
+    }
+
+    pub fn desc(&self) -> &TextureDesc {
+        &self.desc
+    }
+}
+
+impl Drop for VulkanTexture {
+    fn drop(&mut self) {
+        self.lifetime.retire(VulkanGarbage::ImageView(self.view));
+
+        if !matches!(self.lifetime.allocation, VulkanAllocation::Swapchain) {
+            self.lifetime.retire(VulkanGarbage::Image(self.handle));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{Buffer, QueueType, Texture};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceState {
+    Undefined,
+    General,
+    VertexBuffer,
+    IndexBuffer,
+    IndirectArgument,
+    UniformBuffer,
+    ShaderResource,
+    UnorderedAccess,
+    RenderTarget,
+    DepthStencilWrite,
+    DepthStencilRead,
+    TransferSrc,
+    TransferDst,
+    Present,
+}
+
+impl ResourceState {
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            ResourceState::General
+                | ResourceState::UnorderedAccess
+                | ResourceState::RenderTarget
+                | ResourceState::DepthStencilWrite
+                | ResourceState::TransferDst
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureSubresourceRange {
+    pub base_mip_level: u32,
+    pub mip_level_count: u32,
+    pub base_array_layer: u32,
+    pub array_layer_count: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QueueTransfer {
+    Release { dst_queue: QueueType },
+    Acquire { src_queue: QueueType },
+}
+
+#[derive(Copy, Clone)]
+pub struct BufferBarrier<'a> {
+    pub buffer: &'a Buffer,
+    pub old_state: ResourceState,
+    pub new_state: ResourceState,
+    pub queue_transfer: Option<QueueTransfer>,
+}
+
+#[derive(Copy, Clone)]
+pub struct TextureBarrier<'a> {
+    pub texture: &'a Texture,
+    pub range: Option<TextureSubresourceRange>,
+    pub old_state: ResourceState,
+    pub new_state: ResourceState,
+    pub discard_contents: bool,
+    pub queue_transfer: Option<QueueTransfer>,
+}
@@ -0,0 +1,358 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+use crate::{Error, SamplerDesc, ShaderReflection, ShaderStages};
+
+pub(crate) const BINDLESS_SPACE: u32 = 3;
+
+#[derive(Clone)]
+pub enum BindingLayout {
+    #[cfg(feature = "vulkan")]
+    Vulkan(Arc<VulkanBindingLayout>),
+
+    #[cfg(feature = "metal")]
+    Metal(Arc<MetalBindingLayout>),
+}
+
+impl BindingLayout {
+    pub fn desc(&self) -> &BindingLayoutDesc {
+        match self {
+            #[cfg(feature = "vulkan")]
+            BindingLayout::Vulkan(vulkan_binding_layout) => vulkan_binding_layout.desc(),
+
+            #[cfg(feature = "metal")]
+            BindingLayout::Metal(metal_binding_layout) => metal_binding_layout.desc(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_binding_layout(&self) -> &Arc<VulkanBindingLayout> {
+        match self {
+            BindingLayout::Vulkan(vulkan_binding_layout) => vulkan_binding_layout,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_binding_layout(&self) -> &Arc<MetalBindingLayout> {
+        match self {
+            BindingLayout::Metal(metal_binding_layout) => metal_binding_layout,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BindingLayoutDesc {
+    pub name: Cow<'static, str>,
+    pub tables: Vec<DescriptorTableLayout>,
+    pub push_constants: Option<PushConstantRange>,
+    pub static_samplers: Vec<StaticSampler>,
+    pub bindless: bool,
+}
+
+impl BindingLayoutDesc {
+    pub fn table_count(&self) -> u32 {
+        self.static_samplers
+            .iter()
+            .map(|sampler| sampler.space + 1)
+            .chain([self.tables.len() as u32])
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn from_reflection(
+        name: Cow<'static, str>,
+        reflections: &[&ShaderReflection],
+    ) -> Result<Self, Error> {
+        let mut desc = BindingLayoutDesc {
+            name,
+            ..Default::default()
+        };
+
+        for reflection in reflections {
+            let stages = ShaderStages::from(reflection.kind);
+
+            for resource in &reflection.bindings {
+                if resource.space == BINDLESS_SPACE {
+                    desc.bindless = true;
+                    continue;
+                }
+
+                let count = resource.count.ok_or(Error::BindingLayoutConflict {
+                    space: resource.space,
+                    binding: resource.binding,
+                    reason: "unbounded descriptor arrays are only supported in the bindless heap",
+                })?;
+
+                if desc.tables.len() <= resource.space as usize {
+                    desc.tables
+                        .resize_with(resource.space as usize + 1, Default::default);
+                }
+
+                let bindings = &mut desc.tables[resource.space as usize].bindings;
+
+                match bindings
+                    .iter_mut()
+                    .find(|binding| binding.binding == resource.binding)
+                {
+                    Some(binding) => {
+                        if binding.descriptor_type != resource.descriptor_type {
+                            return Err(Error::BindingLayoutConflict {
+                                space: resource.space,
+                                binding: resource.binding,
+                                reason: "stages use different descriptor types",
+                            });
+                        }
+
+                        if binding.count != count {
+                            return Err(Error::BindingLayoutConflict {
+                                space: resource.space,
+                                binding: resource.binding,
+                                reason: "stages use different array sizes",
+                            });
+                        }
+
+                        binding.stages |= stages;
+                    }
+                    None => bindings.push(DescriptorBinding {
+                        binding: resource.binding,
+                        descriptor_type: resource.descriptor_type,
+                        count,
+                        stages,
+                    }),
+                }
+            }
+
+            if let Some(push_constants) = &reflection.push_constants {
+                let range = desc.push_constants.get_or_insert(PushConstantRange {
+                    size: 0,
+                    stages: ShaderStages::empty(),
+                });
+
+                range.size = range.size.max(push_constants.size.next_multiple_of(4));
+                range.stages |= stages;
+            }
+        }
+
+        for table in &mut desc.tables {
+            table.bindings.sort_by_key(|binding| binding.binding);
+        }
+
+        Ok(desc)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DescriptorTableLayout {
+    pub bindings: Vec<DescriptorBinding>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DescriptorBinding {
+    pub binding: u32,
+    pub descriptor_type: DescriptorType,
+    pub count: u32,
+    pub stages: ShaderStages,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DescriptorType {
+    UniformBuffer,
+    StorageBuffer,
+    ReadOnlyStorageBuffer,
+    SampledTexture,
+    StorageTexture,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    Sampler,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PushConstantRange {
+    pub size: u32,
+    pub stages: ShaderStages,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StaticSampler {
+    pub binding: u32,
+    pub space: u32,
+    pub stages: ShaderStages,
+    pub sampler: SamplerDesc,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ShaderKind, ShaderResourceBinding, ShaderStruct};
+
+    fn reflection(
+        kind: ShaderKind,
+        bindings: &[(u32, u32, DescriptorType, Option<u32>)],
+    ) -> ShaderReflection {
+        ShaderReflection {
+            kind,
+            entry_point: "main".to_owned(),
+            bindings: bindings
+                .iter()
+                .map(
+                    |(space, binding, descriptor_type, count)| ShaderResourceBinding {
+                        name: format!("resource{space}_{binding}"),
+                        descriptor_type: *descriptor_type,
+                        space: *space,
+                        binding: *binding,
+                        count: *count,
+                        block: None,
+                    },
+                )
+                .collect(),
+            unsupported_bindings: Vec::new(),
+            push_constants: None,
+            thread_group_size: None,
+            mesh_output: None,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    fn push_constants(size: u32) -> Option<ShaderStruct> {
+        Some(ShaderStruct {
+            name: "PushConstants".to_owned(),
+            size,
+            members: Vec::new(),
+        })
+    }
+
+    fn conflict_reason(reflections: &[&ShaderReflection]) -> &'static str {
+        match BindingLayoutDesc::from_reflection("Layout".into(), reflections) {
+            Err(Error::BindingLayoutConflict { reason, .. }) => reason,
+            _ => panic!("expected a binding layout conflict"),
+        }
+    }
+
+    #[test]
+    fn merges_stages_of_shared_bindings() {
+        let vertex = reflection(
+            ShaderKind::Vertex,
+            &[
+                (0, 1, DescriptorType::StorageBuffer, Some(1)),
+                (0, 0, DescriptorType::UniformBuffer, Some(1)),
+            ],
+        );
+        let fragment = reflection(
+            ShaderKind::Fragment,
+            &[
+                (0, 0, DescriptorType::UniformBuffer, Some(1)),
+                (1, 0, DescriptorType::SampledTexture, Some(4)),
+                (BINDLESS_SPACE, 0, DescriptorType::SampledTexture, None),
+            ],
+        );
+
+        let desc =
+            BindingLayoutDesc::from_reflection("Layout".into(), &[&vertex, &fragment]).unwrap();
+
+        assert!(desc.bindless);
+        assert_eq!(
+            desc.tables,
+            [
+                DescriptorTableLayout {
+                    bindings: vec![
+                        DescriptorBinding {
+                            binding: 0,
+                            descriptor_type: DescriptorType::UniformBuffer,
+                            count: 1,
+                            stages: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                        },
+                        DescriptorBinding {
+                            binding: 1,
+                            descriptor_type: DescriptorType::StorageBuffer,
+                            count: 1,
+                            stages: ShaderStages::VERTEX,
+                        },
+                    ],
+                },
+                DescriptorTableLayout {
+                    bindings: vec![DescriptorBinding {
+                        binding: 0,
+                        descriptor_type: DescriptorType::SampledTexture,
+                        count: 4,
+                        stages: ShaderStages::FRAGMENT,
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_descriptor_types() {
+        let vertex = reflection(
+            ShaderKind::Vertex,
+            &[(0, 0, DescriptorType::UniformBuffer, Some(1))],
+        );
+        let fragment = reflection(
+            ShaderKind::Fragment,
+            &[(0, 0, DescriptorType::StorageBuffer, Some(1))],
+        );
+
+        assert_eq!(
+            conflict_reason(&[&vertex, &fragment]),
+            "stages use different descriptor types"
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_array_sizes() {
+        let vertex = reflection(
+            ShaderKind::Vertex,
+            &[(0, 0, DescriptorType::SampledTexture, Some(2))],
+        );
+        let fragment = reflection(
+            ShaderKind::Fragment,
+            &[(0, 0, DescriptorType::SampledTexture, Some(4))],
+        );
+
+        assert_eq!(
+            conflict_reason(&[&vertex, &fragment]),
+            "stages use different array sizes"
+        );
+    }
+
+    #[test]
+    fn rejects_unbounded_arrays_outside_the_bindless_space() {
+        let compute = reflection(
+            ShaderKind::Compute,
+            &[(1, 0, DescriptorType::StorageTexture, None)],
+        );
+
+        assert_eq!(
+            conflict_reason(&[&compute]),
+            "unbounded descriptor arrays are only supported in the bindless heap"
+        );
+    }
+
+    #[test]
+    fn merges_push_constant_ranges() {
+        let mut mesh = reflection(ShaderKind::Mesh, &[]);
+        mesh.push_constants = push_constants(8);
+        let mut fragment = reflection(ShaderKind::Fragment, &[]);
+        fragment.push_constants = push_constants(18);
+
+        let desc =
+            BindingLayoutDesc::from_reflection("Layout".into(), &[&mesh, &fragment]).unwrap();
+
+        assert_eq!(
+            desc.push_constants,
+            Some(PushConstantRange {
+                size: 20,
+                stages: ShaderStages::MESH | ShaderStages::FRAGMENT,
+            })
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{Buffer, Texture};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DescriptorIndex(pub(crate) u32);
+
+impl DescriptorIndex {
+    pub fn index(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum BindlessResource<'a> {
+    UniformBuffer {
+        buffer: &'a Buffer,
+        offset: u64,
+        size: u64,
+    },
+    StorageBuffer {
+        buffer: &'a Buffer,
+        offset: u64,
+        size: u64,
+    },
+    SampledTexture(&'a Texture),
+    StorageTexture(&'a Texture),
+}
+
+pub(crate) struct DescriptorIndexAllocator {
+    capacity: u32,
+    next_index: u32,
+    free_indices: Vec<u32>,
+    pending_frees: Vec<(u32, [u64; 3])>,
+}
+
+impl DescriptorIndexAllocator {
+    pub(crate) fn new(capacity: u32) -> Self {
+        Self {
+            capacity,
+            next_index: 0,
+            free_indices: Vec::new(),
+            pending_frees: Vec::new(),
+        }
+    }
+
+    pub(crate) fn allocate(&mut self, completed_values: [u64; 3]) -> Option<DescriptorIndex> {
+        for (index, _) in self.pending_frees.extract_if(.., |(_, values)| {
+            values
+                .iter()
+                .zip(&completed_values)
+                .all(|(value, completed_value)| value <= completed_value)
+        }) {
+            self.free_indices.push(index);
+        }
+
+        if let Some(index) = self.free_indices.pop() {
+            return Some(DescriptorIndex(index));
+        }
+
+        if self.next_index < self.capacity {
+            self.next_index += 1;
+            return Some(DescriptorIndex(self.next_index - 1));
+        }
+
+        None
+    }
+
+    pub(crate) fn free(&mut self, index: DescriptorIndex, submitted_values: [u64; 3]) {
+        self.pending_frees.push((index.0, submitted_values));
+    }
+}
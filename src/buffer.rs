@@ -0,0 +1,125 @@
+use crate::Error;
+use std::borrow::Cow;
+use std::ops::{BitOr, BitOrAssign};
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+pub enum Buffer {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanBuffer),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalBuffer),
+}
+
+impl Buffer {
+    pub fn size(&self) -> u64 {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(vulkan_buffer) => vulkan_buffer.size(),
+
+            #[cfg(feature = "metal")]
+            Buffer::Metal(metal_buffer) => metal_buffer.size(),
+        }
+    }
+
+    pub fn mapped_slice(&self) -> Option<&[u8]> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(vulkan_buffer) => vulkan_buffer.mapped_slice(),
+
+            #[cfg(feature = "metal")]
+            Buffer::Metal(metal_buffer) => metal_buffer.mapped_slice(),
+        }
+    }
+
+    pub fn mapped_slice_mut(&mut self) -> Option<&mut [u8]> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(vulkan_buffer) => vulkan_buffer.mapped_slice_mut(),
+
+            #[cfg(feature = "metal")]
+            Buffer::Metal(metal_buffer) => metal_buffer.mapped_slice_mut(),
+        }
+    }
+
+    pub fn device_address(&self) -> Result<u64, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(vulkan_buffer) => vulkan_buffer.device_address(),
+
+            #[cfg(feature = "metal")]
+            Buffer::Metal(metal_buffer) => metal_buffer.device_address(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_buffer(&self) -> &VulkanBuffer {
+        match self {
+            Buffer::Vulkan(vulkan_buffer) => vulkan_buffer,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_buffer(&self) -> &MetalBuffer {
+        match self {
+            Buffer::Metal(metal_buffer) => metal_buffer,
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub struct BufferDesc {
+    pub name: Cow<'static, str>,
+    pub size: u64,
+    pub usage: BufferUsage,
+    pub memory_location: MemoryLocation,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BufferUsage(u32);
+
+impl BufferUsage {
+    pub const TRANSFER_SRC: Self = Self(1 << 0);
+    pub const TRANSFER_DST: Self = Self(1 << 1);
+    pub const UNIFORM: Self = Self(1 << 2);
+    pub const STORAGE: Self = Self(1 << 3);
+    pub const INDEX: Self = Self(1 << 4);
+    pub const VERTEX: Self = Self(1 << 5);
+    pub const INDIRECT: Self = Self(1 << 6);
+    pub const DEVICE_ADDRESS: Self = Self(1 << 7);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl BitOr for BufferUsage {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for BufferUsage {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MemoryLocation {
+    GpuOnly,
+    CpuToGpu,
+    GpuToCpu,
+}
@@ -0,0 +1,202 @@
+use crate::{
+    BufferDesc, BufferUsage, Device, Error, Format, MemoryLocation, Queue, ResourceState,
+    SwapchainImage, Texture, TextureUsage,
+};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Seek, Write};
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturedImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl CapturedImage {
+    pub fn write_png(&self, writer: impl Write) -> Result<(), Error> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn read_png(reader: impl BufRead + Seek) -> Result<Self, Error> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+
+        let mut data = vec![
+            0;
+            reader
+                .output_buffer_size()
+                .ok_or(Error::Unsupported("Png image is too large"))?
+        ];
+        let info = reader.next_frame(&mut data)?;
+        data.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => data,
+            png::ColorType::Rgb => data
+                .chunks_exact(3)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => data
+                .chunks_exact(2)
+                .flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]])
+                .collect(),
+            png::ColorType::Grayscale => data
+                .iter()
+                .flat_map(|value| [*value, *value, *value, 255])
+                .collect(),
+            png::ColorType::Indexed => {
+                return Err(Error::Unsupported("Indexed png images are not supported"));
+            }
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::read_png(BufReader::new(File::open(path)?))
+    }
+}
+
+pub fn capture_texture(
+    device: &Device,
+    queue: &Queue,
+    texture: &Texture,
+    final_state: ResourceState,
+) -> Result<CapturedImage, Error> {
+    let desc = texture.desc();
+
+    if !desc.usage.contains(TextureUsage::TRANSFER_SRC) {
+        return Err(Error::Unsupported(
+            "Captured textures must be created with TextureUsage::TRANSFER_SRC",
+        ));
+    }
+
+    if !capture_supported(desc.format) {
+        return Err(Error::Unsupported(
+            "Only RGBA8, BGRA8 and RGBA16F textures can be captured",
+        ));
+    }
+
+    let bytes_per_row = desc.width * desc.format.bytes_per_pixel();
+
+    let readback_buffer = device.create_buffer(&BufferDesc {
+        name: Cow::Owned(format!("{} readback buffer", desc.name)),
+        size: bytes_per_row as u64 * desc.height as u64,
+        usage: BufferUsage::TRANSFER_DST,
+        memory_location: MemoryLocation::GpuToCpu,
+    })?;
+
+    let mut command_list = device.create_command_list(queue.queue_type())?;
+
+    command_list.begin()?;
+    command_list.copy_texture_to_buffer(texture, 0, 0, &readback_buffer, 0, bytes_per_row);
+    command_list.transition_texture(texture, None, final_state);
+    command_list.end()?;
+
+    let value = queue.submit(&[&command_list])?;
+    queue.wait_for_value(value)?;
+
+    let data = readback_buffer
+        .mapped_slice()
+        .ok_or(Error::Unsupported("Readback buffer is not mapped"))?;
+
+    Ok(CapturedImage {
+        width: desc.width,
+        height: desc.height,
+        pixels: convert_to_rgba8(desc.format, data),
+    })
+}
+
+pub fn capture_swapchain_image(
+    device: &Device,
+    queue: &Queue,
+    image: &SwapchainImage,
+) -> Result<CapturedImage, Error> {
+    capture_texture(device, queue, &image.texture, ResourceState::Present)
+}
+
+fn capture_supported(format: Format) -> bool {
+    matches!(
+        format,
+        Format::R8G8B8A8Unorm
+            | Format::R8G8B8A8Srgb
+            | Format::B8G8R8A8Unorm
+            | Format::B8G8R8A8Srgb
+            | Format::R16G16B16A16Float
+    )
+}
+
+fn convert_to_rgba8(format: Format, data: &[u8]) -> Vec<u8> {
+    match format {
+        Format::B8G8R8A8Unorm | Format::B8G8R8A8Srgb => data
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+            .collect(),
+        Format::R16G16B16A16Float => data
+            .chunks_exact(8)
+            .flat_map(|pixel| {
+                let channel = |index: usize| {
+                    f16_to_f32(u16::from_le_bytes([pixel[index * 2], pixel[index * 2 + 1]]))
+                };
+
+                [
+                    unorm8(linear_to_srgb(channel(0))),
+                    unorm8(linear_to_srgb(channel(1))),
+                    unorm8(linear_to_srgb(channel(2))),
+                    unorm8(channel(3)),
+                ]
+            })
+            .collect(),
+        _ => data.to_vec(),
+    }
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits >> 15) as u32) << 31;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+
+    let magnitude = match exponent {
+        0 => (mantissa as f32 * 2.0f32.powi(-24)).to_bits(),
+        0x1f => 0x7f80_0000 | (mantissa << 13),
+        _ => ((exponent + 112) << 23) | (mantissa << 13),
+    };
+
+    f32::from_bits(sign | magnitude)
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn unorm8(value: f32) -> u8 {
+    if value.is_nan() {
+        return 0;
+    }
+
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
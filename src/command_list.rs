@@ -0,0 +1,528 @@
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+use crate::{
+    Buffer, BufferBarrier, DescriptorSet, Error, IndexType, Pipeline, QueueType, RenderPassDesc,
+    ResourceState, Texture, TextureBarrier, TextureSubresourceRange,
+};
+
+pub enum CommandList {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanCommandList),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalCommandList),
+}
+
+impl CommandList {
+    pub fn queue_type(&self) -> QueueType {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.queue_type(),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.queue_type(),
+        }
+    }
+
+    pub fn begin(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.begin(),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.begin(),
+        }
+    }
+
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.end(),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.end(),
+        }
+    }
+
+    /// Automatic barriers only cover resources passed directly to command list calls, using
+    /// the state each resource has when the command is recorded. Resources accessed through
+    /// descriptor sets or bindless indices must be transitioned explicitly, and buffers used
+    /// inside a render pass must already be in a compatible state when the pass begins.
+    /// Tracked states are shared by all command lists, so command lists that use the same
+    /// resources must be submitted in the order they were recorded.
+    pub fn set_automatic_barriers(&mut self, enabled: bool) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.set_automatic_barriers(enabled)
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.set_automatic_barriers(enabled)
+            }
+        }
+    }
+
+    pub fn transition_buffer(&mut self, buffer: &Buffer, new_state: ResourceState) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.transition_buffer(buffer.as_vulkan_buffer(), new_state)
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.transition_buffer(buffer.as_metal_buffer(), new_state)
+            }
+        }
+    }
+
+    pub fn transition_texture(
+        &mut self,
+        texture: &Texture,
+        range: Option<TextureSubresourceRange>,
+        new_state: ResourceState,
+    ) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.transition_texture(
+                texture.as_vulkan_texture(),
+                range,
+                new_state,
+            ),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.transition_texture(texture.as_metal_texture(), range, new_state)
+            }
+        }
+    }
+
+    pub fn barrier(
+        &mut self,
+        buffer_barriers: &[BufferBarrier],
+        texture_barriers: &[TextureBarrier],
+    ) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.barrier(buffer_barriers, texture_barriers)
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.barrier(buffer_barriers, texture_barriers)
+            }
+        }
+    }
+
+    pub fn begin_render_pass(&mut self, desc: &RenderPassDesc) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.begin_render_pass(desc),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.begin_render_pass(desc),
+        }
+    }
+
+    pub fn end_render_pass(&mut self) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.end_render_pass(),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.end_render_pass(),
+        }
+    }
+
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.set_viewport(viewport),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.set_viewport(viewport),
+        }
+    }
+
+    pub fn set_scissor(&mut self, scissor: &Rect) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.set_scissor(scissor),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.set_scissor(scissor),
+        }
+    }
+
+    pub fn bind_pipeline(&mut self, pipeline: &Pipeline) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.bind_pipeline(pipeline.as_vulkan_pipeline())
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.bind_pipeline(pipeline.as_metal_pipeline());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn bind_descriptor_set(&mut self, descriptor_set: &DescriptorSet) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.bind_descriptor_set(descriptor_set.as_vulkan_descriptor_set())
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(_) => Err(Error::Unsupported(
+                "bind_descriptor_set is not supported on Metal",
+            )),
+        }
+    }
+
+    pub fn set_push_constants<T: bytemuck::Pod>(&mut self, data: &T) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.set_push_constants(bytemuck::bytes_of(data))
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.set_push_constants(bytemuck::bytes_of(data))
+            }
+        }
+    }
+
+    pub fn bind_vertex_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: &[(&Buffer, u64)],
+    ) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                let buffers = buffers
+                    .iter()
+                    .map(|(buffer, offset)| (buffer.as_vulkan_buffer(), *offset))
+                    .collect::<Vec<_>>();
+
+                vulkan_command_list.bind_vertex_buffers(first_binding, &buffers)
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                let buffers = buffers
+                    .iter()
+                    .map(|(buffer, offset)| (buffer.as_metal_buffer(), *offset))
+                    .collect::<Vec<_>>();
+
+                metal_command_list.bind_vertex_buffers(first_binding, &buffers);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn bind_index_buffer(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        index_type: IndexType,
+    ) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.bind_index_buffer(buffer.as_vulkan_buffer(), offset, index_type)
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.bind_index_buffer(buffer.as_metal_buffer(), offset, index_type);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.draw(vertex_count, instance_count, first_vertex, first_instance)
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.draw(vertex_count, instance_count, first_vertex, first_instance)
+            }
+        }
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.draw_indexed(
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset,
+                first_instance,
+            ),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.draw_indexed(
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset,
+                first_instance,
+            ),
+        }
+    }
+
+    pub fn draw_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.draw_indirect(
+                buffer.as_vulkan_buffer(),
+                offset,
+                draw_count,
+                stride,
+            ),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.draw_indirect(
+                    buffer.as_metal_buffer(),
+                    offset,
+                    draw_count,
+                    stride,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    pub fn draw_indexed_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.draw_indexed_indirect(
+                buffer.as_vulkan_buffer(),
+                offset,
+                draw_count,
+                stride,
+            ),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.draw_indexed_indirect(
+                    buffer.as_metal_buffer(),
+                    offset,
+                    draw_count,
+                    stride,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    pub fn draw_mesh_tasks(
+        &mut self,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    ) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => {
+                vulkan_command_list.draw_mesh_tasks(group_count_x, group_count_y, group_count_z)
+            }
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.draw_mesh_tasks(group_count_x, group_count_y, group_count_z);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list
+                .draw_mesh_tasks_indirect(buffer.as_vulkan_buffer(), offset, draw_count, stride),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => {
+                metal_command_list.draw_mesh_tasks_indirect(
+                    buffer.as_metal_buffer(),
+                    offset,
+                    draw_count,
+                    stride,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    pub fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        count_buffer: &Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list
+                .draw_mesh_tasks_indirect_count(
+                    buffer.as_vulkan_buffer(),
+                    offset,
+                    count_buffer.as_vulkan_buffer(),
+                    count_buffer_offset,
+                    max_draw_count,
+                    stride,
+                ),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(_) => Err(Error::Unsupported(
+                "draw_mesh_tasks_indirect_count is not supported on Metal",
+            )),
+        }
+    }
+
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        texture: &Texture,
+        mip_level: u32,
+        array_layer: u32,
+        buffer: &Buffer,
+        buffer_offset: u64,
+        bytes_per_row: u32,
+    ) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list.copy_texture_to_buffer(
+                texture.as_vulkan_texture(),
+                mip_level,
+                array_layer,
+                buffer.as_vulkan_buffer(),
+                buffer_offset,
+                bytes_per_row,
+            ),
+
+            #[cfg(feature = "metal")]
+            CommandList::Metal(metal_command_list) => metal_command_list.copy_texture_to_buffer(
+                texture.as_metal_texture(),
+                mip_level,
+                array_layer,
+                buffer.as_metal_buffer(),
+                buffer_offset,
+                bytes_per_row,
+            ),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_command_list(&self) -> &VulkanCommandList {
+        match self {
+            CommandList::Vulkan(vulkan_command_list) => vulkan_command_list,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_command_list(&self) -> &MetalCommandList {
+        match self {
+            CommandList::Metal(metal_command_list) => metal_command_list,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub min_depth: f32,
+    pub max_depth: f32,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawMeshTasksIndirectCommand {
+    pub group_count_x: u32,
+    pub group_count_y: u32,
+    pub group_count_z: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndirectCommand {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndexedIndirectCommand {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub first_instance: u32,
+}
@@ -0,0 +1,150 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+use crate::{BindingLayout, Buffer, Error, Sampler, Texture};
+
+pub enum DescriptorPool {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanDescriptorPool),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalDescriptorPool),
+}
+
+impl DescriptorPool {
+    pub fn allocate(
+        &mut self,
+        binding_layout: &BindingLayout,
+        table: u32,
+    ) -> Result<DescriptorSet, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            DescriptorPool::Vulkan(vulkan_descriptor_pool) => {
+                vulkan_descriptor_pool.allocate(binding_layout.as_vulkan_binding_layout(), table)
+            }
+
+            #[cfg(feature = "metal")]
+            DescriptorPool::Metal(metal_descriptor_pool) => {
+                metal_descriptor_pool.allocate(binding_layout.as_metal_binding_layout(), table)
+            }
+        }
+    }
+
+    pub fn reset(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            DescriptorPool::Vulkan(vulkan_descriptor_pool) => vulkan_descriptor_pool.reset(),
+
+            #[cfg(feature = "metal")]
+            DescriptorPool::Metal(metal_descriptor_pool) => metal_descriptor_pool.reset(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_descriptor_pool(&self) -> &VulkanDescriptorPool {
+        match self {
+            DescriptorPool::Vulkan(vulkan_descriptor_pool) => vulkan_descriptor_pool,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_descriptor_pool(&self) -> &MetalDescriptorPool {
+        match self {
+            DescriptorPool::Metal(metal_descriptor_pool) => metal_descriptor_pool,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DescriptorPoolDesc {
+    pub name: Cow<'static, str>,
+    pub sets_per_block: u32,
+    pub descriptors_per_block: u32,
+}
+
+impl Default for DescriptorPoolDesc {
+    fn default() -> Self {
+        Self {
+            name: Cow::Borrowed("Descriptor pool"),
+            sets_per_block: 256,
+            descriptors_per_block: 1024,
+        }
+    }
+}
+
+pub enum DescriptorSet {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanDescriptorSet),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalDescriptorSet),
+}
+
+impl DescriptorSet {
+    pub fn table(&self) -> u32 {
+        match self {
+            #[cfg(feature = "vulkan")]
+            DescriptorSet::Vulkan(vulkan_descriptor_set) => vulkan_descriptor_set.table(),
+
+            #[cfg(feature = "metal")]
+            DescriptorSet::Metal(metal_descriptor_set) => metal_descriptor_set.table(),
+        }
+    }
+
+    pub fn write(&self, writes: &[DescriptorWrite]) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            DescriptorSet::Vulkan(vulkan_descriptor_set) => vulkan_descriptor_set.write(writes),
+
+            #[cfg(feature = "metal")]
+            DescriptorSet::Metal(metal_descriptor_set) => metal_descriptor_set.write(writes),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_descriptor_set(&self) -> &VulkanDescriptorSet {
+        match self {
+            DescriptorSet::Vulkan(vulkan_descriptor_set) => vulkan_descriptor_set,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_descriptor_set(&self) -> &MetalDescriptorSet {
+        match self {
+            DescriptorSet::Metal(metal_descriptor_set) => metal_descriptor_set,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct DescriptorWrite<'a> {
+    pub binding: u32,
+    pub array_element: u32,
+    pub resource: DescriptorResource<'a>,
+}
+
+#[derive(Copy, Clone)]
+pub enum DescriptorResource<'a> {
+    UniformBuffer {
+        buffer: &'a Buffer,
+        offset: u64,
+        size: u64,
+    },
+    StorageBuffer {
+        buffer: &'a Buffer,
+        offset: u64,
+        size: u64,
+    },
+    SampledTexture(&'a Texture),
+    StorageTexture(&'a Texture),
+    Sampler(&'a Sampler),
+}
@@ -7,7 +7,12 @@ use crate::backend::vulkan::*;
 use crate::backend::metal::*;
 
 use crate::shader_module::ShaderDesc;
-use crate::{Error, Queue, ShaderModule};
+use crate::{
+    BindingLayout, BindingLayoutDesc, BindlessResource, Buffer, BufferDesc, CommandList,
+    DescriptorIndex, DescriptorPool, DescriptorPoolDesc, Error, GraphicsPipelineDesc, Heap,
+    HeapDesc, MemoryRequirements, MeshPipelineDesc, Pipeline, Queue, QueueType, Sampler,
+    SamplerDesc, ShaderModule, Swapchain, SwapchainDesc, Texture, TextureDesc,
+};
 
 pub enum Device {
     #[cfg(feature = "vulkan")]
@@ -28,6 +33,226 @@ impl Device {
         }
     }
 
+    pub fn create_buffer(&self, desc: &BufferDesc) -> Result<Buffer, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_buffer(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_buffer(desc),
+        }
+    }
+
+    pub fn create_texture(&self, desc: &TextureDesc) -> Result<Texture, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_texture(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_texture(desc),
+        }
+    }
+
+    pub fn create_heap(&self, desc: &HeapDesc) -> Result<Heap, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_heap(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_heap(desc),
+        }
+    }
+
+    pub fn buffer_memory_requirements(&self, desc: &BufferDesc) -> MemoryRequirements {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.buffer_memory_requirements(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.buffer_memory_requirements(desc),
+        }
+    }
+
+    pub fn texture_memory_requirements(&self, desc: &TextureDesc) -> MemoryRequirements {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.texture_memory_requirements(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.texture_memory_requirements(desc),
+        }
+    }
+
+    pub fn create_placed_buffer(
+        &self,
+        heap: &Heap,
+        offset: u64,
+        desc: &BufferDesc,
+    ) -> Result<Buffer, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => {
+                vulkan_device.create_placed_buffer(heap.as_vulkan_heap(), offset, desc)
+            }
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => {
+                metal_device.create_placed_buffer(heap.as_metal_heap(), offset, desc)
+            }
+        }
+    }
+
+    pub fn create_placed_texture(
+        &self,
+        heap: &Heap,
+        offset: u64,
+        desc: &TextureDesc,
+    ) -> Result<Texture, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => {
+                vulkan_device.create_placed_texture(heap.as_vulkan_heap(), offset, desc)
+            }
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => {
+                metal_device.create_placed_texture(heap.as_metal_heap(), offset, desc)
+            }
+        }
+    }
+
+    pub fn create_mesh_pipeline(&self, desc: &MeshPipelineDesc) -> Result<Pipeline, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_mesh_pipeline(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_mesh_pipeline(desc),
+        }
+    }
+
+    pub fn create_graphics_pipeline(&self, desc: &GraphicsPipelineDesc) -> Result<Pipeline, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_graphics_pipeline(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_graphics_pipeline(desc),
+        }
+    }
+
+    pub fn create_binding_layout(&self, desc: &BindingLayoutDesc) -> Result<BindingLayout, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_binding_layout(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_binding_layout(desc),
+        }
+    }
+
+    pub fn create_sampler(&self, desc: &SamplerDesc) -> Result<Sampler, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_sampler(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_sampler(desc),
+        }
+    }
+
+    pub fn allocate_descriptor(
+        &self,
+        resource: BindlessResource,
+    ) -> Result<DescriptorIndex, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.allocate_descriptor(resource),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.allocate_descriptor(resource),
+        }
+    }
+
+    pub fn allocate_sampler_descriptor(&self, sampler: &Sampler) -> Result<DescriptorIndex, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => {
+                vulkan_device.allocate_sampler_descriptor(sampler.as_vulkan_sampler())
+            }
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => {
+                metal_device.allocate_sampler_descriptor(sampler.as_metal_sampler())
+            }
+        }
+    }
+
+    pub fn free_descriptor(&self, index: DescriptorIndex) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.free_descriptor(index),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.free_descriptor(index),
+        }
+    }
+
+    pub fn free_sampler_descriptor(&self, index: DescriptorIndex) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.free_sampler_descriptor(index),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.free_sampler_descriptor(index),
+        }
+    }
+
+    pub fn create_descriptor_pool(
+        &self,
+        desc: &DescriptorPoolDesc,
+    ) -> Result<DescriptorPool, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_descriptor_pool(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(_) => Err(Error::Unsupported(
+                "Descriptor pools are not supported by the Metal backend",
+            )),
+        }
+    }
+
+    pub fn create_swapchain(&self, desc: &SwapchainDesc) -> Result<Swapchain, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_swapchain(desc),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_swapchain(desc),
+        }
+    }
+
+    pub fn create_command_list(&self, queue_type: QueueType) -> Result<CommandList, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.create_command_list(queue_type),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.create_command_list(queue_type),
+        }
+    }
+
+    pub fn collect_garbage(&self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(vulkan_device) => vulkan_device.collect_garbage(),
+
+            #[cfg(feature = "metal")]
+            Device::Metal(metal_device) => metal_device.collect_garbage(),
+        }
+    }
+
     pub fn get_direct_queue(&self) -> Queue {
         match self {
             #[cfg(feature = "vulkan")]
@@ -0,0 +1,55 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    R8Unorm,
+    R8G8Unorm,
+    R8G8B8A8Unorm,
+    R8G8B8A8Srgb,
+    B8G8R8A8Unorm,
+    B8G8R8A8Srgb,
+    A2B10G10R10Unorm,
+    R16Float,
+    R16G16Float,
+    R16G16B16A16Float,
+    R32Uint,
+    R32Float,
+    R32G32Float,
+    R32G32B32Float,
+    R32G32B32A32Float,
+    D16Unorm,
+    D32Float,
+    D24UnormS8Uint,
+    D32FloatS8Uint,
+}
+
+impl Format {
+    pub fn is_depth(&self) -> bool {
+        matches!(
+            self,
+            Format::D16Unorm | Format::D32Float | Format::D24UnormS8Uint | Format::D32FloatS8Uint
+        )
+    }
+
+    pub fn is_stencil(&self) -> bool {
+        matches!(self, Format::D24UnormS8Uint | Format::D32FloatS8Uint)
+    }
+
+    pub fn bytes_per_pixel(&self) -> u32 {
+        match self {
+            Format::R8Unorm => 1,
+            Format::R8G8Unorm | Format::R16Float | Format::D16Unorm => 2,
+            Format::R8G8B8A8Unorm
+            | Format::R8G8B8A8Srgb
+            | Format::B8G8R8A8Unorm
+            | Format::B8G8R8A8Srgb
+            | Format::A2B10G10R10Unorm
+            | Format::R16G16Float
+            | Format::R32Uint
+            | Format::R32Float
+            | Format::D32Float
+            | Format::D24UnormS8Uint => 4,
+            Format::R16G16B16A16Float | Format::R32G32Float | Format::D32FloatS8Uint => 8,
+            Format::R32G32B32Float => 12,
+            Format::R32G32B32A32Float => 16,
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    Buffer, BufferDesc, BufferUsage, CommandList, DescriptorPool, DescriptorPoolDesc, Device,
+    Error, MemoryLocation, Queue, QueueType,
+};
+use std::any::Any;
+use std::borrow::Cow;
+
+pub struct FramesInFlightDesc {
+    pub frame_count: u32,
+    pub queue_type: QueueType,
+    pub upload_buffer_size: u64,
+    pub descriptor_pool: Option<DescriptorPoolDesc>,
+}
+
+impl Default for FramesInFlightDesc {
+    fn default() -> Self {
+        Self {
+            frame_count: 2,
+            queue_type: QueueType::Direct,
+            upload_buffer_size: 4 * 1024 * 1024,
+            descriptor_pool: None,
+        }
+    }
+}
+
+pub struct FrameContext {
+    pub command_list: CommandList,
+    pub upload_buffer: Buffer,
+    pub descriptor_pool: Option<DescriptorPool>,
+
+    upload_offset: u64,
+    submitted_value: u64,
+
+    retained: Vec<Box<dyn Any>>,
+}
+
+impl FrameContext {
+    pub fn upload(&mut self, data: &[u8], alignment: u64) -> Option<u64> {
+        let offset = self.allocate_upload(data.len() as u64, alignment)?;
+
+        let mapped_slice = self.upload_buffer.mapped_slice_mut()?;
+        mapped_slice[offset as usize..offset as usize + data.len()].copy_from_slice(data);
+
+        Some(offset)
+    }
+
+    pub fn upload_pod<T: bytemuck::Pod>(&mut self, value: &T) -> Option<u64> {
+        self.upload(
+            bytemuck::bytes_of(value),
+            std::mem::align_of::<T>().max(16) as u64,
+        )
+    }
+
+    pub fn allocate_upload(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        let offset = self.upload_offset.next_multiple_of(alignment.max(1));
+
+        if offset + size > self.upload_buffer.size() {
+            return None;
+        }
+
+        self.upload_offset = offset + size;
+
+        Some(offset)
+    }
+
+    pub fn upload_bytes_used(&self) -> u64 {
+        self.upload_offset
+    }
+
+    pub fn retain(&mut self, resource: impl Any) {
+        self.retained.push(Box::new(resource));
+    }
+
+    pub fn submitted_value(&self) -> u64 {
+        self.submitted_value
+    }
+}
+
+pub struct FramesInFlight {
+    queue: Queue,
+
+    frames: Vec<FrameContext>,
+    frame_index: usize,
+    frame_number: u64,
+
+    recording: bool,
+}
+
+impl FramesInFlight {
+    pub fn new(device: &Device, desc: &FramesInFlightDesc) -> Result<Self, Error> {
+        let queue = match desc.queue_type {
+            QueueType::Direct => device.get_direct_queue(),
+            QueueType::Compute => device.get_compute_queue(),
+            QueueType::Transfer => device.get_transfer_queue(),
+        };
+
+        let frames = (0..desc.frame_count.max(1))
+            .map(|frame_index| {
+                Ok(FrameContext {
+                    command_list: device.create_command_list(desc.queue_type)?,
+                    upload_buffer: device.create_buffer(&BufferDesc {
+                        name: Cow::Owned(format!("Frame {frame_index} upload buffer")),
+                        size: desc.upload_buffer_size.max(1),
+                        usage: BufferUsage::TRANSFER_SRC
+                            | BufferUsage::UNIFORM
+                            | BufferUsage::STORAGE
+                            | BufferUsage::INDEX
+                            | BufferUsage::VERTEX
+                            | BufferUsage::INDIRECT,
+                        memory_location: MemoryLocation::CpuToGpu,
+                    })?,
+                    descriptor_pool: desc
+                        .descriptor_pool
+                        .as_ref()
+                        .map(|descriptor_pool_desc| {
+                            device.create_descriptor_pool(&DescriptorPoolDesc {
+                                name: Cow::Owned(format!(
+                                    "Frame {frame_index} {}",
+                                    descriptor_pool_desc.name
+                                )),
+                                ..descriptor_pool_desc.clone()
+                            })
+                        })
+                        .transpose()?,
+                    upload_offset: 0,
+                    submitted_value: 0,
+                    retained: Vec::new(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            queue,
+            frames,
+            frame_index: 0,
+            frame_number: 0,
+            recording: false,
+        })
+    }
+
+    pub fn begin_frame(&mut self) -> Result<&mut FrameContext, Error> {
+        debug_assert!(
+            !self.recording,
+            "begin_frame called twice without end_frame"
+        );
+
+        let frame = &mut self.frames[self.frame_index];
+
+        self.queue.wait_for_value(frame.submitted_value)?;
+
+        frame.upload_offset = 0;
+        frame.retained.clear();
+
+        if let Some(descriptor_pool) = &mut frame.descriptor_pool {
+            descriptor_pool.reset()?;
+        }
+
+        frame.command_list.begin()?;
+
+        self.recording = true;
+
+        Ok(frame)
+    }
+
+    pub fn current_frame(&mut self) -> &mut FrameContext {
+        &mut self.frames[self.frame_index]
+    }
+
+    pub fn end_frame(&mut self) -> Result<u64, Error> {
+        self.end_frame_with_waits(&[])
+    }
+
+    pub fn end_frame_with_waits(&mut self, waits: &[(&Queue, u64)]) -> Result<u64, Error> {
+        debug_assert!(self.recording, "end_frame called without begin_frame");
+
+        let frame = &mut self.frames[self.frame_index];
+
+        frame.command_list.end()?;
+
+        let submitted_value = self
+            .queue
+            .submit_with_waits(&[&frame.command_list], waits)?;
+        frame.submitted_value = submitted_value;
+
+        self.recording = false;
+        self.frame_index = (self.frame_index + 1) % self.frames.len();
+        self.frame_number += 1;
+
+        Ok(submitted_value)
+    }
+
+    pub fn queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    pub fn frame_index(&self) -> usize {
+        self.frame_index
+    }
+
+    pub fn frame_number(&self) -> u64 {
+        self.frame_number
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn wait_idle(&mut self) -> Result<(), Error> {
+        for frame in &mut self.frames {
+            self.queue.wait_for_value(frame.submitted_value)?;
+            frame.retained.clear();
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for FramesInFlight {
+    fn drop(&mut self) {
+        let _ = self.wait_idle();
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{
+    BackendType, CapturedImage, ColorAttachment, CommandList, Device, DeviceDesc, Error, Format,
+    Instance, InstanceDesc, LoadOp, Queue, Rect, RenderPassDesc, ResourceState, StoreOp, Texture,
+    TextureDesc, TextureDimension, TextureUsage, WindowHandle, capture_texture,
+};
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+
+pub struct GoldenImageDesc<'a> {
+    pub name: Cow<'a, str>,
+    pub width: u32,
+    pub height: u32,
+    pub format: Format,
+    pub clear_value: [f32; 4],
+    pub reference_dir: &'a Path,
+    pub output_dir: &'a Path,
+    pub tolerance: u8,
+    pub max_differing_pixels: u64,
+    pub update_reference: bool,
+}
+
+pub struct ImageComparison {
+    pub differing_pixels: u64,
+    pub max_difference: u8,
+    pub diff_image: CapturedImage,
+}
+
+pub struct GoldenImageHarness {
+    device: Device,
+    queue: Queue,
+}
+
+impl GoldenImageHarness {
+    pub fn new(
+        backend_type: BackendType,
+        physical_device_name: Option<&str>,
+        debug: bool,
+    ) -> Result<Self, Error> {
+        let instance = Instance::new(&InstanceDesc {
+            backend_type,
+            debug,
+            engine_name: Cow::Borrowed("Golden image harness"),
+            application_name: Cow::Borrowed("Golden image harness"),
+            window_handle: WindowHandle::Headless,
+        })?;
+
+        let physical_devices = instance.get_physical_devices()?;
+
+        let physical_device = physical_devices
+            .iter()
+            .find(|physical_device| {
+                physical_device_name.is_none_or(|name| physical_device.name().contains(name))
+            })
+            .ok_or(Error::NoBackendFound)?;
+
+        let device = instance.create_device(&DeviceDesc { physical_device })?;
+        let queue = device.get_direct_queue();
+
+        Ok(Self { device, queue })
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    pub fn render(
+        &self,
+        desc: &GoldenImageDesc,
+        record: impl FnOnce(&mut CommandList, &Texture),
+    ) -> Result<CapturedImage, Error> {
+        let texture = self.device.create_texture(&TextureDesc {
+            name: Cow::Owned(format!("{} golden image", desc.name)),
+            dimension: TextureDimension::D2,
+            width: desc.width,
+            height: desc.height,
+            depth: 1,
+            array_layers: 1,
+            mip_levels: 1,
+            sample_count: 1,
+            format: desc.format,
+            usage: TextureUsage::RENDER_TARGET | TextureUsage::TRANSFER_SRC,
+        })?;
+
+        let mut command_list = self.device.create_command_list(self.queue.queue_type())?;
+
+        command_list.begin()?;
+
+        command_list.begin_render_pass(&RenderPassDesc {
+            color_attachments: &[ColorAttachment {
+                texture: &texture,
+                resolve_target: None,
+                load_op: LoadOp::Clear,
+                store_op: StoreOp::Store,
+                clear_value: desc.clear_value,
+            }],
+            depth_stencil_attachment: None,
+            render_area: Rect {
+                x: 0,
+                y: 0,
+                width: desc.width,
+                height: desc.height,
+            },
+        });
+
+        record(&mut command_list, &texture);
+
+        command_list.end_render_pass();
+        command_list.end()?;
+
+        self.queue.submit(&[&command_list])?;
+
+        capture_texture(
+            &self.device,
+            &self.queue,
+            &texture,
+            ResourceState::TransferSrc,
+        )
+    }
+
+    pub fn check(
+        &self,
+        desc: &GoldenImageDesc,
+        record: impl FnOnce(&mut CommandList, &Texture),
+    ) -> Result<ImageComparison, Error> {
+        let actual = self.render(desc, record)?;
+
+        check_golden_image(desc, &actual)
+    }
+}
+
+pub fn check_golden_image(
+    desc: &GoldenImageDesc,
+    actual: &CapturedImage,
+) -> Result<ImageComparison, Error> {
+    let reference_path = desc.reference_dir.join(format!("{}.png", desc.name));
+
+    if desc.update_reference {
+        fs::create_dir_all(desc.reference_dir)?;
+        actual.save_png(&reference_path)?;
+
+        return Ok(compare_images(actual, actual, desc.tolerance));
+    }
+
+    if !reference_path.exists() {
+        fs::create_dir_all(desc.output_dir)?;
+        actual.save_png(desc.output_dir.join(format!("{}.actual.png", desc.name)))?;
+
+        return Err(Error::GoldenImageMissing(desc.name.to_string()));
+    }
+
+    let expected = CapturedImage::load_png(&reference_path)?;
+    let comparison = compare_images(actual, &expected, desc.tolerance);
+
+    if comparison.differing_pixels > desc.max_differing_pixels {
+        fs::create_dir_all(desc.output_dir)?;
+
+        actual.save_png(desc.output_dir.join(format!("{}.actual.png", desc.name)))?;
+        comparison
+            .diff_image
+            .save_png(desc.output_dir.join(format!("{}.diff.png", desc.name)))?;
+
+        return Err(Error::GoldenImageMismatch {
+            name: desc.name.to_string(),
+            differing_pixels: comparison.differing_pixels,
+            max_difference: comparison.max_difference,
+        });
+    }
+
+    Ok(comparison)
+}
+
+pub fn compare_images(
+    actual: &CapturedImage,
+    expected: &CapturedImage,
+    tolerance: u8,
+) -> ImageComparison {
+    if actual.width != expected.width || actual.height != expected.height {
+        return ImageComparison {
+            differing_pixels: actual.width as u64 * actual.height as u64,
+            max_difference: u8::MAX,
+            diff_image: CapturedImage {
+                width: actual.width,
+                height: actual.height,
+                pixels: [255, 0, 0, 255].repeat((actual.width * actual.height) as usize),
+            },
+        };
+    }
+
+    let mut differing_pixels = 0;
+    let mut max_difference = 0;
+
+    let pixels = actual
+        .pixels
+        .chunks_exact(4)
+        .zip(expected.pixels.chunks_exact(4))
+        .flat_map(|(actual, expected)| {
+            let difference = actual
+                .iter()
+                .zip(expected)
+                .map(|(actual, expected)| actual.abs_diff(*expected))
+                .max()
+                .unwrap_or(0);
+
+            max_difference = max_difference.max(difference);
+
+            if difference > tolerance {
+                differing_pixels += 1;
+
+                [255, 0, 0, 255]
+            } else {
+                let luminance =
+                    ((expected[0] as u32 + expected[1] as u32 + expected[2] as u32) / 12) as u8;
+
+                [luminance, luminance, luminance, 255]
+            }
+        })
+        .collect();
+
+    ImageComparison {
+        differing_pixels,
+        max_difference,
+        diff_image: CapturedImage {
+            width: actual.width,
+            height: actual.height,
+            pixels,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32, pixels: &[[u8; 4]]) -> CapturedImage {
+        CapturedImage {
+            width,
+            height,
+            pixels: pixels.concat(),
+        }
+    }
+
+    #[test]
+    fn size_mismatch_marks_every_pixel() {
+        let actual = image(2, 1, &[[0, 0, 0, 255]; 2]);
+        let expected = image(1, 1, &[[0, 0, 0, 255]]);
+
+        let comparison = compare_images(&actual, &expected, 0);
+
+        assert_eq!(comparison.differing_pixels, 2);
+        assert_eq!(comparison.max_difference, u8::MAX);
+        assert_eq!(comparison.diff_image.width, 2);
+        assert_eq!(comparison.diff_image.height, 1);
+        assert_eq!(comparison.diff_image.pixels, [255, 0, 0, 255].repeat(2));
+    }
+
+    #[test]
+    fn differences_within_tolerance_pass() {
+        let actual = image(2, 1, &[[100, 100, 100, 255], [10, 20, 30, 255]]);
+        let expected = image(2, 1, &[[102, 99, 100, 255], [10, 20, 34, 255]]);
+
+        let comparison = compare_images(&actual, &expected, 2);
+
+        assert_eq!(comparison.differing_pixels, 1);
+        assert_eq!(comparison.max_difference, 4);
+
+        let comparison = compare_images(&actual, &expected, 4);
+
+        assert_eq!(comparison.differing_pixels, 0);
+        assert_eq!(comparison.max_difference, 4);
+    }
+
+    #[test]
+    fn diff_image_highlights_differing_pixels() {
+        let actual = image(2, 1, &[[120, 60, 0, 255], [0, 0, 0, 255]]);
+        let expected = image(2, 1, &[[120, 60, 0, 255], [255, 255, 255, 255]]);
+
+        let comparison = compare_images(&actual, &expected, 0);
+
+        assert_eq!(comparison.differing_pixels, 1);
+        assert_eq!(
+            comparison.diff_image.pixels,
+            [15, 15, 15, 255, 255, 0, 0, 255]
+        );
+    }
+}
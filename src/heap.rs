@@ -0,0 +1,57 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+pub enum Heap {
+    #[cfg(feature = "vulkan")]
+    Vulkan(Arc<VulkanHeap>),
+
+    #[cfg(feature = "metal")]
+    Metal(Arc<MetalHeap>),
+}
+
+impl Heap {
+    pub fn size(&self) -> u64 {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Heap::Vulkan(vulkan_heap) => vulkan_heap.size(),
+
+            #[cfg(feature = "metal")]
+            Heap::Metal(metal_heap) => metal_heap.size(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_heap(&self) -> &Arc<VulkanHeap> {
+        match self {
+            Heap::Vulkan(vulkan_heap) => vulkan_heap,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_heap(&self) -> &Arc<MetalHeap> {
+        match self {
+            Heap::Metal(metal_heap) => metal_heap,
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub struct HeapDesc {
+    pub name: Cow<'static, str>,
+    pub size: u64,
+    pub memory_type_bits: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MemoryRequirements {
+    pub size: u64,
+    pub alignment: u64,
+    pub memory_type_bits: u32,
+}
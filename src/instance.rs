@@ -67,9 +67,11 @@ pub struct InstanceDesc<'a> {
     pub window_handle: WindowHandle<'a>,
 }
 
+#[derive(Copy, Clone)]
 pub enum WindowHandle<'a> {
     #[cfg(feature = "sdl")]
     Sdl(&'a sdl3::video::Window),
+    Headless,
 }
 
 pub struct DeviceDesc<'a> {
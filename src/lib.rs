@@ -1,18 +1,60 @@
 #![feature(str_as_str)]
 
 mod backend;
+mod barrier;
+mod binding_layout;
+mod bindless;
+mod buffer;
+mod capture;
+mod command_list;
+mod descriptor_set;
 mod device;
+mod format;
+mod frames_in_flight;
+#[cfg(feature = "test-support")]
+mod golden_image;
+mod heap;
 mod instance;
 mod physical_device;
+mod pipeline;
 mod queue;
+mod render_graph;
+mod render_pass;
+mod sampler;
+mod shader_codegen;
+mod shader_embed;
 mod shader_module;
+mod shader_reflection;
+mod swapchain;
+mod texture;
 mod util;
 
+pub use barrier::*;
+pub use binding_layout::*;
+pub use bindless::*;
+pub use buffer::*;
+pub use capture::*;
+pub use command_list::*;
+pub use descriptor_set::*;
 pub use device::*;
+pub use format::*;
+pub use frames_in_flight::*;
+#[cfg(feature = "test-support")]
+pub use golden_image::*;
+pub use heap::*;
 pub use instance::*;
 pub use physical_device::*;
+pub use pipeline::*;
 pub use queue::*;
+pub use render_graph::*;
+pub use render_pass::*;
+pub use sampler::*;
+pub use shader_codegen::*;
+pub use shader_embed::*;
 pub use shader_module::*;
+pub use shader_reflection::*;
+pub use swapchain::*;
+pub use texture::*;
 
 use hassle_rs::HassleError;
 use std::ffi::NulError;
@@ -45,12 +87,59 @@ pub enum Error {
     #[error("Vulkan error: {0}")]
     VulkanError(#[from] ash::vk::Result),
 
+    #[cfg(feature = "vulkan")]
+    #[error("Vulkan memory type not found")]
+    VulkanMemoryTypeNotFound,
+
     #[error("Invalid shader source")]
     InvalidShaderSource,
 
+    #[error("Invalid SPIR-V: {0}")]
+    InvalidSpirV(&'static str),
+
+    #[error("Shader reflection error: {0}")]
+    ShaderReflection(&'static str),
+
+    #[error("Invalid shader kind {0:?}, expected {1:?}")]
+    InvalidShaderKind(ShaderKind, ShaderKind),
+
+    #[error("Invalid push constant size {0}, expected a multiple of 4 no larger than {1}")]
+    InvalidPushConstantSize(usize, u32),
+
+    #[error("Binding layout conflict at space {space}, binding {binding}: {reason}")]
+    BindingLayoutConflict {
+        space: u32,
+        binding: u32,
+        reason: &'static str,
+    },
+
+    #[error("Unsupported: {0}")]
+    Unsupported(&'static str),
+
     #[error("Hassle rs error: {0}")]
     HassleRs(#[from] HassleError),
 
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Png encoding error: {0}")]
+    PngEncoding(#[from] png::EncodingError),
+
+    #[error("Png decoding error: {0}")]
+    PngDecoding(#[from] png::DecodingError),
+
+    #[error("Golden image {0} has no reference image")]
+    GoldenImageMissing(String),
+
+    #[error(
+        "Golden image {name} does not match its reference: {differing_pixels} pixels differ, max difference {max_difference}"
+    )]
+    GoldenImageMismatch {
+        name: String,
+        differing_pixels: u64,
+        max_difference: u8,
+    },
+
     #[cfg(feature = "metal")]
     #[error("Metal shader converter error: {0}")]
     MetalShaderConverter(String),
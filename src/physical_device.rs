@@ -22,6 +22,42 @@ impl PhysicalDevice {
         }
     }
 
+    pub fn supports_mesh_shaders(&self) -> bool {
+        match self {
+            #[cfg(feature = "vulkan")]
+            PhysicalDevice::Vulkan(vulkan_physical_device) => {
+                vulkan_physical_device.mesh_shader_supported
+            }
+
+            #[cfg(feature = "metal")]
+            PhysicalDevice::Metal(_) => true,
+        }
+    }
+
+    pub fn supports_bindless(&self) -> bool {
+        match self {
+            #[cfg(feature = "vulkan")]
+            PhysicalDevice::Vulkan(vulkan_physical_device) => {
+                vulkan_physical_device.bindless_supported
+            }
+
+            #[cfg(feature = "metal")]
+            PhysicalDevice::Metal(_) => true,
+        }
+    }
+
+    pub fn supports_descriptor_buffers(&self) -> bool {
+        match self {
+            #[cfg(feature = "vulkan")]
+            PhysicalDevice::Vulkan(vulkan_physical_device) => {
+                vulkan_physical_device.descriptor_buffer_supported
+            }
+
+            #[cfg(feature = "metal")]
+            PhysicalDevice::Metal(_) => true,
+        }
+    }
+
     #[cfg(feature = "vulkan")]
     pub fn as_vulkan_physical_device(&self) -> &Arc<VulkanPhysicalDevice> {
         match self {
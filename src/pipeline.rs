@@ -0,0 +1,325 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+use crate::{BindingLayout, Format, ShaderModule};
+
+pub enum Pipeline {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanPipeline),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalPipeline),
+}
+
+impl Pipeline {
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_pipeline(&self) -> &VulkanPipeline {
+        match self {
+            Pipeline::Vulkan(vulkan_pipeline) => vulkan_pipeline,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_pipeline(&self) -> &MetalPipeline {
+        match self {
+            Pipeline::Metal(metal_pipeline) => metal_pipeline,
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub struct MeshPipelineDesc<'a> {
+    pub name: Cow<'static, str>,
+    pub binding_layout: Option<&'a BindingLayout>,
+    pub task_shader: Option<&'a ShaderModule>,
+    pub mesh_shader: &'a ShaderModule,
+    pub fragment_shader: Option<&'a ShaderModule>,
+    pub rasterizer: RasterizerState,
+    pub depth_stencil: DepthStencilState,
+    pub blend: BlendState,
+    pub render_target_formats: RenderTargetFormats,
+}
+
+pub struct GraphicsPipelineDesc<'a> {
+    pub name: Cow<'static, str>,
+    pub binding_layout: Option<&'a BindingLayout>,
+    pub vertex_shader: &'a ShaderModule,
+    pub fragment_shader: Option<&'a ShaderModule>,
+    pub vertex_input: VertexInputLayout,
+    pub primitive_topology: PrimitiveTopology,
+    pub primitive_restart: bool,
+    pub rasterizer: RasterizerState,
+    pub depth_stencil: DepthStencilState,
+    pub blend: BlendState,
+    pub render_target_formats: RenderTargetFormats,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VertexInputLayout {
+    pub buffers: Vec<VertexBufferLayout>,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexBufferLayout {
+    pub stride: u32,
+    pub step_mode: VertexStepMode,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VertexStepMode {
+    Vertex,
+    Instance,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexAttribute {
+    pub location: u32,
+    pub buffer_index: u32,
+    pub format: Format,
+    pub offset: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PrimitiveTopology {
+    PointList,
+    LineList,
+    LineStrip,
+    TriangleList,
+    TriangleStrip,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IndexType {
+    U16,
+    U32,
+}
+
+impl IndexType {
+    pub fn size(&self) -> u64 {
+        match self {
+            IndexType::U16 => 2,
+            IndexType::U32 => 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RenderTargetFormats {
+    pub color_formats: Vec<Format>,
+    pub depth_stencil_format: Option<Format>,
+    pub sample_count: u32,
+}
+
+impl Default for RenderTargetFormats {
+    fn default() -> Self {
+        Self {
+            color_formats: vec![],
+            depth_stencil_format: None,
+            sample_count: 1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RasterizerState {
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    pub depth_clamp: bool,
+    pub depth_bias: f32,
+    pub depth_bias_slope_scale: f32,
+    pub depth_bias_clamp: f32,
+}
+
+impl Default for RasterizerState {
+    fn default() -> Self {
+        Self {
+            polygon_mode: PolygonMode::Fill,
+            cull_mode: CullMode::None,
+            front_face: FrontFace::CounterClockwise,
+            depth_clamp: false,
+            depth_bias: 0.0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PolygonMode {
+    Fill,
+    Line,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CullMode {
+    None,
+    Front,
+    Back,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FrontFace {
+    Clockwise,
+    CounterClockwise,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DepthStencilState {
+    pub depth_test: bool,
+    pub depth_write: bool,
+    pub depth_compare_op: CompareOp,
+    pub stencil_test: bool,
+    pub stencil_read_mask: u8,
+    pub stencil_write_mask: u8,
+    pub stencil_front: StencilFaceState,
+    pub stencil_back: StencilFaceState,
+}
+
+impl Default for DepthStencilState {
+    fn default() -> Self {
+        Self {
+            depth_test: false,
+            depth_write: false,
+            depth_compare_op: CompareOp::Always,
+            stencil_test: false,
+            stencil_read_mask: 0xff,
+            stencil_write_mask: 0xff,
+            stencil_front: StencilFaceState::default(),
+            stencil_back: StencilFaceState::default(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StencilFaceState {
+    pub fail_op: StencilOp,
+    pub depth_fail_op: StencilOp,
+    pub pass_op: StencilOp,
+    pub compare_op: CompareOp,
+}
+
+impl Default for StencilFaceState {
+    fn default() -> Self {
+        Self {
+            fail_op: StencilOp::Keep,
+            depth_fail_op: StencilOp::Keep,
+            pass_op: StencilOp::Keep,
+            compare_op: CompareOp::Always,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StencilOp {
+    Keep,
+    Zero,
+    Replace,
+    IncrementAndClamp,
+    DecrementAndClamp,
+    Invert,
+    IncrementAndWrap,
+    DecrementAndWrap,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlendState {
+    pub alpha_to_coverage: bool,
+    pub attachments: Vec<ColorBlendAttachment>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ColorBlendAttachment {
+    pub blend_enable: bool,
+    pub src_color_blend_factor: BlendFactor,
+    pub dst_color_blend_factor: BlendFactor,
+    pub color_blend_op: BlendOp,
+    pub src_alpha_blend_factor: BlendFactor,
+    pub dst_alpha_blend_factor: BlendFactor,
+    pub alpha_blend_op: BlendOp,
+    pub write_mask: ColorWriteMask,
+}
+
+impl Default for ColorBlendAttachment {
+    fn default() -> Self {
+        Self {
+            blend_enable: false,
+            src_color_blend_factor: BlendFactor::One,
+            dst_color_blend_factor: BlendFactor::Zero,
+            color_blend_op: BlendOp::Add,
+            src_alpha_blend_factor: BlendFactor::One,
+            dst_alpha_blend_factor: BlendFactor::Zero,
+            alpha_blend_op: BlendOp::Add,
+            write_mask: ColorWriteMask::ALL,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlendOp {
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ColorWriteMask(u8);
+
+impl ColorWriteMask {
+    pub const R: Self = Self(1 << 0);
+    pub const G: Self = Self(1 << 1);
+    pub const B: Self = Self(1 << 2);
+    pub const A: Self = Self(1 << 3);
+    pub const ALL: Self = Self(0b1111);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl std::ops::BitOr for ColorWriteMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
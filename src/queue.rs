@@ -3,6 +3,7 @@ use crate::backend::vulkan::*;
 
 #[cfg(feature = "metal")]
 use crate::backend::metal::*;
+use crate::{CommandList, Error, Swapchain};
 use std::sync::Arc;
 
 pub enum Queue {
@@ -13,6 +14,110 @@ pub enum Queue {
     Metal(Arc<MetalQueue>),
 }
 
+impl Queue {
+    pub fn queue_type(&self) -> QueueType {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(vulkan_queue) => vulkan_queue.queue_type(),
+
+            #[cfg(feature = "metal")]
+            Queue::Metal(metal_queue) => metal_queue.queue_type(),
+        }
+    }
+
+    pub fn submit(&self, command_lists: &[&CommandList]) -> Result<u64, Error> {
+        self.submit_with_waits(command_lists, &[])
+    }
+
+    pub fn submit_with_waits(
+        &self,
+        command_lists: &[&CommandList],
+        waits: &[(&Queue, u64)],
+    ) -> Result<u64, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(vulkan_queue) => vulkan_queue.submit(
+                &command_lists
+                    .iter()
+                    .map(|command_list| command_list.as_vulkan_command_list())
+                    .collect::<Vec<_>>(),
+                &waits
+                    .iter()
+                    .map(|(queue, value)| (queue.as_vulkan_queue(), *value))
+                    .collect::<Vec<_>>(),
+            ),
+
+            #[cfg(feature = "metal")]
+            Queue::Metal(metal_queue) => metal_queue.submit(
+                &command_lists
+                    .iter()
+                    .map(|command_list| command_list.as_metal_command_list())
+                    .collect::<Vec<_>>(),
+                &waits
+                    .iter()
+                    .map(|(queue, value)| (queue.as_metal_queue(), *value))
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+
+    pub fn present(&self, swapchain: &Swapchain) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(vulkan_queue) => vulkan_queue.present(swapchain.as_vulkan_swapchain()),
+
+            #[cfg(feature = "metal")]
+            Queue::Metal(metal_queue) => metal_queue.present(swapchain.as_metal_swapchain()),
+        }
+    }
+
+    pub fn wait_for_value(&self, value: u64) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(vulkan_queue) => vulkan_queue.wait_for_value(value),
+
+            #[cfg(feature = "metal")]
+            Queue::Metal(metal_queue) => metal_queue.wait_for_value(value),
+        }
+    }
+
+    pub fn completed_value(&self) -> Result<u64, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(vulkan_queue) => vulkan_queue.completed_value(),
+
+            #[cfg(feature = "metal")]
+            Queue::Metal(metal_queue) => metal_queue.completed_value(),
+        }
+    }
+
+    pub fn wait_idle(&self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(vulkan_queue) => vulkan_queue.wait_idle(),
+
+            #[cfg(feature = "metal")]
+            Queue::Metal(metal_queue) => metal_queue.wait_idle(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_queue(&self) -> &VulkanQueue {
+        match self {
+            Queue::Vulkan(vulkan_queue) => vulkan_queue,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_queue(&self) -> &MetalQueue {
+        match self {
+            Queue::Metal(metal_queue) => metal_queue,
+            _ => unreachable!(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum QueueType {
     Direct,
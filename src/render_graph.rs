@@ -0,0 +1,1473 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::{
+    Buffer, BufferBarrier, BufferDesc, CommandList, Device, Error, Heap, HeapDesc,
+    MemoryRequirements, Queue, QueueTransfer, QueueType, ResourceState, Texture, TextureBarrier,
+    TextureDesc,
+};
+
+type PassExecuteFn<'a> = Box<dyn FnOnce(&mut CommandList, &RenderGraphResources<'a>) + 'a>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BufferHandle(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureHandle(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GraphResourceKind {
+    Buffer,
+    Texture,
+}
+
+enum GraphResourceSource<'a> {
+    ImportedBuffer(&'a Buffer),
+    ImportedTexture(&'a Texture),
+    TransientBuffer(BufferDesc),
+    TransientTexture(TextureDesc),
+}
+
+struct GraphResource<'a> {
+    name: Cow<'static, str>,
+    source: GraphResourceSource<'a>,
+    initial_state: ResourceState,
+}
+
+impl GraphResource<'_> {
+    fn kind(&self) -> GraphResourceKind {
+        match self.source {
+            GraphResourceSource::ImportedBuffer(_) | GraphResourceSource::TransientBuffer(_) => {
+                GraphResourceKind::Buffer
+            }
+            GraphResourceSource::ImportedTexture(_) | GraphResourceSource::TransientTexture(_) => {
+                GraphResourceKind::Texture
+            }
+        }
+    }
+
+    fn is_imported(&self) -> bool {
+        matches!(
+            self.source,
+            GraphResourceSource::ImportedBuffer(_) | GraphResourceSource::ImportedTexture(_)
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GraphResourceAccess {
+    pub resource: usize,
+    pub state: ResourceState,
+    pub write: bool,
+}
+
+struct GraphPass<'a> {
+    name: Cow<'static, str>,
+    queue_type: QueueType,
+    accesses: Vec<GraphResourceAccess>,
+    side_effects: bool,
+    execute: Option<PassExecuteFn<'a>>,
+}
+
+pub struct RenderGraph<'a> {
+    resources: Vec<GraphResource<'a>>,
+    passes: Vec<GraphPass<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+    pub fn new() -> Self {
+        Self {
+            resources: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    pub fn import_buffer(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        buffer: &'a Buffer,
+        current_state: ResourceState,
+    ) -> BufferHandle {
+        BufferHandle(self.add_resource(
+            name.into(),
+            GraphResourceSource::ImportedBuffer(buffer),
+            current_state,
+        ))
+    }
+
+    pub fn import_texture(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        texture: &'a Texture,
+        current_state: ResourceState,
+    ) -> TextureHandle {
+        TextureHandle(self.add_resource(
+            name.into(),
+            GraphResourceSource::ImportedTexture(texture),
+            current_state,
+        ))
+    }
+
+    pub fn create_buffer(&mut self, desc: BufferDesc) -> BufferHandle {
+        BufferHandle(self.add_resource(
+            desc.name.clone(),
+            GraphResourceSource::TransientBuffer(desc),
+            ResourceState::Undefined,
+        ))
+    }
+
+    pub fn create_texture(&mut self, desc: TextureDesc) -> TextureHandle {
+        TextureHandle(self.add_resource(
+            desc.name.clone(),
+            GraphResourceSource::TransientTexture(desc),
+            ResourceState::Undefined,
+        ))
+    }
+
+    pub fn add_pass(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        queue_type: QueueType,
+    ) -> PassBuilder<'_, 'a> {
+        self.passes.push(GraphPass {
+            name: name.into(),
+            queue_type,
+            accesses: Vec::new(),
+            side_effects: false,
+            execute: None,
+        });
+
+        PassBuilder {
+            pass: self.passes.last_mut().unwrap(),
+        }
+    }
+
+    pub fn compile(self, device: &Device) -> Result<CompiledRenderGraph<'a>, Error> {
+        let RenderGraph { resources, passes } = self;
+
+        let (mut graph, transient_heaps) =
+            schedule_passes(&resources, passes, |source| match source {
+                GraphResourceSource::TransientBuffer(desc) => {
+                    device.buffer_memory_requirements(desc)
+                }
+                GraphResourceSource::TransientTexture(desc) => {
+                    device.texture_memory_requirements(desc)
+                }
+                _ => unreachable!(),
+            });
+
+        graph.heaps = transient_heaps
+            .iter()
+            .map(|transient_heap| {
+                device.create_heap(&HeapDesc {
+                    name: Cow::Borrowed("Render graph transient heap"),
+                    size: transient_heap.size,
+                    memory_type_bits: transient_heap.memory_type_bits,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        graph.resources.entries = resources
+            .into_iter()
+            .zip(&graph.compiled_resources)
+            .map(|(resource, compiled_resource)| {
+                Ok(
+                    match (
+                        resource.source,
+                        compiled_resource.heap.map(|heap| &graph.heaps[heap]),
+                        compiled_resource.heap_offset,
+                    ) {
+                        (GraphResourceSource::ImportedBuffer(buffer), _, _) => {
+                            ResourceEntry::ImportedBuffer(buffer)
+                        }
+                        (GraphResourceSource::ImportedTexture(texture), _, _) => {
+                            ResourceEntry::ImportedTexture(texture)
+                        }
+                        (GraphResourceSource::TransientBuffer(desc), Some(heap), Some(offset)) => {
+                            ResourceEntry::TransientBuffer(
+                                device.create_placed_buffer(heap, offset, &desc)?,
+                            )
+                        }
+                        (GraphResourceSource::TransientTexture(desc), Some(heap), Some(offset)) => {
+                            ResourceEntry::TransientTexture(
+                                device.create_placed_texture(heap, offset, &desc)?,
+                            )
+                        }
+                        _ => ResourceEntry::Unused,
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(graph)
+    }
+
+    fn add_resource(
+        &mut self,
+        name: Cow<'static, str>,
+        source: GraphResourceSource<'a>,
+        initial_state: ResourceState,
+    ) -> usize {
+        self.resources.push(GraphResource {
+            name,
+            source,
+            initial_state,
+        });
+
+        self.resources.len() - 1
+    }
+}
+
+impl Default for RenderGraph<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PassBuilder<'g, 'a> {
+    pass: &'g mut GraphPass<'a>,
+}
+
+impl<'a> PassBuilder<'_, 'a> {
+    pub fn read_buffer(&mut self, buffer: BufferHandle, state: ResourceState) -> &mut Self {
+        self.access(buffer.0, state, false)
+    }
+
+    pub fn write_buffer(&mut self, buffer: BufferHandle, state: ResourceState) -> &mut Self {
+        self.access(buffer.0, state, true)
+    }
+
+    pub fn read_texture(&mut self, texture: TextureHandle, state: ResourceState) -> &mut Self {
+        self.access(texture.0, state, false)
+    }
+
+    pub fn write_texture(&mut self, texture: TextureHandle, state: ResourceState) -> &mut Self {
+        self.access(texture.0, state, true)
+    }
+
+    pub fn side_effects(&mut self) -> &mut Self {
+        self.pass.side_effects = true;
+        self
+    }
+
+    pub fn execute(
+        &mut self,
+        execute: impl FnOnce(&mut CommandList, &RenderGraphResources<'a>) + 'a,
+    ) {
+        self.pass.execute = Some(Box::new(execute));
+    }
+
+    fn access(&mut self, resource: usize, state: ResourceState, write: bool) -> &mut Self {
+        self.pass.accesses.push(GraphResourceAccess {
+            resource,
+            state,
+            write,
+        });
+        self
+    }
+}
+
+enum ResourceEntry<'a> {
+    ImportedBuffer(&'a Buffer),
+    ImportedTexture(&'a Texture),
+    TransientBuffer(Buffer),
+    TransientTexture(Texture),
+    Unused,
+}
+
+pub struct RenderGraphResources<'a> {
+    entries: Vec<ResourceEntry<'a>>,
+}
+
+impl RenderGraphResources<'_> {
+    pub fn buffer(&self, buffer: BufferHandle) -> &Buffer {
+        match &self.entries[buffer.0] {
+            ResourceEntry::ImportedBuffer(buffer) => buffer,
+            ResourceEntry::TransientBuffer(buffer) => buffer,
+            _ => panic!("Render graph resource {} is not a live buffer", buffer.0),
+        }
+    }
+
+    pub fn texture(&self, texture: TextureHandle) -> &Texture {
+        match &self.entries[texture.0] {
+            ResourceEntry::ImportedTexture(texture) => texture,
+            ResourceEntry::TransientTexture(texture) => texture,
+            _ => panic!("Render graph resource {} is not a live texture", texture.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CompiledResource {
+    pub name: Cow<'static, str>,
+    pub kind: GraphResourceKind,
+    pub imported: bool,
+    pub lifetime: Option<Range<usize>>,
+    pub heap: Option<usize>,
+    pub heap_offset: Option<u64>,
+    pub size: u64,
+    pub aliases: Option<usize>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GraphBarrier {
+    pub resource: usize,
+    pub old_state: ResourceState,
+    pub new_state: ResourceState,
+    pub discard_contents: bool,
+    pub queue_transfer: Option<QueueTransfer>,
+}
+
+pub struct CompiledPass<'a> {
+    pub name: Cow<'static, str>,
+    pub queue_type: QueueType,
+    pub accesses: Vec<GraphResourceAccess>,
+    pub barriers: Vec<GraphBarrier>,
+    pub release_barriers: Vec<GraphBarrier>,
+    execute: Option<PassExecuteFn<'a>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GraphBatch {
+    pub queue_type: QueueType,
+    pub passes: Range<usize>,
+    pub waits: Vec<usize>,
+}
+
+pub struct CompiledRenderGraph<'a> {
+    resources: RenderGraphResources<'a>,
+    compiled_resources: Vec<CompiledResource>,
+    passes: Vec<CompiledPass<'a>>,
+    culled_passes: Vec<Cow<'static, str>>,
+    batches: Vec<GraphBatch>,
+    heap_size: u64,
+    heaps: Vec<Heap>,
+}
+
+impl<'a> CompiledRenderGraph<'a> {
+    pub fn resources(&self) -> &[CompiledResource] {
+        &self.compiled_resources
+    }
+
+    pub fn passes(&self) -> &[CompiledPass<'a>] {
+        &self.passes
+    }
+
+    pub fn culled_passes(&self) -> &[Cow<'static, str>] {
+        &self.culled_passes
+    }
+
+    pub fn batches(&self) -> &[GraphBatch] {
+        &self.batches
+    }
+
+    pub fn heap_size(&self) -> u64 {
+        self.heap_size
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph RenderGraph {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(dot, "    node [fontname=\"monospace\"];").unwrap();
+
+        for (batch_index, batch) in self.batches.iter().enumerate() {
+            writeln!(dot, "    subgraph cluster_batch{batch_index} {{").unwrap();
+            writeln!(
+                dot,
+                "        label=\"batch {batch_index} ({:?})\";",
+                batch.queue_type
+            )
+            .unwrap();
+            writeln!(dot, "        style=rounded;").unwrap();
+
+            for pass_index in batch.passes.clone() {
+                let pass = &self.passes[pass_index];
+
+                let mut label = dot_escape(&pass.name);
+                for barrier in pass.barriers.iter().chain(&pass.release_barriers) {
+                    write!(
+                        label,
+                        "\\l{}: {:?} -> {:?}{}{}",
+                        dot_escape(&self.compiled_resources[barrier.resource].name),
+                        barrier.old_state,
+                        barrier.new_state,
+                        if barrier.discard_contents {
+                            " (discard)"
+                        } else {
+                            ""
+                        },
+                        match barrier.queue_transfer {
+                            Some(QueueTransfer::Release { dst_queue }) => {
+                                format!(" (release to {dst_queue:?})")
+                            }
+                            Some(QueueTransfer::Acquire { src_queue }) => {
+                                format!(" (acquire from {src_queue:?})")
+                            }
+                            None => String::new(),
+                        }
+                    )
+                    .unwrap();
+                }
+                if !pass.barriers.is_empty() || !pass.release_barriers.is_empty() {
+                    label.push_str("\\l");
+                }
+
+                writeln!(
+                    dot,
+                    "        pass{pass_index} [shape=box, label=\"{label}\"];"
+                )
+                .unwrap();
+            }
+
+            writeln!(dot, "    }}").unwrap();
+        }
+
+        for (culled_index, name) in self.culled_passes.iter().enumerate() {
+            writeln!(
+                dot,
+                "    culled{culled_index} [shape=box, style=dashed, color=gray, fontcolor=gray, label=\"{} (culled)\"];",
+                dot_escape(name)
+            )
+            .unwrap();
+        }
+
+        for (resource_index, resource) in self.compiled_resources.iter().enumerate() {
+            let mut label = format!("{} ({:?})", dot_escape(&resource.name), resource.kind);
+            if let (Some(heap), Some(offset)) = (resource.heap, resource.heap_offset) {
+                write!(
+                    label,
+                    "\\nheap {heap} [{offset}, {})",
+                    offset + resource.size
+                )
+                .unwrap();
+            }
+
+            writeln!(
+                dot,
+                "    resource{resource_index} [shape=ellipse, style={}, label=\"{label}\"];",
+                if resource.imported { "bold" } else { "solid" }
+            )
+            .unwrap();
+        }
+
+        for (pass_index, pass) in self.passes.iter().enumerate() {
+            for access in &pass.accesses {
+                if access.write {
+                    writeln!(
+                        dot,
+                        "    pass{pass_index} -> resource{} [label=\"{:?}\", color=red];",
+                        access.resource, access.state
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(
+                        dot,
+                        "    resource{} -> pass{pass_index} [label=\"{:?}\"];",
+                        access.resource, access.state
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        for batch in &self.batches {
+            for wait in &batch.waits {
+                writeln!(
+                    dot,
+                    "    pass{} -> pass{} [style=dashed, color=blue, label=\"wait\"];",
+                    self.batches[*wait].passes.end - 1,
+                    batch.passes.start
+                )
+                .unwrap();
+            }
+        }
+
+        for (resource_index, resource) in self.compiled_resources.iter().enumerate() {
+            if let Some(aliased) = resource.aliases {
+                writeln!(
+                    dot,
+                    "    resource{aliased} -> resource{resource_index} [style=dotted, label=\"aliases\"];"
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        write!(json, "{{\"heap_size\":{},\"passes\":[", self.heap_size()).unwrap();
+        for (pass_index, pass) in self.passes.iter().enumerate() {
+            if pass_index > 0 {
+                json.push(',');
+            }
+
+            write!(
+                json,
+                "{{\"index\":{pass_index},\"name\":{},\"queue\":\"{:?}\",\"accesses\":[",
+                json_string(&pass.name),
+                pass.queue_type
+            )
+            .unwrap();
+            for (access_index, access) in pass.accesses.iter().enumerate() {
+                if access_index > 0 {
+                    json.push(',');
+                }
+                write!(
+                    json,
+                    "{{\"resource\":{},\"state\":\"{:?}\",\"write\":{}}}",
+                    access.resource, access.state, access.write
+                )
+                .unwrap();
+            }
+
+            json.push_str("],\"barriers\":");
+            json_barriers(&mut json, &pass.barriers);
+            json.push_str(",\"release_barriers\":");
+            json_barriers(&mut json, &pass.release_barriers);
+            json.push('}');
+        }
+
+        json.push_str("],\"culled_passes\":[");
+        for (culled_index, name) in self.culled_passes.iter().enumerate() {
+            if culled_index > 0 {
+                json.push(',');
+            }
+            json.push_str(&json_string(name));
+        }
+
+        json.push_str("],\"resources\":[");
+        for (resource_index, resource) in self.compiled_resources.iter().enumerate() {
+            if resource_index > 0 {
+                json.push(',');
+            }
+
+            write!(
+                json,
+                "{{\"index\":{resource_index},\"name\":{},\"kind\":\"{:?}\",\"imported\":{},\"lifetime\":{},\"heap\":{},\"heap_offset\":{},\"size\":{},\"aliases\":{}}}",
+                json_string(&resource.name),
+                resource.kind,
+                resource.imported,
+                resource
+                    .lifetime
+                    .as_ref()
+                    .map_or("null".to_owned(), |lifetime| format!(
+                        "[{},{}]",
+                        lifetime.start,
+                        lifetime.end - 1
+                    )),
+                resource
+                    .heap
+                    .map_or("null".to_owned(), |heap| heap.to_string()),
+                resource
+                    .heap_offset
+                    .map_or("null".to_owned(), |offset| offset.to_string()),
+                resource.size,
+                resource
+                    .aliases
+                    .map_or("null".to_owned(), |aliased| aliased.to_string()),
+            )
+            .unwrap();
+        }
+
+        json.push_str("],\"batches\":[");
+        for (batch_index, batch) in self.batches.iter().enumerate() {
+            if batch_index > 0 {
+                json.push(',');
+            }
+
+            write!(
+                json,
+                "{{\"index\":{batch_index},\"queue\":\"{:?}\",\"passes\":[{},{}],\"waits\":[{}]}}",
+                batch.queue_type,
+                batch.passes.start,
+                batch.passes.end - 1,
+                batch
+                    .waits
+                    .iter()
+                    .map(|wait| wait.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+            .unwrap();
+        }
+        json.push_str("]}");
+
+        json
+    }
+
+    pub fn execute(self, device: &Device) -> Result<(), Error> {
+        let queues = [
+            device.get_direct_queue(),
+            device.get_compute_queue(),
+            device.get_transfer_queue(),
+        ];
+        let queue = |queue_type: QueueType| -> &Queue {
+            match queue_type {
+                QueueType::Direct => &queues[0],
+                QueueType::Compute => &queues[1],
+                QueueType::Transfer => &queues[2],
+            }
+        };
+
+        // Command lists, transient resources and heaps are destroyed deferred once the graph is
+        // consumed, so they are released as soon as the submitted values have completed.
+        let CompiledRenderGraph {
+            resources,
+            mut passes,
+            batches,
+            ..
+        } = self;
+
+        let mut batch_values = Vec::with_capacity(batches.len());
+
+        for batch in &batches {
+            let mut command_list = device.create_command_list(batch.queue_type)?;
+            command_list.begin()?;
+            command_list.set_automatic_barriers(false);
+
+            for pass in &mut passes[batch.passes.clone()] {
+                record_barriers(&mut command_list, &resources, &pass.barriers);
+
+                if let Some(execute) = pass.execute.take() {
+                    execute(&mut command_list, &resources);
+                }
+
+                record_barriers(&mut command_list, &resources, &pass.release_barriers);
+            }
+
+            command_list.end()?;
+
+            let waits = batch
+                .waits
+                .iter()
+                .map(|wait| (queue(batches[*wait].queue_type), batch_values[*wait]))
+                .collect::<Vec<_>>();
+
+            let value = queue(batch.queue_type).submit_with_waits(&[&command_list], &waits)?;
+            batch_values.push(value);
+        }
+
+        Ok(())
+    }
+}
+
+struct ResourceUsage {
+    state: ResourceState,
+    last_writer: Option<usize>,
+    readers: Vec<usize>,
+    last_access: Option<usize>,
+    accessed_by: Vec<usize>,
+}
+
+fn record_barriers(
+    command_list: &mut CommandList,
+    resources: &RenderGraphResources,
+    barriers: &[GraphBarrier],
+) {
+    let buffer_barriers = barriers
+        .iter()
+        .filter_map(|barrier| match &resources.entries[barrier.resource] {
+            ResourceEntry::ImportedBuffer(buffer) => Some((*buffer, barrier)),
+            ResourceEntry::TransientBuffer(buffer) => Some((buffer, barrier)),
+            _ => None,
+        })
+        .map(|(buffer, barrier)| BufferBarrier {
+            buffer,
+            old_state: barrier.old_state,
+            new_state: barrier.new_state,
+            queue_transfer: barrier.queue_transfer,
+        })
+        .collect::<Vec<_>>();
+
+    let texture_barriers = barriers
+        .iter()
+        .filter_map(|barrier| match &resources.entries[barrier.resource] {
+            ResourceEntry::ImportedTexture(texture) => Some((*texture, barrier)),
+            ResourceEntry::TransientTexture(texture) => Some((texture, barrier)),
+            _ => None,
+        })
+        .map(|(texture, barrier)| TextureBarrier {
+            texture,
+            range: None,
+            old_state: barrier.old_state,
+            new_state: barrier.new_state,
+            discard_contents: barrier.discard_contents,
+            queue_transfer: barrier.queue_transfer,
+        })
+        .collect::<Vec<_>>();
+
+    command_list.barrier(&buffer_barriers, &texture_barriers);
+}
+
+fn schedule_passes<'a>(
+    resources: &[GraphResource<'a>],
+    graph_passes: Vec<GraphPass<'a>>,
+    memory_requirements: impl Fn(&GraphResourceSource) -> MemoryRequirements,
+) -> (CompiledRenderGraph<'a>, Vec<TransientHeap>) {
+    let live = cull_passes(resources, &graph_passes);
+
+    let mut passes = Vec::new();
+    let mut culled_passes = Vec::new();
+    for (pass, live) in graph_passes.into_iter().zip(live) {
+        if live {
+            passes.push(pass);
+        } else {
+            culled_passes.push(pass.name);
+        }
+    }
+
+    let mut compiled_resources = resources
+        .iter()
+        .map(|resource| CompiledResource {
+            name: resource.name.clone(),
+            kind: resource.kind(),
+            imported: resource.is_imported(),
+            lifetime: None,
+            heap: None,
+            heap_offset: None,
+            size: 0,
+            aliases: None,
+        })
+        .collect::<Vec<_>>();
+
+    for (pass_index, pass) in passes.iter().enumerate() {
+        for access in &pass.accesses {
+            let lifetime = &mut compiled_resources[access.resource].lifetime;
+            *lifetime = Some(match lifetime.take() {
+                Some(lifetime) => lifetime.start..pass_index + 1,
+                None => pass_index..pass_index + 1,
+            });
+        }
+    }
+
+    let transient_heaps =
+        alias_transient_resources(resources, &mut compiled_resources, memory_requirements);
+
+    let mut dependencies = vec![HashSet::new(); passes.len()];
+    let mut barriers = vec![Vec::new(); passes.len()];
+    let mut release_barriers = vec![Vec::new(); passes.len()];
+    let mut resource_states = resources
+        .iter()
+        .map(|resource| ResourceUsage {
+            state: resource.initial_state,
+            last_writer: None,
+            readers: Vec::new(),
+            last_access: None,
+            accessed_by: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    for (pass_index, pass) in passes.iter().enumerate() {
+        for access in &pass.accesses {
+            let resource = &compiled_resources[access.resource];
+            let usage = &mut resource_states[access.resource];
+
+            let first_use = resource.lifetime.as_ref().unwrap().start == pass_index
+                && usage.last_writer.is_none()
+                && usage.readers.is_empty();
+
+            let mut old_state = usage.state;
+            let mut discard_contents = false;
+
+            if first_use && !resource.imported {
+                discard_contents = true;
+
+                // Any pass that accessed the aliased resource may still be using its memory, not
+                // only the last one, e.g. readers on other queues.
+                if let Some(aliased) = resource.aliases {
+                    let aliased_usage = &resource_states[aliased];
+                    old_state = aliased_usage.state;
+
+                    dependencies[pass_index].extend(aliased_usage.accessed_by.iter().copied());
+                }
+            }
+
+            let usage = &mut resource_states[access.resource];
+
+            if let Some(last_writer) = usage.last_writer {
+                dependencies[pass_index].insert(last_writer);
+            }
+            if access.write {
+                dependencies[pass_index].extend(usage.readers.iter().copied());
+            }
+
+            // Resources are exclusive to one queue family, so contents that survive a change of
+            // queue are released by the last pass on the old queue and acquired here.
+            let queue_transfer = usage
+                .last_access
+                .filter(|last_access| {
+                    passes[*last_access].queue_type != pass.queue_type
+                        && !discard_contents
+                        && old_state != ResourceState::Undefined
+                })
+                .map(|last_access| (last_access, passes[last_access].queue_type));
+
+            if let Some((last_access, src_queue)) = queue_transfer {
+                dependencies[pass_index].insert(last_access);
+
+                release_barriers[last_access].push(GraphBarrier {
+                    resource: access.resource,
+                    old_state,
+                    new_state: access.state,
+                    discard_contents,
+                    queue_transfer: Some(QueueTransfer::Release {
+                        dst_queue: pass.queue_type,
+                    }),
+                });
+
+                barriers[pass_index].push(GraphBarrier {
+                    resource: access.resource,
+                    old_state,
+                    new_state: access.state,
+                    discard_contents,
+                    queue_transfer: Some(QueueTransfer::Acquire { src_queue }),
+                });
+            }
+
+            let needs_barrier = queue_transfer.is_none()
+                && match resource.kind {
+                    GraphResourceKind::Buffer => {
+                        old_state != ResourceState::Undefined
+                            && (old_state.is_write() || access.state.is_write())
+                    }
+                    GraphResourceKind::Texture => {
+                        old_state != access.state
+                            || old_state.is_write()
+                            || access.state.is_write()
+                            || discard_contents
+                    }
+                };
+
+            if needs_barrier {
+                barriers[pass_index].push(GraphBarrier {
+                    resource: access.resource,
+                    old_state,
+                    new_state: access.state,
+                    discard_contents,
+                    queue_transfer: None,
+                });
+            }
+
+            usage.state = access.state;
+            usage.last_access = Some(pass_index);
+            usage.accessed_by.push(pass_index);
+            if access.write {
+                usage.last_writer = Some(pass_index);
+                usage.readers.clear();
+            } else {
+                usage.readers.push(pass_index);
+            }
+        }
+
+        dependencies[pass_index].remove(&pass_index);
+    }
+
+    let mut batches: Vec<GraphBatch> = Vec::new();
+    let mut pass_batches = Vec::with_capacity(passes.len());
+    for (pass_index, pass) in passes.iter().enumerate() {
+        match batches.last_mut() {
+            Some(batch) if batch.queue_type == pass.queue_type => {
+                batch.passes.end = pass_index + 1;
+            }
+            _ => batches.push(GraphBatch {
+                queue_type: pass.queue_type,
+                passes: pass_index..pass_index + 1,
+                waits: Vec::new(),
+            }),
+        }
+
+        let batch_index = batches.len() - 1;
+        pass_batches.push(batch_index);
+
+        for dependency in &dependencies[pass_index] {
+            let dependency_batch = pass_batches[*dependency];
+
+            if batches[dependency_batch].queue_type == pass.queue_type {
+                continue;
+            }
+
+            let dependency_queue_type = batches[dependency_batch].queue_type;
+            let existing_wait = batches[batch_index]
+                .waits
+                .iter()
+                .position(|wait| batches[*wait].queue_type == dependency_queue_type);
+
+            let waits = &mut batches[batch_index].waits;
+            match existing_wait {
+                Some(position) => {
+                    waits[position] = waits[position].max(dependency_batch);
+                }
+                None => waits.push(dependency_batch),
+            }
+        }
+    }
+
+    for batch in &mut batches {
+        batch.waits.sort_unstable();
+    }
+
+    let passes = passes
+        .into_iter()
+        .zip(barriers)
+        .zip(release_barriers)
+        .map(|((pass, barriers), release_barriers)| CompiledPass {
+            name: pass.name,
+            queue_type: pass.queue_type,
+            accesses: pass.accesses,
+            barriers,
+            release_barriers,
+            execute: pass.execute,
+        })
+        .collect();
+
+    let graph = CompiledRenderGraph {
+        resources: RenderGraphResources {
+            entries: Vec::new(),
+        },
+        compiled_resources,
+        passes,
+        culled_passes,
+        batches,
+        heap_size: transient_heaps.iter().map(|heap| heap.size).sum(),
+        heaps: Vec::new(),
+    };
+
+    (graph, transient_heaps)
+}
+
+fn cull_passes(resources: &[GraphResource], passes: &[GraphPass]) -> Vec<bool> {
+    let mut live_passes = vec![false; passes.len()];
+    let mut live_resources = HashSet::new();
+
+    for (pass_index, pass) in passes.iter().enumerate().rev() {
+        let live = pass.side_effects
+            || pass.accesses.iter().any(|access| {
+                access.write
+                    && (resources[access.resource].is_imported()
+                        || live_resources.contains(&access.resource))
+            });
+
+        if !live {
+            continue;
+        }
+
+        live_passes[pass_index] = true;
+
+        for access in &pass.accesses {
+            if access.write {
+                live_resources.remove(&access.resource);
+            }
+        }
+        for access in &pass.accesses {
+            if !access.write {
+                live_resources.insert(access.resource);
+            }
+        }
+    }
+
+    live_passes
+}
+
+struct TransientHeap {
+    size: u64,
+    memory_type_bits: u32,
+}
+
+fn alias_transient_resources(
+    resources: &[GraphResource],
+    compiled_resources: &mut [CompiledResource],
+    memory_requirements: impl Fn(&GraphResourceSource) -> MemoryRequirements,
+) -> Vec<TransientHeap> {
+    struct MemoryBlock {
+        heap: usize,
+        offset: u64,
+        size: u64,
+        occupant: usize,
+        free_after: usize,
+    }
+
+    let mut order = (0..resources.len())
+        .filter(|index| {
+            !resources[*index].is_imported() && compiled_resources[*index].lifetime.is_some()
+        })
+        .collect::<Vec<_>>();
+    order.sort_by_key(|index| compiled_resources[*index].lifetime.as_ref().unwrap().start);
+
+    let mut blocks: Vec<MemoryBlock> = Vec::new();
+    let mut heaps: Vec<TransientHeap> = Vec::new();
+
+    for index in order {
+        let memory_requirements = memory_requirements(&resources[index].source);
+
+        let lifetime = compiled_resources[index].lifetime.clone().unwrap();
+
+        // Every resource placed in a heap must accept the heap's memory type, so a heap only
+        // takes resources whose memory type bits still overlap with everything already in it.
+        let block = blocks.iter_mut().find(|block| {
+            block.free_after < lifetime.start
+                && block.size >= memory_requirements.size
+                && block.offset % memory_requirements.alignment == 0
+                && heaps[block.heap].memory_type_bits & memory_requirements.memory_type_bits != 0
+        });
+
+        let compiled_resource = &mut compiled_resources[index];
+        compiled_resource.size = memory_requirements.size;
+
+        match block {
+            Some(block) => {
+                heaps[block.heap].memory_type_bits &= memory_requirements.memory_type_bits;
+
+                compiled_resource.heap = Some(block.heap);
+                compiled_resource.heap_offset = Some(block.offset);
+                compiled_resource.aliases = Some(block.occupant);
+
+                block.occupant = index;
+                block.free_after = lifetime.end - 1;
+            }
+            None => {
+                let heap = match heaps.iter().position(|heap| {
+                    heap.memory_type_bits & memory_requirements.memory_type_bits != 0
+                }) {
+                    Some(heap) => heap,
+                    None => {
+                        heaps.push(TransientHeap {
+                            size: 0,
+                            memory_type_bits: memory_requirements.memory_type_bits,
+                        });
+                        heaps.len() - 1
+                    }
+                };
+
+                let transient_heap = &mut heaps[heap];
+                transient_heap.memory_type_bits &= memory_requirements.memory_type_bits;
+
+                let offset = transient_heap
+                    .size
+                    .next_multiple_of(memory_requirements.alignment);
+                transient_heap.size = offset + memory_requirements.size;
+
+                compiled_resource.heap = Some(heap);
+                compiled_resource.heap_offset = Some(offset);
+
+                blocks.push(MemoryBlock {
+                    heap,
+                    offset,
+                    size: memory_requirements.size,
+                    occupant: index,
+                    free_after: lifetime.end - 1,
+                });
+            }
+        }
+    }
+
+    heaps
+}
+
+fn json_barriers(json: &mut String, barriers: &[GraphBarrier]) {
+    json.push('[');
+    for (barrier_index, barrier) in barriers.iter().enumerate() {
+        if barrier_index > 0 {
+            json.push(',');
+        }
+        write!(
+            json,
+            "{{\"resource\":{},\"old_state\":\"{:?}\",\"new_state\":\"{:?}\",\"discard_contents\":{},\"queue_transfer\":{}}}",
+            barrier.resource,
+            barrier.old_state,
+            barrier.new_state,
+            barrier.discard_contents,
+            match barrier.queue_transfer {
+                Some(QueueTransfer::Release { dst_queue }) => {
+                    format!("{{\"release\":\"{dst_queue:?}\"}}")
+                }
+                Some(QueueTransfer::Acquire { src_queue }) => {
+                    format!("{{\"acquire\":\"{src_queue:?}\"}}")
+                }
+                None => "null".to_owned(),
+            }
+        )
+        .unwrap();
+    }
+    json.push(']');
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BufferUsage, MemoryLocation};
+
+    fn buffer(name: &'static str) -> BufferDesc {
+        BufferDesc {
+            name: Cow::Borrowed(name),
+            size: 1024,
+            usage: BufferUsage::STORAGE,
+            memory_location: MemoryLocation::GpuOnly,
+        }
+    }
+
+    fn schedule(graph: RenderGraph<'_>) -> CompiledRenderGraph<'_> {
+        let RenderGraph { resources, passes } = graph;
+
+        let (graph, _) = schedule_passes(&resources, passes, |source| match source {
+            GraphResourceSource::TransientBuffer(desc) => MemoryRequirements {
+                size: desc.size,
+                alignment: 256,
+                memory_type_bits: 0b1,
+            },
+            _ => unreachable!(),
+        });
+
+        graph
+    }
+
+    fn barrier(
+        resource: usize,
+        old_state: ResourceState,
+        new_state: ResourceState,
+        discard_contents: bool,
+        queue_transfer: Option<QueueTransfer>,
+    ) -> GraphBarrier {
+        GraphBarrier {
+            resource,
+            old_state,
+            new_state,
+            discard_contents,
+            queue_transfer,
+        }
+    }
+
+    #[test]
+    fn culls_passes_without_observable_writes() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_buffer(buffer("a"));
+        let b = graph.create_buffer(buffer("b"));
+
+        graph
+            .add_pass("overwritten", QueueType::Direct)
+            .write_buffer(a, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("write a", QueueType::Direct)
+            .write_buffer(a, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("unused", QueueType::Direct)
+            .write_buffer(b, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("read a", QueueType::Direct)
+            .read_buffer(a, ResourceState::ShaderResource)
+            .side_effects();
+
+        let graph = schedule(graph);
+
+        let passes = graph
+            .passes()
+            .iter()
+            .map(|pass| pass.name.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(passes, ["write a", "read a"]);
+        assert_eq!(graph.culled_passes(), ["overwritten", "unused"]);
+        assert_eq!(graph.resources()[a.0].lifetime, Some(0..2));
+        assert_eq!(graph.resources()[b.0].lifetime, None);
+        assert_eq!(graph.resources()[b.0].heap, None);
+    }
+
+    #[test]
+    fn generates_barriers_between_dependent_accesses() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_buffer(buffer("a"));
+        let b = graph.create_buffer(buffer("b"));
+
+        graph
+            .add_pass("upload", QueueType::Direct)
+            .write_buffer(a, ResourceState::TransferDst);
+        graph
+            .add_pass("simulate", QueueType::Direct)
+            .read_buffer(a, ResourceState::ShaderResource)
+            .write_buffer(b, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("draw", QueueType::Direct)
+            .read_buffer(a, ResourceState::ShaderResource)
+            .read_buffer(b, ResourceState::VertexBuffer)
+            .side_effects();
+
+        let graph = schedule(graph);
+        let passes = graph.passes();
+
+        assert!(passes[0].barriers.is_empty());
+        assert_eq!(
+            passes[1].barriers,
+            [barrier(
+                a.0,
+                ResourceState::TransferDst,
+                ResourceState::ShaderResource,
+                false,
+                None
+            )]
+        );
+        assert_eq!(
+            passes[2].barriers,
+            [barrier(
+                b.0,
+                ResourceState::UnorderedAccess,
+                ResourceState::VertexBuffer,
+                false,
+                None
+            )]
+        );
+        assert!(passes.iter().all(|pass| pass.release_barriers.is_empty()));
+    }
+
+    #[test]
+    fn aliases_transient_resources_with_disjoint_lifetimes() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_buffer(buffer("a"));
+        let b = graph.create_buffer(buffer("b"));
+        let c = graph.create_buffer(buffer("c"));
+
+        graph
+            .add_pass("write a", QueueType::Direct)
+            .write_buffer(a, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("write b", QueueType::Direct)
+            .read_buffer(a, ResourceState::ShaderResource)
+            .write_buffer(b, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("write c", QueueType::Direct)
+            .read_buffer(b, ResourceState::ShaderResource)
+            .write_buffer(c, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("read c", QueueType::Direct)
+            .read_buffer(c, ResourceState::ShaderResource)
+            .side_effects();
+
+        let graph = schedule(graph);
+        let resources = graph.resources();
+
+        assert_eq!(graph.heap_size(), 2048);
+        assert_eq!(resources[a.0].heap_offset, Some(0));
+        assert_eq!(resources[b.0].heap_offset, Some(1024));
+        assert_eq!(resources[b.0].aliases, None);
+        assert_eq!(resources[c.0].heap_offset, Some(0));
+        assert_eq!(resources[c.0].aliases, Some(a.0));
+        assert!(graph.passes()[2].barriers.contains(&barrier(
+            c.0,
+            ResourceState::ShaderResource,
+            ResourceState::UnorderedAccess,
+            true,
+            None
+        )));
+    }
+
+    #[test]
+    fn batches_consecutive_passes_on_the_same_queue() {
+        let mut graph = RenderGraph::new();
+
+        for (name, queue_type) in [
+            ("shadows", QueueType::Direct),
+            ("gbuffer", QueueType::Direct),
+            ("culling", QueueType::Compute),
+            ("lighting", QueueType::Direct),
+        ] {
+            graph.add_pass(name, queue_type).side_effects();
+        }
+
+        let graph = schedule(graph);
+
+        assert_eq!(
+            graph.batches(),
+            [
+                GraphBatch {
+                    queue_type: QueueType::Direct,
+                    passes: 0..2,
+                    waits: Vec::new(),
+                },
+                GraphBatch {
+                    queue_type: QueueType::Compute,
+                    passes: 2..3,
+                    waits: Vec::new(),
+                },
+                GraphBatch {
+                    queue_type: QueueType::Direct,
+                    passes: 3..4,
+                    waits: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn transfers_ownership_and_waits_across_queues() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_buffer(buffer("a"));
+
+        graph
+            .add_pass("write", QueueType::Direct)
+            .write_buffer(a, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("read", QueueType::Compute)
+            .read_buffer(a, ResourceState::ShaderResource)
+            .side_effects();
+
+        let graph = schedule(graph);
+        let passes = graph.passes();
+
+        assert_eq!(
+            passes[0].release_barriers,
+            [barrier(
+                a.0,
+                ResourceState::UnorderedAccess,
+                ResourceState::ShaderResource,
+                false,
+                Some(QueueTransfer::Release {
+                    dst_queue: QueueType::Compute
+                })
+            )]
+        );
+        assert_eq!(
+            passes[1].barriers,
+            [barrier(
+                a.0,
+                ResourceState::UnorderedAccess,
+                ResourceState::ShaderResource,
+                false,
+                Some(QueueTransfer::Acquire {
+                    src_queue: QueueType::Direct
+                })
+            )]
+        );
+        assert!(graph.batches()[0].waits.is_empty());
+        assert_eq!(graph.batches()[1].waits, [0]);
+    }
+
+    #[test]
+    fn aliased_resources_wait_on_every_pass_of_the_previous_occupant() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_buffer(buffer("a"));
+        let b = graph.create_buffer(buffer("b"));
+
+        graph
+            .add_pass("write a", QueueType::Compute)
+            .write_buffer(a, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("read a", QueueType::Direct)
+            .read_buffer(a, ResourceState::ShaderResource)
+            .side_effects();
+        graph
+            .add_pass("write b", QueueType::Transfer)
+            .write_buffer(b, ResourceState::TransferDst)
+            .side_effects();
+
+        let graph = schedule(graph);
+
+        assert_eq!(graph.resources()[b.0].aliases, Some(a.0));
+        assert_eq!(graph.batches()[1].waits, [0]);
+        assert_eq!(graph.batches()[2].waits, [0, 1]);
+    }
+
+    fn export_graph() -> CompiledRenderGraph<'static> {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_buffer(buffer("a"));
+        let b = graph.create_buffer(buffer("b"));
+        let c = graph.create_buffer(buffer("c"));
+
+        graph
+            .add_pass("write a", QueueType::Direct)
+            .write_buffer(a, ResourceState::UnorderedAccess);
+        graph
+            .add_pass("read a", QueueType::Compute)
+            .read_buffer(a, ResourceState::ShaderResource)
+            .write_buffer(b, ResourceState::UnorderedAccess)
+            .side_effects();
+        graph
+            .add_pass("unused \"debug\"", QueueType::Direct)
+            .write_buffer(c, ResourceState::UnorderedAccess);
+
+        schedule(graph)
+    }
+
+    #[test]
+    fn exports_dot() {
+        assert_eq!(
+            export_graph().to_dot(),
+            r#"digraph RenderGraph {
+    rankdir=LR;
+    node [fontname="monospace"];
+    subgraph cluster_batch0 {
+        label="batch 0 (Direct)";
+        style=rounded;
+        pass0 [shape=box, label="write a\la: UnorderedAccess -> ShaderResource (release to Compute)\l"];
+    }
+    subgraph cluster_batch1 {
+        label="batch 1 (Compute)";
+        style=rounded;
+        pass1 [shape=box, label="read a\la: UnorderedAccess -> ShaderResource (acquire from Direct)\l"];
+    }
+    culled0 [shape=box, style=dashed, color=gray, fontcolor=gray, label="unused \"debug\" (culled)"];
+    resource0 [shape=ellipse, style=solid, label="a (Buffer)\nheap 0 [0, 1024)"];
+    resource1 [shape=ellipse, style=solid, label="b (Buffer)\nheap 0 [1024, 2048)"];
+    resource2 [shape=ellipse, style=solid, label="c (Buffer)"];
+    pass0 -> resource0 [label="UnorderedAccess", color=red];
+    resource0 -> pass1 [label="ShaderResource"];
+    pass1 -> resource1 [label="UnorderedAccess", color=red];
+    pass0 -> pass1 [style=dashed, color=blue, label="wait"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn exports_json() {
+        assert_eq!(
+            export_graph().to_json(),
+            concat!(
+                r#"{"heap_size":2048,"passes":["#,
+                r#"{"index":0,"name":"write a","queue":"Direct","#,
+                r#""accesses":[{"resource":0,"state":"UnorderedAccess","write":true}],"#,
+                r#""barriers":[],"#,
+                r#""release_barriers":[{"resource":0,"old_state":"UnorderedAccess","new_state":"ShaderResource","discard_contents":false,"queue_transfer":{"release":"Compute"}}]},"#,
+                r#"{"index":1,"name":"read a","queue":"Compute","#,
+                r#""accesses":[{"resource":0,"state":"ShaderResource","write":false},{"resource":1,"state":"UnorderedAccess","write":true}],"#,
+                r#""barriers":[{"resource":0,"old_state":"UnorderedAccess","new_state":"ShaderResource","discard_contents":false,"queue_transfer":{"acquire":"Direct"}}],"#,
+                r#""release_barriers":[]}],"#,
+                r#""culled_passes":["unused \"debug\""],"#,
+                r#""resources":["#,
+                r#"{"index":0,"name":"a","kind":"Buffer","imported":false,"lifetime":[0,1],"heap":0,"heap_offset":0,"size":1024,"aliases":null},"#,
+                r#"{"index":1,"name":"b","kind":"Buffer","imported":false,"lifetime":[1,1],"heap":0,"heap_offset":1024,"size":1024,"aliases":null},"#,
+                r#"{"index":2,"name":"c","kind":"Buffer","imported":false,"lifetime":null,"heap":null,"heap_offset":null,"size":0,"aliases":null}],"#,
+                r#""batches":[{"index":0,"queue":"Direct","passes":[0,0],"waits":[]},{"index":1,"queue":"Compute","passes":[1,1],"waits":[0]}]}"#,
+            )
+        );
+    }
+}
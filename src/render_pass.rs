@@ -0,0 +1,40 @@
+use crate::{Rect, Texture};
+
+pub struct RenderPassDesc<'a> {
+    pub color_attachments: &'a [ColorAttachment<'a>],
+    pub depth_stencil_attachment: Option<DepthStencilAttachment<'a>>,
+    pub render_area: Rect,
+}
+
+#[derive(Copy, Clone)]
+pub struct ColorAttachment<'a> {
+    pub texture: &'a Texture,
+    pub resolve_target: Option<&'a Texture>,
+    pub load_op: LoadOp,
+    pub store_op: StoreOp,
+    pub clear_value: [f32; 4],
+}
+
+#[derive(Copy, Clone)]
+pub struct DepthStencilAttachment<'a> {
+    pub texture: &'a Texture,
+    pub depth_load_op: LoadOp,
+    pub depth_store_op: StoreOp,
+    pub clear_depth: f32,
+    pub stencil_load_op: LoadOp,
+    pub stencil_store_op: StoreOp,
+    pub clear_stencil: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LoadOp {
+    Load,
+    Clear,
+    DontCare,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StoreOp {
+    Store,
+    DontCare,
+}
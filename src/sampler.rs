@@ -0,0 +1,99 @@
+use crate::CompareOp;
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+pub enum Sampler {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanSampler),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalSampler),
+}
+
+impl Sampler {
+    pub fn desc(&self) -> &SamplerDesc {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Sampler::Vulkan(vulkan_sampler) => vulkan_sampler.desc(),
+
+            #[cfg(feature = "metal")]
+            Sampler::Metal(metal_sampler) => metal_sampler.desc(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_sampler(&self) -> &VulkanSampler {
+        match self {
+            Sampler::Vulkan(vulkan_sampler) => vulkan_sampler,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_sampler(&self) -> &MetalSampler {
+        match self {
+            Sampler::Metal(metal_sampler) => metal_sampler,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SamplerDesc {
+    pub mag_filter: Filter,
+    pub min_filter: Filter,
+    pub mipmap_filter: Filter,
+    pub address_mode_u: AddressMode,
+    pub address_mode_v: AddressMode,
+    pub address_mode_w: AddressMode,
+    pub mip_lod_bias: f32,
+    pub max_anisotropy: Option<f32>,
+    pub compare_op: Option<CompareOp>,
+    pub min_lod: f32,
+    pub max_lod: f32,
+    pub border_color: BorderColor,
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            mipmap_filter: Filter::Linear,
+            address_mode_u: AddressMode::Repeat,
+            address_mode_v: AddressMode::Repeat,
+            address_mode_w: AddressMode::Repeat,
+            mip_lod_bias: 0.0,
+            max_anisotropy: None,
+            compare_op: None,
+            min_lod: 0.0,
+            max_lod: f32::MAX,
+            border_color: BorderColor::TransparentBlack,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AddressMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    ClampToBorder,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BorderColor {
+    TransparentBlack,
+    OpaqueBlack,
+    OpaqueWhite,
+}
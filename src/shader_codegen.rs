@@ -0,0 +1,516 @@
+use crate::shader_reflection::reflect_spirv;
+use crate::{
+    DescriptorType, Error, ShaderDesc, ShaderScalarType, ShaderStruct, ShaderStructMember,
+    ShaderType, util,
+};
+use std::fmt::Write;
+
+pub fn generate_shader_structs(desc: &ShaderDesc) -> Result<String, Error> {
+    let spir_v = util::spirv_code(desc)?;
+    let reflection = reflect_spirv(&spir_v, desc.kind, &desc.entry_point)?;
+
+    let mut generator = StructGenerator::default();
+
+    for binding in &reflection.bindings {
+        let Some(block) = &binding.block else {
+            continue;
+        };
+
+        match binding.descriptor_type {
+            DescriptorType::UniformBuffer => {
+                generator.generate(block, block.size)?;
+            }
+            DescriptorType::StorageBuffer | DescriptorType::ReadOnlyStorageBuffer => {
+                // Structured buffers wrap their elements in a single runtime array member.
+                for member in &block.members {
+                    if let ShaderType::Array {
+                        element, stride, ..
+                    } = &member.ty
+                    {
+                        if let ShaderType::Struct(element) = element.as_ref() {
+                            generator.generate(element, *stride)?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(push_constants) = &reflection.push_constants {
+        generator.generate(push_constants, push_constants.size)?;
+    }
+
+    Ok(generator.output)
+}
+
+#[derive(Default)]
+struct StructGenerator {
+    generated: Vec<(String, u32, Vec<ShaderStructMember>)>,
+    output: String,
+}
+
+impl StructGenerator {
+    fn generate(&mut self, shader_struct: &ShaderStruct, min_size: u32) -> Result<String, Error> {
+        let name = struct_name(&shader_struct.name);
+        let min_size = shader_struct.size.max(min_size);
+
+        if let Some((_, size, members)) = self
+            .generated
+            .iter()
+            .find(|(generated, ..)| *generated == name)
+        {
+            if *size != min_size || *members != shader_struct.members {
+                return Err(Error::ShaderReflection(
+                    "Struct is used with conflicting layouts",
+                ));
+            }
+
+            return Ok(name);
+        }
+        self.generated
+            .push((name.clone(), min_size, shader_struct.members.clone()));
+
+        let mut members = shader_struct.members.iter().collect::<Vec<_>>();
+        members.sort_by_key(|member| member.offset);
+
+        let mut fields = String::new();
+        let mut offset = 0;
+        let mut alignment = 1;
+
+        for (index, member) in members.iter().enumerate() {
+            if member.offset < offset {
+                return Err(Error::ShaderReflection(
+                    "Overlapping struct members cannot be represented in Rust",
+                ));
+            }
+
+            if member.offset > offset {
+                writeln!(
+                    fields,
+                    "    pub _pad{index}: [u8; {}],",
+                    member.offset - offset
+                )
+                .unwrap();
+            }
+
+            let rust_type = self.rust_type(&member.ty)?;
+            alignment = alignment.max(type_alignment(&member.ty));
+
+            writeln!(fields, "    pub {}: {rust_type},", field_name(&member.name)).unwrap();
+            offset = member.offset + member.ty.size();
+        }
+
+        let size = min_size.next_multiple_of(alignment);
+
+        if size > offset {
+            writeln!(
+                fields,
+                "    pub _pad{}: [u8; {}],",
+                members.len(),
+                size - offset
+            )
+            .unwrap();
+        }
+
+        writeln!(self.output, "#[repr(C)]").unwrap();
+        writeln!(
+            self.output,
+            "#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]"
+        )
+        .unwrap();
+        writeln!(self.output, "pub struct {name} {{\n{fields}}}\n").unwrap();
+        writeln!(
+            self.output,
+            "const _: () = assert!(std::mem::size_of::<{name}>() == {size});\n"
+        )
+        .unwrap();
+
+        Ok(name)
+    }
+
+    fn rust_type(&mut self, ty: &ShaderType) -> Result<String, Error> {
+        Ok(match ty {
+            ShaderType::Scalar(scalar) => scalar_type(*scalar).to_owned(),
+            ShaderType::Vector { scalar, components } => {
+                format!("[{}; {components}]", scalar_type(*scalar))
+            }
+            ShaderType::Matrix {
+                scalar,
+                columns,
+                rows,
+                stride,
+                row_major,
+            } => {
+                let (vectors, components) = if *row_major {
+                    (*rows, *columns)
+                } else {
+                    (*columns, *rows)
+                };
+
+                let lanes = if *stride == 0 {
+                    components
+                } else {
+                    stride / scalar.size()
+                };
+
+                if lanes == components {
+                    format!("[[{}; {components}]; {vectors}]", scalar_type(*scalar))
+                } else {
+                    // The last vector is not padded out to the stride, so the matrix is flattened.
+                    format!(
+                        "[{}; {}]",
+                        scalar_type(*scalar),
+                        (vectors - 1) * lanes + components
+                    )
+                }
+            }
+            ShaderType::Array {
+                element,
+                count,
+                stride,
+            } => {
+                let count = count.ok_or(Error::ShaderReflection(
+                    "Runtime-sized arrays cannot be represented in a Rust struct",
+                ))?;
+
+                let element_type = match element.as_ref() {
+                    ShaderType::Struct(element) => self.generate(element, *stride)?,
+                    ShaderType::Scalar(scalar) if *stride > scalar.size() => {
+                        format!("[{}; {}]", scalar_type(*scalar), stride / scalar.size())
+                    }
+                    ShaderType::Vector { scalar, components }
+                        if *stride > scalar.size() * components =>
+                    {
+                        format!("[{}; {}]", scalar_type(*scalar), stride / scalar.size())
+                    }
+                    element if *stride == 0 || *stride == element.size() => {
+                        self.rust_type(element)?
+                    }
+                    _ => {
+                        return Err(Error::ShaderReflection(
+                            "Array stride cannot be represented in a Rust struct",
+                        ));
+                    }
+                };
+
+                format!("[{element_type}; {count}]")
+            }
+            ShaderType::Struct(shader_struct) => self.generate(shader_struct, 0)?,
+        })
+    }
+}
+
+fn scalar_type(scalar: ShaderScalarType) -> &'static str {
+    match scalar {
+        ShaderScalarType::Bool => "u32",
+        ShaderScalarType::Int(16) => "i16",
+        ShaderScalarType::Int(64) => "i64",
+        ShaderScalarType::Int(_) => "i32",
+        ShaderScalarType::Uint(16) | ShaderScalarType::Float(16) => "u16",
+        ShaderScalarType::Uint(64) => "u64",
+        ShaderScalarType::Uint(_) => "u32",
+        ShaderScalarType::Float(64) => "f64",
+        ShaderScalarType::Float(_) => "f32",
+    }
+}
+
+fn type_alignment(ty: &ShaderType) -> u32 {
+    match ty {
+        ShaderType::Scalar(scalar)
+        | ShaderType::Vector { scalar, .. }
+        | ShaderType::Matrix { scalar, .. } => scalar.size(),
+        ShaderType::Array { element, .. } => type_alignment(element),
+        ShaderType::Struct(shader_struct) => shader_struct
+            .members
+            .iter()
+            .map(|member| type_alignment(&member.ty))
+            .max()
+            .unwrap_or(1),
+    }
+}
+
+fn struct_name(name: &str) -> String {
+    // DXC names wrapper types like `ConstantBuffer.Foo` after the element type.
+    let name = name.rsplit('.').next().unwrap_or(name);
+
+    let mut struct_name = String::new();
+    let mut upper = true;
+
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if upper {
+                struct_name.push(c.to_ascii_uppercase());
+            } else {
+                struct_name.push(c);
+            }
+            upper = false;
+        } else {
+            upper = true;
+        }
+    }
+
+    if struct_name.is_empty() || struct_name.starts_with(|c: char| c.is_ascii_digit()) {
+        struct_name.insert_str(0, "Struct");
+    }
+
+    struct_name
+}
+
+fn field_name(name: &str) -> String {
+    let mut field_name = String::new();
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !field_name.ends_with('_') {
+                field_name.push('_');
+            }
+            field_name.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            field_name.push(c);
+        } else if !field_name.ends_with('_') {
+            field_name.push('_');
+        }
+    }
+
+    match field_name.as_str() {
+        "" | "_" => "field".to_owned(),
+        // These cannot be raw identifiers.
+        "self" | "super" | "crate" => format!("{field_name}_"),
+        "as" | "async" | "await" | "box" | "break" | "const" | "continue" | "dyn" | "else"
+        | "enum" | "extern" | "false" | "fn" | "for" | "gen" | "if" | "impl" | "in" | "let"
+        | "loop" | "match" | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "static"
+        | "struct" | "trait" | "true" | "try" | "type" | "unsafe" | "use" | "where" | "while"
+        | "yield" => format!("r#{field_name}"),
+        _ if field_name.starts_with(|c: char| c.is_ascii_digit()) => format!("_{field_name}"),
+        _ => field_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const F32: ShaderScalarType = ShaderScalarType::Float(32);
+
+    fn member(name: &str, offset: u32, ty: ShaderType) -> ShaderStructMember {
+        ShaderStructMember {
+            name: name.to_owned(),
+            offset,
+            size: ty.size(),
+            ty,
+        }
+    }
+
+    fn shader_struct(name: &str, size: u32, members: Vec<ShaderStructMember>) -> ShaderStruct {
+        ShaderStruct {
+            name: name.to_owned(),
+            size,
+            members,
+        }
+    }
+
+    fn generate(shader_struct: &ShaderStruct) -> String {
+        let mut generator = StructGenerator::default();
+        generator.generate(shader_struct, 0).unwrap();
+        generator.output
+    }
+
+    #[test]
+    fn inserts_member_and_tail_padding() {
+        let params = shader_struct(
+            "type.ConstantBuffer.Params",
+            32,
+            vec![
+                member("Scale", 0, ShaderType::Scalar(F32)),
+                member(
+                    "Offset",
+                    16,
+                    ShaderType::Vector {
+                        scalar: F32,
+                        components: 3,
+                    },
+                ),
+            ],
+        );
+
+        assert_eq!(
+            generate(&params),
+            r"#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Params {
+    pub scale: f32,
+    pub _pad1: [u8; 12],
+    pub offset: [f32; 3],
+    pub _pad2: [u8; 4],
+}
+
+const _: () = assert!(std::mem::size_of::<Params>() == 32);
+
+"
+        );
+    }
+
+    #[test]
+    fn flattens_matrices_with_padded_columns() {
+        let transform = shader_struct(
+            "Transform",
+            112,
+            vec![
+                member(
+                    "Model",
+                    0,
+                    ShaderType::Matrix {
+                        scalar: F32,
+                        columns: 4,
+                        rows: 4,
+                        stride: 16,
+                        row_major: false,
+                    },
+                ),
+                member(
+                    "Normal",
+                    64,
+                    ShaderType::Matrix {
+                        scalar: F32,
+                        columns: 3,
+                        rows: 3,
+                        stride: 16,
+                        row_major: false,
+                    },
+                ),
+            ],
+        );
+
+        assert_eq!(
+            generate(&transform),
+            r"#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Transform {
+    pub model: [[f32; 4]; 4],
+    pub normal: [f32; 11],
+    pub _pad2: [u8; 4],
+}
+
+const _: () = assert!(std::mem::size_of::<Transform>() == 112);
+
+"
+        );
+    }
+
+    #[test]
+    fn pads_array_elements_to_the_stride() {
+        let light = shader_struct(
+            "Light",
+            20,
+            vec![
+                member(
+                    "Position",
+                    0,
+                    ShaderType::Vector {
+                        scalar: F32,
+                        components: 3,
+                    },
+                ),
+                member("Intensity", 12, ShaderType::Scalar(F32)),
+                member("Range", 16, ShaderType::Scalar(F32)),
+            ],
+        );
+        let scene = shader_struct(
+            "Scene",
+            192,
+            vec![
+                member(
+                    "Lights",
+                    0,
+                    ShaderType::Array {
+                        element: Box::new(ShaderType::Struct(light)),
+                        count: Some(4),
+                        stride: 32,
+                    },
+                ),
+                member(
+                    "Weights",
+                    128,
+                    ShaderType::Array {
+                        element: Box::new(ShaderType::Scalar(F32)),
+                        count: Some(4),
+                        stride: 16,
+                    },
+                ),
+            ],
+        );
+
+        assert_eq!(
+            generate(&scene),
+            r"#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Light {
+    pub position: [f32; 3],
+    pub intensity: f32,
+    pub range: f32,
+    pub _pad3: [u8; 12],
+}
+
+const _: () = assert!(std::mem::size_of::<Light>() == 32);
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Scene {
+    pub lights: [Light; 4],
+    pub weights: [[f32; 4]; 4],
+}
+
+const _: () = assert!(std::mem::size_of::<Scene>() == 192);
+
+"
+        );
+    }
+
+    #[test]
+    fn generates_identical_structs_once() {
+        let params = shader_struct(
+            "Params",
+            4,
+            vec![member("Scale", 0, ShaderType::Scalar(F32))],
+        );
+
+        let mut generator = StructGenerator::default();
+        generator.generate(&params, 0).unwrap();
+        generator.generate(&params, 0).unwrap();
+
+        assert_eq!(generator.output, generate(&params));
+    }
+
+    #[test]
+    fn rejects_structs_with_conflicting_members() {
+        let scale = shader_struct(
+            "Params",
+            4,
+            vec![member("Scale", 0, ShaderType::Scalar(F32))],
+        );
+        let bias = shader_struct(
+            "Params",
+            4,
+            vec![member("Bias", 0, ShaderType::Scalar(F32))],
+        );
+
+        let mut generator = StructGenerator::default();
+        generator.generate(&scale, 0).unwrap();
+
+        assert!(matches!(
+            generator.generate(&bias, 0),
+            Err(Error::ShaderReflection(_))
+        ));
+    }
+
+    #[test]
+    fn escapes_keyword_field_names() {
+        assert_eq!(field_name("baseColor"), "base_color");
+        assert_eq!(field_name("type"), "r#type");
+        assert_eq!(field_name("await"), "r#await");
+        assert_eq!(field_name("self"), "self_");
+        assert_eq!(field_name("Crate"), "crate_");
+        assert_eq!(field_name("0Offset"), "_0_offset");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Error, ShaderDesc, ShaderKind, ShaderSource, util};
+use std::borrow::Cow;
+use std::ops::{BitOr, BitOrAssign};
+use std::path::Path;
+
+#[macro_export]
+macro_rules! include_shader {
+    ($name:literal) => {
+        $crate::ShaderSource::Embedded {
+            spirv: ::std::borrow::Cow::Borrowed(include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/",
+                $name,
+                ".spv"
+            ))),
+            dxil: ::std::borrow::Cow::Borrowed(include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/",
+                $name,
+                ".dxil"
+            ))),
+        }
+    };
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderTargets(u32);
+
+impl ShaderTargets {
+    pub const SPIRV: Self = Self(1 << 0);
+    pub const DXIL: Self = Self(1 << 1);
+    pub const ALL: Self = Self(0b11);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl BitOr for ShaderTargets {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ShaderTargets {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+pub struct EmbeddedShaderDesc<'a> {
+    pub name: Cow<'static, str>,
+    pub source_path: &'a Path,
+    pub defines: Vec<(Cow<'static, str>, Option<Cow<'static, str>>)>,
+    pub kind: ShaderKind,
+    pub entry_point: Cow<'static, str>,
+    pub targets: ShaderTargets,
+}
+
+pub fn embed_shader(desc: &EmbeddedShaderDesc, out_dir: impl AsRef<Path>) -> Result<(), Error> {
+    let out_dir = out_dir.as_ref();
+
+    println!("cargo:rerun-if-changed={}", desc.source_path.display());
+
+    let shader_desc = ShaderDesc {
+        name: desc.name.clone(),
+        source: ShaderSource::Hlsl {
+            source: Cow::Owned(std::fs::read_to_string(desc.source_path)?),
+            defines: desc.defines.clone(),
+        },
+        kind: desc.kind,
+        entry_point: desc.entry_point.clone(),
+        reflect: false,
+    };
+
+    for (target, spirv, extension) in [
+        (ShaderTargets::SPIRV, true, "spv"),
+        (ShaderTargets::DXIL, false, "dxil"),
+    ] {
+        // Targets that were not requested are left empty so include_shader! still resolves.
+        let code = if desc.targets.contains(target) {
+            util::compile_hlsl(&shader_desc, spirv)?
+        } else {
+            Vec::new()
+        };
+
+        std::fs::write(out_dir.join(format!("{}.{extension}", desc.name)), code)?;
+    }
+
+    Ok(())
+}
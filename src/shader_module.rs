@@ -1,4 +1,6 @@
+use crate::ShaderReflection;
 use std::borrow::Cow;
+use std::ops::{BitOr, BitOrAssign};
 
 #[cfg(feature = "vulkan")]
 use crate::backend::vulkan::*;
@@ -14,11 +16,53 @@ pub enum ShaderModule {
     Metal(MetalShaderModule),
 }
 
+impl ShaderModule {
+    pub fn kind(&self) -> ShaderKind {
+        match self {
+            #[cfg(feature = "vulkan")]
+            ShaderModule::Vulkan(vulkan_shader_module) => vulkan_shader_module.kind(),
+
+            #[cfg(feature = "metal")]
+            ShaderModule::Metal(metal_shader_module) => metal_shader_module.kind(),
+        }
+    }
+
+    /// Empty on Metal for shaders without SPIR-V, see `ShaderDesc::reflect`.
+    pub fn reflection(&self) -> &ShaderReflection {
+        match self {
+            #[cfg(feature = "vulkan")]
+            ShaderModule::Vulkan(vulkan_shader_module) => vulkan_shader_module.reflection(),
+
+            #[cfg(feature = "metal")]
+            ShaderModule::Metal(metal_shader_module) => metal_shader_module.reflection(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_shader_module(&self) -> &VulkanShaderModule {
+        match self {
+            ShaderModule::Vulkan(vulkan_shader_module) => vulkan_shader_module,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_shader_module(&self) -> &MetalShaderModule {
+        match self {
+            ShaderModule::Metal(metal_shader_module) => metal_shader_module,
+            _ => unreachable!(),
+        }
+    }
+}
+
 pub struct ShaderDesc {
     pub name: Cow<'static, str>,
     pub source: ShaderSource,
     pub kind: ShaderKind,
     pub entry_point: Cow<'static, str>,
+    /// Reflects HLSL sources on Metal, which takes a second compile to SPIR-V. Embedded SPIR-V
+    /// is always reflected.
+    pub reflect: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -30,10 +74,66 @@ pub enum ShaderKind {
     Compute,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderStages(u32);
+
+impl ShaderStages {
+    pub const VERTEX: Self = Self(1 << 0);
+    pub const TASK: Self = Self(1 << 1);
+    pub const MESH: Self = Self(1 << 2);
+    pub const FRAGMENT: Self = Self(1 << 3);
+    pub const COMPUTE: Self = Self(1 << 4);
+    pub const ALL: Self = Self(0b11111);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    pub const fn intersects(&self, other: Self) -> bool {
+        (self.0 & other.0) != 0
+    }
+}
+
+impl From<ShaderKind> for ShaderStages {
+    fn from(kind: ShaderKind) -> Self {
+        match kind {
+            ShaderKind::Vertex => ShaderStages::VERTEX,
+            ShaderKind::Task => ShaderStages::TASK,
+            ShaderKind::Mesh => ShaderStages::MESH,
+            ShaderKind::Fragment => ShaderStages::FRAGMENT,
+            ShaderKind::Compute => ShaderStages::COMPUTE,
+        }
+    }
+}
+
+impl BitOr for ShaderStages {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ShaderStages {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
 #[derive(Clone)]
 pub enum ShaderSource {
     Hlsl {
         source: Cow<'static, str>,
         defines: Vec<(Cow<'static, str>, Option<Cow<'static, str>>)>,
     },
+    Embedded {
+        spirv: Cow<'static, [u8]>,
+        dxil: Cow<'static, [u8]>,
+    },
+    SpirV(Cow<'static, [u32]>),
+    Dxil(Cow<'static, [u8]>),
 }
@@ -0,0 +1,1086 @@
+use crate::{DescriptorType, Error, ShaderKind};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderReflection {
+    pub kind: ShaderKind,
+    pub entry_point: String,
+    pub bindings: Vec<ShaderResourceBinding>,
+    pub unsupported_bindings: Vec<UnsupportedShaderBinding>,
+    pub push_constants: Option<ShaderStruct>,
+    pub thread_group_size: Option<[u32; 3]>,
+    pub mesh_output: Option<MeshOutput>,
+    pub inputs: Vec<ShaderVariable>,
+    pub outputs: Vec<ShaderVariable>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderResourceBinding {
+    pub name: String,
+    pub descriptor_type: DescriptorType,
+    pub space: u32,
+    pub binding: u32,
+    pub count: Option<u32>,
+    pub block: Option<ShaderStruct>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedShaderBinding {
+    pub name: String,
+    pub space: u32,
+    pub binding: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderStruct {
+    pub name: String,
+    pub size: u32,
+    pub members: Vec<ShaderStructMember>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderStructMember {
+    pub name: String,
+    pub offset: u32,
+    pub size: u32,
+    pub ty: ShaderType,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShaderType {
+    Scalar(ShaderScalarType),
+    Vector {
+        scalar: ShaderScalarType,
+        components: u32,
+    },
+    Matrix {
+        scalar: ShaderScalarType,
+        columns: u32,
+        rows: u32,
+        stride: u32,
+        row_major: bool,
+    },
+    Array {
+        element: Box<ShaderType>,
+        count: Option<u32>,
+        stride: u32,
+    },
+    Struct(ShaderStruct),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderScalarType {
+    Bool,
+    Int(u32),
+    Uint(u32),
+    Float(u32),
+}
+
+impl ShaderScalarType {
+    pub fn size(&self) -> u32 {
+        match self {
+            ShaderScalarType::Bool => 4,
+            ShaderScalarType::Int(width)
+            | ShaderScalarType::Uint(width)
+            | ShaderScalarType::Float(width) => width / 8,
+        }
+    }
+}
+
+impl ShaderType {
+    pub fn size(&self) -> u32 {
+        match self {
+            ShaderType::Scalar(scalar) => scalar.size(),
+            ShaderType::Vector { scalar, components } => scalar.size() * components,
+            ShaderType::Matrix {
+                scalar,
+                columns,
+                rows,
+                stride,
+                row_major,
+            } => {
+                let (vectors, components) = if *row_major {
+                    (*rows, *columns)
+                } else {
+                    (*columns, *rows)
+                };
+
+                if *stride == 0 {
+                    vectors * components * scalar.size()
+                } else {
+                    (vectors - 1) * stride + components * scalar.size()
+                }
+            }
+            ShaderType::Array {
+                element,
+                count,
+                stride,
+            } => {
+                let stride = if *stride == 0 {
+                    element.size()
+                } else {
+                    *stride
+                };
+
+                count.unwrap_or(0) * stride
+            }
+            ShaderType::Struct(shader_struct) => shader_struct.size,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MeshOutput {
+    pub max_vertices: u32,
+    pub max_primitives: u32,
+    pub topology: MeshOutputTopology,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MeshOutputTopology {
+    Points,
+    Lines,
+    Triangles,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderVariable {
+    pub name: String,
+    pub location: u32,
+    pub per_primitive: bool,
+    pub ty: ShaderType,
+}
+
+const MAGIC: u32 = 0x07230203;
+const VERSION_1_4: u32 = 0x0001_0400;
+
+const OP_NAME: u32 = 5;
+const OP_MEMBER_NAME: u32 = 6;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_EXECUTION_MODE: u32 = 16;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_FUNCTION: u32 = 54;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+const OP_EXECUTION_MODE_ID: u32 = 331;
+
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ROW_MAJOR: u32 = 4;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_NON_WRITABLE: u32 = 24;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+const DECORATION_PER_PRIMITIVE: u32 = 5271;
+
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_OUTPUT: u32 = 3;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+const EXECUTION_MODE_OUTPUT_VERTICES: u32 = 26;
+const EXECUTION_MODE_OUTPUT_POINTS: u32 = 27;
+const EXECUTION_MODE_LOCAL_SIZE_ID: u32 = 38;
+const EXECUTION_MODE_OUTPUT_LINES: u32 = 5269;
+const EXECUTION_MODE_OUTPUT_PRIMITIVES: u32 = 5270;
+const EXECUTION_MODE_OUTPUT_TRIANGLES: u32 = 5298;
+
+const IMAGE_DIM_BUFFER: u32 = 5;
+
+#[derive(Default)]
+struct Decorations {
+    set: Option<u32>,
+    binding: Option<u32>,
+    location: Option<u32>,
+    offset: Option<u32>,
+    array_stride: Option<u32>,
+    matrix_stride: Option<u32>,
+    buffer_block: bool,
+    row_major: bool,
+    non_writable: bool,
+    built_in: bool,
+    per_primitive: bool,
+}
+
+impl Decorations {
+    fn apply(&mut self, decoration: u32, operand: Option<u32>) {
+        match decoration {
+            DECORATION_BUFFER_BLOCK => self.buffer_block = true,
+            DECORATION_ROW_MAJOR => self.row_major = true,
+            DECORATION_ARRAY_STRIDE => self.array_stride = operand,
+            DECORATION_MATRIX_STRIDE => self.matrix_stride = operand,
+            DECORATION_BUILT_IN => self.built_in = true,
+            DECORATION_NON_WRITABLE => self.non_writable = true,
+            DECORATION_LOCATION => self.location = operand,
+            DECORATION_BINDING => self.binding = operand,
+            DECORATION_DESCRIPTOR_SET => self.set = operand,
+            DECORATION_OFFSET => self.offset = operand,
+            DECORATION_PER_PRIMITIVE => self.per_primitive = true,
+            _ => {}
+        }
+    }
+}
+
+enum SpirvType {
+    Bool,
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Image { dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage,
+    Array { element: u32, length: u32 },
+    RuntimeArray { element: u32 },
+    Struct { members: Vec<u32> },
+    Pointer { pointee: u32 },
+}
+
+#[derive(Default)]
+struct SpirvModule {
+    version: u32,
+    names: HashMap<u32, String>,
+    member_names: HashMap<(u32, u32), String>,
+    decorations: HashMap<u32, Decorations>,
+    member_decorations: HashMap<(u32, u32), Decorations>,
+    types: HashMap<u32, SpirvType>,
+    constants: HashMap<u32, u32>,
+    variables: Vec<(u32, u32, u32)>,
+    entry_points: Vec<(u32, String, Vec<u32>)>,
+    execution_modes: Vec<(u32, u32, Vec<u32>)>,
+}
+
+pub(crate) fn reflect_spirv(
+    spirv: &[u32],
+    kind: ShaderKind,
+    entry_point: &str,
+) -> Result<ShaderReflection, Error> {
+    let module = SpirvModule::parse(spirv)?;
+
+    let (entry_point_id, interface) = module
+        .entry_points
+        .iter()
+        .find(|(_, name, _)| name == entry_point)
+        .map(|(id, _, interface)| (*id, interface))
+        .ok_or(Error::ShaderReflection(
+            "SPIR-V module does not contain the requested entry point",
+        ))?;
+
+    // Entry points list the Input and Output variables they use, and since SPIR-V 1.4 every
+    // global variable they use.
+    let in_interface = |variable_id: u32, storage_class: u32| {
+        interface.contains(&variable_id)
+            || (module.version < VERSION_1_4
+                && !matches!(storage_class, STORAGE_CLASS_INPUT | STORAGE_CLASS_OUTPUT))
+    };
+
+    let mut reflection = ShaderReflection {
+        kind,
+        entry_point: entry_point.to_owned(),
+        bindings: Vec::new(),
+        unsupported_bindings: Vec::new(),
+        push_constants: None,
+        thread_group_size: None,
+        mesh_output: None,
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+    };
+
+    let mut max_vertices = None;
+    let mut max_primitives = None;
+    let mut topology = None;
+
+    for (_, mode, operands) in module
+        .execution_modes
+        .iter()
+        .filter(|(id, _, _)| *id == entry_point_id)
+    {
+        match *mode {
+            EXECUTION_MODE_LOCAL_SIZE if operands.len() >= 3 => {
+                reflection.thread_group_size = Some([operands[0], operands[1], operands[2]]);
+            }
+            EXECUTION_MODE_LOCAL_SIZE_ID if operands.len() >= 3 => {
+                reflection.thread_group_size = Some([
+                    module.constant(operands[0])?,
+                    module.constant(operands[1])?,
+                    module.constant(operands[2])?,
+                ]);
+            }
+            EXECUTION_MODE_OUTPUT_VERTICES => max_vertices = operands.first().copied(),
+            EXECUTION_MODE_OUTPUT_PRIMITIVES => max_primitives = operands.first().copied(),
+            EXECUTION_MODE_OUTPUT_POINTS => topology = Some(MeshOutputTopology::Points),
+            EXECUTION_MODE_OUTPUT_LINES => topology = Some(MeshOutputTopology::Lines),
+            EXECUTION_MODE_OUTPUT_TRIANGLES => topology = Some(MeshOutputTopology::Triangles),
+            _ => {}
+        }
+    }
+
+    if kind == ShaderKind::Mesh {
+        reflection.mesh_output = Some(MeshOutput {
+            max_vertices: max_vertices.unwrap_or(0),
+            max_primitives: max_primitives.unwrap_or(0),
+            topology: topology.unwrap_or(MeshOutputTopology::Triangles),
+        });
+    }
+
+    for (variable_id, pointer_type_id, storage_class) in &module.variables {
+        if !in_interface(*variable_id, *storage_class) {
+            continue;
+        }
+
+        let Some(SpirvType::Pointer { pointee, .. }) = module.types.get(pointer_type_id) else {
+            return Err(Error::ShaderReflection(
+                "SPIR-V variable does not have a pointer type",
+            ));
+        };
+
+        let decorations = module.decorations.get(variable_id);
+        let name = module.names.get(variable_id).cloned().unwrap_or_default();
+
+        match *storage_class {
+            STORAGE_CLASS_INPUT | STORAGE_CLASS_OUTPUT => {
+                let Some(decorations) = decorations.filter(|decorations| !decorations.built_in)
+                else {
+                    continue;
+                };
+
+                let Some(location) = decorations.location else {
+                    continue;
+                };
+
+                let mut ty = module.shader_type(*pointee, None)?;
+
+                // Mesh shader outputs are arrays sized by the output limits.
+                if kind == ShaderKind::Mesh && *storage_class == STORAGE_CLASS_OUTPUT {
+                    if let ShaderType::Array { element, .. } = ty {
+                        ty = *element;
+                    }
+                }
+
+                let (prefix, variables) = if *storage_class == STORAGE_CLASS_INPUT {
+                    ("in.var.", &mut reflection.inputs)
+                } else {
+                    ("out.var.", &mut reflection.outputs)
+                };
+
+                variables.push(ShaderVariable {
+                    name: name.strip_prefix(prefix).unwrap_or(&name).to_owned(),
+                    location,
+                    per_primitive: decorations.per_primitive,
+                    ty,
+                });
+            }
+            STORAGE_CLASS_PUSH_CONSTANT => {
+                reflection.push_constants = Some(module.shader_struct(*pointee)?);
+            }
+            STORAGE_CLASS_UNIFORM_CONSTANT
+            | STORAGE_CLASS_UNIFORM
+            | STORAGE_CLASS_STORAGE_BUFFER => {
+                let Some(decorations) = decorations else {
+                    continue;
+                };
+
+                let (Some(space), Some(binding)) = (decorations.set, decorations.binding) else {
+                    continue;
+                };
+
+                let (resource_type_id, count) = match module.types.get(pointee) {
+                    Some(SpirvType::Array { element, length }) => {
+                        (*element, Some(module.constant(*length)?))
+                    }
+                    Some(SpirvType::RuntimeArray { element }) => (*element, None),
+                    _ => (*pointee, Some(1)),
+                };
+
+                let Some((descriptor_type, block)) =
+                    module.descriptor_type(resource_type_id, *storage_class)?
+                else {
+                    reflection
+                        .unsupported_bindings
+                        .push(UnsupportedShaderBinding {
+                            name,
+                            space,
+                            binding,
+                        });
+                    continue;
+                };
+
+                reflection.bindings.push(ShaderResourceBinding {
+                    name,
+                    descriptor_type,
+                    space,
+                    binding,
+                    count,
+                    block,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    reflection
+        .bindings
+        .sort_by_key(|binding| (binding.space, binding.binding));
+    reflection
+        .unsupported_bindings
+        .sort_by_key(|binding| (binding.space, binding.binding));
+    reflection.inputs.sort_by_key(|variable| variable.location);
+    reflection.outputs.sort_by_key(|variable| variable.location);
+
+    Ok(reflection)
+}
+
+impl SpirvModule {
+    fn parse(spirv: &[u32]) -> Result<Self, Error> {
+        if spirv.len() < 5 || spirv[0] != MAGIC {
+            return Err(Error::ShaderReflection("Invalid SPIR-V header"));
+        }
+
+        let mut module = SpirvModule {
+            version: spirv[1],
+            ..Default::default()
+        };
+        let mut words = &spirv[5..];
+
+        while !words.is_empty() {
+            let word_count = (words[0] >> 16) as usize;
+            let opcode = words[0] & 0xffff;
+
+            if word_count == 0 || word_count > words.len() {
+                return Err(Error::ShaderReflection("Truncated SPIR-V instruction"));
+            }
+
+            let operands = &words[1..word_count];
+            words = &words[word_count..];
+
+            match opcode {
+                OP_NAME if !operands.is_empty() => {
+                    module
+                        .names
+                        .insert(operands[0], literal_string(&operands[1..]));
+                }
+                OP_MEMBER_NAME if operands.len() >= 2 => {
+                    module
+                        .member_names
+                        .insert((operands[0], operands[1]), literal_string(&operands[2..]));
+                }
+                OP_ENTRY_POINT if operands.len() >= 2 => {
+                    let name_words = literal_string_words(&operands[2..]);
+                    module.entry_points.push((
+                        operands[1],
+                        literal_string(&operands[2..]),
+                        operands[2 + name_words..].to_vec(),
+                    ));
+                }
+                OP_EXECUTION_MODE | OP_EXECUTION_MODE_ID if operands.len() >= 2 => {
+                    module
+                        .execution_modes
+                        .push((operands[0], operands[1], operands[2..].to_vec()));
+                }
+                OP_DECORATE if operands.len() >= 2 => {
+                    module
+                        .decorations
+                        .entry(operands[0])
+                        .or_default()
+                        .apply(operands[1], operands.get(2).copied());
+                }
+                OP_MEMBER_DECORATE if operands.len() >= 3 => {
+                    module
+                        .member_decorations
+                        .entry((operands[0], operands[1]))
+                        .or_default()
+                        .apply(operands[2], operands.get(3).copied());
+                }
+                OP_TYPE_BOOL if !operands.is_empty() => {
+                    module.types.insert(operands[0], SpirvType::Bool);
+                }
+                OP_TYPE_INT if operands.len() >= 3 => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::Int {
+                            width: operands[1],
+                            signed: operands[2] != 0,
+                        },
+                    );
+                }
+                OP_TYPE_FLOAT if operands.len() >= 2 => {
+                    module
+                        .types
+                        .insert(operands[0], SpirvType::Float { width: operands[1] });
+                }
+                OP_TYPE_VECTOR if operands.len() >= 3 => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::Vector {
+                            component: operands[1],
+                            count: operands[2],
+                        },
+                    );
+                }
+                OP_TYPE_MATRIX if operands.len() >= 3 => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::Matrix {
+                            column: operands[1],
+                            count: operands[2],
+                        },
+                    );
+                }
+                OP_TYPE_IMAGE if operands.len() >= 7 => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::Image {
+                            dim: operands[2],
+                            sampled: operands[6],
+                        },
+                    );
+                }
+                OP_TYPE_SAMPLER if !operands.is_empty() => {
+                    module.types.insert(operands[0], SpirvType::Sampler);
+                }
+                OP_TYPE_SAMPLED_IMAGE if !operands.is_empty() => {
+                    module.types.insert(operands[0], SpirvType::SampledImage);
+                }
+                OP_TYPE_ARRAY if operands.len() >= 3 => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::Array {
+                            element: operands[1],
+                            length: operands[2],
+                        },
+                    );
+                }
+                OP_TYPE_RUNTIME_ARRAY if operands.len() >= 2 => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::RuntimeArray {
+                            element: operands[1],
+                        },
+                    );
+                }
+                OP_TYPE_STRUCT if !operands.is_empty() => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::Struct {
+                            members: operands[1..].to_vec(),
+                        },
+                    );
+                }
+                OP_TYPE_POINTER if operands.len() >= 3 => {
+                    module.types.insert(
+                        operands[0],
+                        SpirvType::Pointer {
+                            pointee: operands[2],
+                        },
+                    );
+                }
+                OP_CONSTANT | OP_SPEC_CONSTANT if operands.len() >= 3 => {
+                    module.constants.insert(operands[1], operands[2]);
+                }
+                OP_VARIABLE if operands.len() >= 3 => {
+                    module
+                        .variables
+                        .push((operands[1], operands[0], operands[2]));
+                }
+                // Global declarations always precede the first function.
+                OP_FUNCTION => break,
+                _ => {}
+            }
+        }
+
+        Ok(module)
+    }
+
+    fn constant(&self, id: u32) -> Result<u32, Error> {
+        self.constants
+            .get(&id)
+            .copied()
+            .ok_or(Error::ShaderReflection(
+                "SPIR-V array length is not a constant",
+            ))
+    }
+
+    fn descriptor_type(
+        &self,
+        type_id: u32,
+        storage_class: u32,
+    ) -> Result<Option<(DescriptorType, Option<ShaderStruct>)>, Error> {
+        match self.types.get(&type_id) {
+            Some(SpirvType::Struct { members }) => {
+                let decorations = self.decorations.get(&type_id);
+                let buffer_block = decorations.is_some_and(|decorations| decorations.buffer_block);
+
+                let descriptor_type =
+                    if storage_class == STORAGE_CLASS_STORAGE_BUFFER || buffer_block {
+                        let read_only = (0..members.len() as u32).all(|member| {
+                            self.member_decorations
+                                .get(&(type_id, member))
+                                .is_some_and(|decorations| decorations.non_writable)
+                        });
+
+                        if read_only {
+                            DescriptorType::ReadOnlyStorageBuffer
+                        } else {
+                            DescriptorType::StorageBuffer
+                        }
+                    } else {
+                        DescriptorType::UniformBuffer
+                    };
+
+                Ok(Some((descriptor_type, Some(self.shader_struct(type_id)?))))
+            }
+            Some(SpirvType::Image {
+                dim: IMAGE_DIM_BUFFER,
+                sampled: 2,
+            }) => Ok(Some((DescriptorType::StorageTexelBuffer, None))),
+            Some(SpirvType::Image {
+                dim: IMAGE_DIM_BUFFER,
+                ..
+            }) => Ok(Some((DescriptorType::UniformTexelBuffer, None))),
+            Some(SpirvType::Image { sampled: 2, .. }) => {
+                Ok(Some((DescriptorType::StorageTexture, None)))
+            }
+            Some(SpirvType::Image { .. }) => Ok(Some((DescriptorType::SampledTexture, None))),
+            Some(SpirvType::Sampler) => Ok(Some((DescriptorType::Sampler, None))),
+            // Combined image samplers and anything else without a descriptor type equivalent.
+            _ => Ok(None),
+        }
+    }
+
+    fn shader_struct(&self, type_id: u32) -> Result<ShaderStruct, Error> {
+        let Some(SpirvType::Struct { members }) = self.types.get(&type_id) else {
+            return Err(Error::ShaderReflection("Expected a SPIR-V struct type"));
+        };
+
+        let name = self.names.get(&type_id).cloned().unwrap_or_default();
+
+        let members = members
+            .iter()
+            .enumerate()
+            .map(|(index, member_type_id)| {
+                let member = (type_id, index as u32);
+                let decorations = self.member_decorations.get(&member);
+                let ty = self.shader_type(*member_type_id, decorations)?;
+
+                Ok(ShaderStructMember {
+                    name: self.member_names.get(&member).cloned().unwrap_or_default(),
+                    offset: decorations
+                        .and_then(|decorations| decorations.offset)
+                        .unwrap_or(0),
+                    size: ty.size(),
+                    ty,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let size = members
+            .iter()
+            .map(|member| member.offset + member.size)
+            .max()
+            .unwrap_or(0);
+
+        Ok(ShaderStruct {
+            name: name.strip_prefix("type.").unwrap_or(&name).to_owned(),
+            size,
+            members,
+        })
+    }
+
+    fn shader_type(
+        &self,
+        type_id: u32,
+        member_decorations: Option<&Decorations>,
+    ) -> Result<ShaderType, Error> {
+        match self.types.get(&type_id) {
+            Some(SpirvType::Bool | SpirvType::Int { .. } | SpirvType::Float { .. }) => {
+                Ok(ShaderType::Scalar(self.scalar_type(type_id)?))
+            }
+            Some(SpirvType::Vector { component, count }) => Ok(ShaderType::Vector {
+                scalar: self.scalar_type(*component)?,
+                components: *count,
+            }),
+            Some(SpirvType::Matrix { column, count }) => {
+                let Some(SpirvType::Vector {
+                    component,
+                    count: rows,
+                }) = self.types.get(column)
+                else {
+                    return Err(Error::ShaderReflection(
+                        "SPIR-V matrix column is not a vector",
+                    ));
+                };
+
+                Ok(ShaderType::Matrix {
+                    scalar: self.scalar_type(*component)?,
+                    columns: *count,
+                    rows: *rows,
+                    stride: member_decorations
+                        .and_then(|decorations| decorations.matrix_stride)
+                        .unwrap_or(0),
+                    row_major: member_decorations.is_some_and(|decorations| decorations.row_major),
+                })
+            }
+            Some(SpirvType::Array { element, length }) => Ok(ShaderType::Array {
+                element: Box::new(self.shader_type(*element, member_decorations)?),
+                count: Some(self.constant(*length)?),
+                stride: self.array_stride(type_id),
+            }),
+            Some(SpirvType::RuntimeArray { element }) => Ok(ShaderType::Array {
+                element: Box::new(self.shader_type(*element, member_decorations)?),
+                count: None,
+                stride: self.array_stride(type_id),
+            }),
+            Some(SpirvType::Struct { .. }) => Ok(ShaderType::Struct(self.shader_struct(type_id)?)),
+            _ => Err(Error::ShaderReflection("Unsupported SPIR-V data type")),
+        }
+    }
+
+    fn scalar_type(&self, type_id: u32) -> Result<ShaderScalarType, Error> {
+        match self.types.get(&type_id) {
+            Some(SpirvType::Bool) => Ok(ShaderScalarType::Bool),
+            Some(SpirvType::Int {
+                width,
+                signed: true,
+            }) => Ok(ShaderScalarType::Int(*width)),
+            Some(SpirvType::Int {
+                width,
+                signed: false,
+            }) => Ok(ShaderScalarType::Uint(*width)),
+            Some(SpirvType::Float { width }) => Ok(ShaderScalarType::Float(*width)),
+            _ => Err(Error::ShaderReflection("Expected a SPIR-V scalar type")),
+        }
+    }
+
+    fn array_stride(&self, type_id: u32) -> u32 {
+        self.decorations
+            .get(&type_id)
+            .and_then(|decorations| decorations.array_stride)
+            .unwrap_or(0)
+    }
+}
+
+fn literal_string(words: &[u32]) -> String {
+    let bytes = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take_while(|byte| *byte != 0)
+        .collect::<Vec<_>>();
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn literal_string_words(words: &[u32]) -> usize {
+    words
+        .iter()
+        .position(|word| word.to_le_bytes().contains(&0))
+        .map_or(words.len(), |index| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXECUTION_MODEL_FRAGMENT: u32 = 4;
+    const EXECUTION_MODEL_GL_COMPUTE: u32 = 5;
+    const DECORATION_BLOCK: u32 = 2;
+    const DIM_2D: u32 = 1;
+
+    #[derive(Default)]
+    struct Assembler {
+        words: Vec<u32>,
+    }
+
+    impl Assembler {
+        fn op(&mut self, opcode: u32, operands: &[u32]) -> &mut Self {
+            self.words
+                .push(((operands.len() as u32 + 1) << 16) | opcode);
+            self.words.extend_from_slice(operands);
+            self
+        }
+
+        fn op_string(&mut self, opcode: u32, operands: &[u32], string: &str) -> &mut Self {
+            self.op_string_with(opcode, operands, string, &[])
+        }
+
+        fn op_string_with(
+            &mut self,
+            opcode: u32,
+            operands: &[u32],
+            string: &str,
+            trailing: &[u32],
+        ) -> &mut Self {
+            let mut bytes = string.as_bytes().to_vec();
+            bytes.resize((bytes.len() / 4 + 1) * 4, 0);
+
+            let operands = operands
+                .iter()
+                .copied()
+                .chain(
+                    bytes
+                        .chunks(4)
+                        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())),
+                )
+                .chain(trailing.iter().copied())
+                .collect::<Vec<_>>();
+
+            self.op(opcode, &operands)
+        }
+
+        fn finish(&self) -> Vec<u32> {
+            self.finish_with_version(0x0001_0300)
+        }
+
+        fn finish_with_version(&self, version: u32) -> Vec<u32> {
+            let mut spirv = vec![MAGIC, version, 0, 64, 0];
+            spirv.extend_from_slice(&self.words);
+            spirv
+        }
+    }
+
+    fn resource(
+        asm: &mut Assembler,
+        variable: u32,
+        pointer: u32,
+        pointee: u32,
+        storage_class: u32,
+        space: u32,
+        binding: u32,
+    ) {
+        asm.op(OP_TYPE_POINTER, &[pointer, storage_class, pointee])
+            .op(OP_VARIABLE, &[pointer, variable, storage_class])
+            .op(OP_DECORATE, &[variable, DECORATION_DESCRIPTOR_SET, space])
+            .op(OP_DECORATE, &[variable, DECORATION_BINDING, binding]);
+    }
+
+    fn compute_module() -> Vec<u32> {
+        let mut asm = Assembler::default();
+
+        asm.op_string(OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 1], "main")
+            .op(OP_EXECUTION_MODE, &[1, EXECUTION_MODE_LOCAL_SIZE, 8, 4, 1])
+            .op(OP_TYPE_FLOAT, &[2, 32])
+            .op(OP_TYPE_INT, &[3, 32, 0])
+            .op(OP_TYPE_VECTOR, &[4, 2, 4])
+            .op(OP_CONSTANT, &[3, 5, 4]);
+
+        // cbuffer Constants { float4 tint; } at space 0, binding 0.
+        asm.op_string(OP_NAME, &[10], "type.Constants")
+            .op_string(OP_MEMBER_NAME, &[10, 0], "tint")
+            .op(OP_TYPE_STRUCT, &[10, 4])
+            .op(OP_DECORATE, &[10, DECORATION_BLOCK])
+            .op(OP_MEMBER_DECORATE, &[10, 0, DECORATION_OFFSET, 0]);
+        resource(&mut asm, 12, 11, 10, STORAGE_CLASS_UNIFORM, 0, 0);
+
+        // StructuredBuffer<float> at space 0, binding 1.
+        asm.op(OP_TYPE_RUNTIME_ARRAY, &[13, 2])
+            .op(OP_DECORATE, &[13, DECORATION_ARRAY_STRIDE, 4])
+            .op(OP_TYPE_STRUCT, &[14, 13])
+            .op(OP_DECORATE, &[14, DECORATION_BLOCK])
+            .op(OP_MEMBER_DECORATE, &[14, 0, DECORATION_OFFSET, 0])
+            .op(OP_MEMBER_DECORATE, &[14, 0, DECORATION_NON_WRITABLE]);
+        resource(&mut asm, 16, 15, 14, STORAGE_CLASS_STORAGE_BUFFER, 0, 1);
+
+        // Texture2D textures[4] at space 1, binding 0.
+        asm.op(OP_TYPE_IMAGE, &[17, 2, DIM_2D, 0, 0, 0, 1, 0])
+            .op(OP_TYPE_ARRAY, &[18, 17, 5]);
+        resource(&mut asm, 20, 19, 18, STORAGE_CLASS_UNIFORM_CONSTANT, 1, 0);
+
+        // RWTexture2D<float4> images[] at space 1, binding 1.
+        asm.op(OP_TYPE_IMAGE, &[21, 2, DIM_2D, 0, 0, 0, 2, 1])
+            .op(OP_TYPE_RUNTIME_ARRAY, &[22, 21]);
+        resource(&mut asm, 24, 23, 22, STORAGE_CLASS_UNIFORM_CONSTANT, 1, 1);
+
+        // Buffer<float4> and RWBuffer<float4> at space 2.
+        asm.op(OP_TYPE_IMAGE, &[25, 2, IMAGE_DIM_BUFFER, 0, 0, 0, 1, 0]);
+        resource(&mut asm, 27, 26, 25, STORAGE_CLASS_UNIFORM_CONSTANT, 2, 0);
+        asm.op(OP_TYPE_IMAGE, &[28, 2, IMAGE_DIM_BUFFER, 0, 0, 0, 2, 1]);
+        resource(&mut asm, 30, 29, 28, STORAGE_CLASS_UNIFORM_CONSTANT, 2, 1);
+
+        // SamplerState at space 3, binding 0 and a combined image sampler at binding 1.
+        asm.op(OP_TYPE_SAMPLER, &[31]);
+        resource(&mut asm, 33, 32, 31, STORAGE_CLASS_UNIFORM_CONSTANT, 3, 0);
+        asm.op(OP_TYPE_SAMPLED_IMAGE, &[34, 17])
+            .op_string(OP_NAME, &[36], "combined");
+        resource(&mut asm, 36, 35, 34, STORAGE_CLASS_UNIFORM_CONSTANT, 3, 1);
+
+        // Push constants { uint index; float4 color; }.
+        asm.op_string(OP_NAME, &[37], "type.PushConstants")
+            .op_string(OP_MEMBER_NAME, &[37, 0], "index")
+            .op_string(OP_MEMBER_NAME, &[37, 1], "color")
+            .op(OP_TYPE_STRUCT, &[37, 3, 4])
+            .op(OP_DECORATE, &[37, DECORATION_BLOCK])
+            .op(OP_MEMBER_DECORATE, &[37, 0, DECORATION_OFFSET, 0])
+            .op(OP_MEMBER_DECORATE, &[37, 1, DECORATION_OFFSET, 16])
+            .op(OP_TYPE_POINTER, &[38, STORAGE_CLASS_PUSH_CONSTANT, 37])
+            .op(OP_VARIABLE, &[38, 39, STORAGE_CLASS_PUSH_CONSTANT]);
+
+        asm.finish()
+    }
+
+    #[test]
+    fn reflects_descriptor_types_and_arrays() {
+        let reflection = reflect_spirv(&compute_module(), ShaderKind::Compute, "main").unwrap();
+
+        let bindings = reflection
+            .bindings
+            .iter()
+            .map(|binding| {
+                (
+                    binding.space,
+                    binding.binding,
+                    binding.descriptor_type,
+                    binding.count,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            bindings,
+            [
+                (0, 0, DescriptorType::UniformBuffer, Some(1)),
+                (0, 1, DescriptorType::ReadOnlyStorageBuffer, Some(1)),
+                (1, 0, DescriptorType::SampledTexture, Some(4)),
+                (1, 1, DescriptorType::StorageTexture, None),
+                (2, 0, DescriptorType::UniformTexelBuffer, Some(1)),
+                (2, 1, DescriptorType::StorageTexelBuffer, Some(1)),
+                (3, 0, DescriptorType::Sampler, Some(1)),
+            ]
+        );
+
+        let constants = reflection.bindings[0].block.as_ref().unwrap();
+        assert_eq!(constants.name, "Constants");
+        assert_eq!(constants.size, 16);
+        assert_eq!(constants.members[0].name, "tint");
+
+        assert_eq!(reflection.thread_group_size, Some([8, 4, 1]));
+    }
+
+    #[test]
+    fn records_unsupported_bindings() {
+        let reflection = reflect_spirv(&compute_module(), ShaderKind::Compute, "main").unwrap();
+
+        assert_eq!(
+            reflection.unsupported_bindings,
+            [UnsupportedShaderBinding {
+                name: "combined".to_owned(),
+                space: 3,
+                binding: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn reflects_push_constants() {
+        let reflection = reflect_spirv(&compute_module(), ShaderKind::Compute, "main").unwrap();
+
+        assert_eq!(
+            reflection.push_constants,
+            Some(ShaderStruct {
+                name: "PushConstants".to_owned(),
+                size: 32,
+                members: vec![
+                    ShaderStructMember {
+                        name: "index".to_owned(),
+                        offset: 0,
+                        size: 4,
+                        ty: ShaderType::Scalar(ShaderScalarType::Uint(32)),
+                    },
+                    ShaderStructMember {
+                        name: "color".to_owned(),
+                        offset: 16,
+                        size: 16,
+                        ty: ShaderType::Vector {
+                            scalar: ShaderScalarType::Float(32),
+                            components: 4,
+                        },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_missing_entry_point() {
+        let result = reflect_spirv(&compute_module(), ShaderKind::Compute, "other");
+
+        assert!(matches!(result, Err(Error::ShaderReflection(_))));
+    }
+
+    fn two_entry_point_module(version: u32) -> Vec<u32> {
+        let mut asm = Assembler::default();
+
+        asm.op_string_with(
+            OP_ENTRY_POINT,
+            &[EXECUTION_MODEL_FRAGMENT, 1],
+            "first",
+            &[12, 16],
+        )
+        .op_string_with(
+            OP_ENTRY_POINT,
+            &[EXECUTION_MODEL_FRAGMENT, 2],
+            "second",
+            &[14],
+        )
+        .op(OP_TYPE_FLOAT, &[3, 32])
+        .op(OP_TYPE_VECTOR, &[4, 3, 4])
+        .op(OP_TYPE_SAMPLER, &[10]);
+
+        // One sampler per entry point and an input only used by the first one.
+        resource(&mut asm, 12, 11, 10, STORAGE_CLASS_UNIFORM_CONSTANT, 0, 0);
+        resource(&mut asm, 14, 13, 10, STORAGE_CLASS_UNIFORM_CONSTANT, 0, 1);
+        asm.op(OP_TYPE_POINTER, &[15, STORAGE_CLASS_INPUT, 4])
+            .op(OP_VARIABLE, &[15, 16, STORAGE_CLASS_INPUT])
+            .op(OP_DECORATE, &[16, DECORATION_LOCATION, 0]);
+
+        asm.finish_with_version(version)
+    }
+
+    fn binding_slots(reflection: &ShaderReflection) -> Vec<(u32, u32)> {
+        reflection
+            .bindings
+            .iter()
+            .map(|binding| (binding.space, binding.binding))
+            .collect()
+    }
+
+    #[test]
+    fn filters_globals_by_entry_point_interface() {
+        let module = two_entry_point_module(0x0001_0600);
+
+        let first = reflect_spirv(&module, ShaderKind::Fragment, "first").unwrap();
+        assert_eq!(binding_slots(&first), [(0, 0)]);
+        assert_eq!(first.inputs.len(), 1);
+
+        let second = reflect_spirv(&module, ShaderKind::Fragment, "second").unwrap();
+        assert_eq!(binding_slots(&second), [(0, 1)]);
+        assert!(second.inputs.is_empty());
+    }
+
+    #[test]
+    fn keeps_resources_outside_the_interface_before_spirv_1_4() {
+        let module = two_entry_point_module(0x0001_0300);
+
+        let second = reflect_spirv(&module, ShaderKind::Fragment, "second").unwrap();
+        assert_eq!(binding_slots(&second), [(0, 0), (0, 1)]);
+        assert!(second.inputs.is_empty());
+    }
+}
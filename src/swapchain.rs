@@ -0,0 +1,146 @@
+use crate::{Error, Format, Texture, WindowHandle};
+use std::sync::Arc;
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+pub enum Swapchain {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanSwapchain),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalSwapchain),
+}
+
+impl Swapchain {
+    pub fn acquire_next_image(&self) -> Result<Option<SwapchainImage>, Error> {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.acquire_next_image(),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.acquire_next_image(),
+        }
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.resize(width, height),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.resize(width, height),
+        }
+    }
+
+    pub fn format(&self) -> Format {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.format(),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.format(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.width(),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.width(),
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.height(),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.height(),
+        }
+    }
+
+    pub fn color_space(&self) -> ColorSpace {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.color_space(),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.color_space(),
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.present_mode(),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.present_mode(),
+        }
+    }
+
+    pub fn image_count(&self) -> u32 {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain.image_count(),
+
+            #[cfg(feature = "metal")]
+            Swapchain::Metal(metal_swapchain) => metal_swapchain.image_count(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_swapchain(&self) -> &VulkanSwapchain {
+        match self {
+            Swapchain::Vulkan(vulkan_swapchain) => vulkan_swapchain,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_swapchain(&self) -> &MetalSwapchain {
+        match self {
+            Swapchain::Metal(metal_swapchain) => metal_swapchain,
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub struct SwapchainDesc<'a> {
+    pub window_handle: WindowHandle<'a>,
+    pub width: u32,
+    pub height: u32,
+    pub format: Format,
+    pub color_space: ColorSpace,
+    pub present_mode: PresentMode,
+    pub image_count: u32,
+}
+
+pub struct SwapchainImage {
+    pub texture: Arc<Texture>,
+    pub width: u32,
+    pub height: u32,
+    pub resized: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PresentMode {
+    Fifo,
+    FifoRelaxed,
+    Mailbox,
+    Immediate,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    SrgbNonLinear,
+    Hdr10St2084,
+    ExtendedSrgbLinear,
+}
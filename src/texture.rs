@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+use std::ops::{BitOr, BitOrAssign};
+
+#[cfg(feature = "vulkan")]
+use crate::backend::vulkan::*;
+
+#[cfg(feature = "metal")]
+use crate::backend::metal::*;
+
+use crate::{Format, TextureSubresourceRange};
+
+pub enum Texture {
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanTexture),
+
+    #[cfg(feature = "metal")]
+    Metal(MetalTexture),
+}
+
+impl Texture {
+    pub fn desc(&self) -> &TextureDesc {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(vulkan_texture) => vulkan_texture.desc(),
+
+            #[cfg(feature = "metal")]
+            Texture::Metal(metal_texture) => metal_texture.desc(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    pub fn as_vulkan_texture(&self) -> &VulkanTexture {
+        match self {
+            Texture::Vulkan(vulkan_texture) => vulkan_texture,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    pub fn as_metal_texture(&self) -> &MetalTexture {
+        match self {
+            Texture::Metal(metal_texture) => metal_texture,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureDesc {
+    pub name: Cow<'static, str>,
+    pub dimension: TextureDimension,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub array_layers: u32,
+    pub mip_levels: u32,
+    pub sample_count: u32,
+    pub format: Format,
+    pub usage: TextureUsage,
+}
+
+impl TextureDesc {
+    pub fn full_subresource_range(&self) -> TextureSubresourceRange {
+        TextureSubresourceRange {
+            base_mip_level: 0,
+            mip_level_count: self.mip_levels,
+            base_array_layer: 0,
+            array_layer_count: self.array_layers,
+        }
+    }
+}
+
+impl Default for TextureDesc {
+    fn default() -> Self {
+        Self {
+            name: Cow::Borrowed(""),
+            dimension: TextureDimension::D2,
+            width: 1,
+            height: 1,
+            depth: 1,
+            array_layers: 1,
+            mip_levels: 1,
+            sample_count: 1,
+            format: Format::R8G8B8A8Unorm,
+            usage: TextureUsage::SAMPLED,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextureDimension {
+    D1,
+    D2,
+    D3,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureUsage(u32);
+
+impl TextureUsage {
+    pub const TRANSFER_SRC: Self = Self(1 << 0);
+    pub const TRANSFER_DST: Self = Self(1 << 1);
+    pub const SAMPLED: Self = Self(1 << 2);
+    pub const STORAGE: Self = Self(1 << 3);
+    pub const RENDER_TARGET: Self = Self(1 << 4);
+    pub const DEPTH_STENCIL: Self = Self(1 << 5);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl BitOr for TextureUsage {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for TextureUsage {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
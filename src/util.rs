@@ -1,4 +1,5 @@
 use crate::{Error, ShaderDesc, ShaderKind, ShaderSource};
+use std::borrow::Cow;
 
 fn get_target(stage: ShaderKind) -> &'static str {
     match stage {
@@ -14,7 +15,9 @@ pub fn compile_hlsl(desc: &ShaderDesc, spirv: bool) -> Result<Vec<u8>, Error> {
     let mut args = vec!["-HV 2021"];
     if spirv {
         args.push("-spirv");
-        args.push("-fspv-target-env=vulkan1.3")
+        args.push("-fspv-target-env=vulkan1.3");
+        args.extend(["-fvk-bind-resource-heap", "0", "3"]);
+        args.extend(["-fvk-bind-sampler-heap", "1", "3"]);
     }
 
     match &desc.source {
@@ -37,3 +40,35 @@ pub fn compile_hlsl(desc: &ShaderDesc, spirv: bool) -> Result<Vec<u8>, Error> {
         _ => Err(Error::InvalidShaderSource),
     }
 }
+
+pub(crate) fn spirv_code(desc: &ShaderDesc) -> Result<Cow<'_, [u32]>, Error> {
+    match &desc.source {
+        ShaderSource::SpirV(code) => Ok(Cow::Borrowed(code.as_ref())),
+        ShaderSource::Embedded { spirv, .. } if !spirv.is_empty() => {
+            Ok(Cow::Owned(spirv_words(spirv)?))
+        }
+        ShaderSource::Hlsl { .. } => Ok(Cow::Owned(spirv_words(&compile_hlsl(desc, true)?)?)),
+        _ => Err(Error::InvalidShaderSource),
+    }
+}
+
+#[cfg(feature = "metal")]
+pub(crate) fn dxil_code(desc: &ShaderDesc) -> Result<Cow<'_, [u8]>, Error> {
+    match &desc.source {
+        ShaderSource::Dxil(code) => Ok(Cow::Borrowed(code.as_ref())),
+        ShaderSource::Embedded { dxil, .. } if !dxil.is_empty() => Ok(Cow::Borrowed(dxil.as_ref())),
+        ShaderSource::Hlsl { .. } => Ok(Cow::Owned(compile_hlsl(desc, false)?)),
+        _ => Err(Error::InvalidShaderSource),
+    }
+}
+
+fn spirv_words(code: &[u8]) -> Result<Vec<u32>, Error> {
+    if code.len() % 4 != 0 {
+        return Err(Error::InvalidShaderSource);
+    }
+
+    Ok(code
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect())
+}